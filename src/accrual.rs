@@ -0,0 +1,106 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, TimeZone};
+use civil::DstPolicy;
+use month_calc::add_months_local;
+
+/// The rate at which leave (or anything else) accrues.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccrualRate {
+    /// An amount accrued per month of service.
+    PerMonth(f64),
+    /// An amount accrued per year of service, accruing evenly as one twelfth per month.
+    PerYear(f64),
+}
+
+impl AccrualRate {
+    fn monthly(&self) -> f64 {
+        match *self {
+            AccrualRate::PerMonth(amount) => amount,
+            AccrualRate::PerYear(amount) => amount / 12.0,
+        }
+    }
+}
+
+/// Returns the amount accrued between `start` and `end` at the given rate.
+///
+/// Each complete month accrues the full monthly amount.  Months are counted from `start` with
+/// `add_months_local`, so for a start on the 31st, a month is complete on the last day of
+/// shorter months, and a month ending in a DST gap is complete once the clocks have gone
+/// forward.
+/// The final partial month accrues in proportion to the time elapsed within it, so 15 days into
+/// a 30-day month accrues half the monthly amount.  Returns 0 if `end` is not after `start`.
+pub fn accrual<Tz: TimeZone, Tz2: TimeZone>(start: &DateTime<Tz>, end: &DateTime<Tz2>,
+                                            rate: AccrualRate) -> f64 {
+    let end = end.with_timezone(&start.timezone());
+    if end <= *start {
+        return 0.0;
+    }
+
+    // Estimate the number of complete months from the calendar fields, then correct it if the
+    // day of the month (or the time) hasn't been reached yet.
+    let shift = |months| {
+        add_months_local(start, months, DstPolicy::ShiftForward)
+            .expect("Value invalid: the resulting date is out of range")
+    };
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    while shift(months) > end {
+        months -= 1;
+    }
+
+    let anchor = shift(months);
+    let next_anchor = shift(months + 1);
+    let elapsed = end.signed_duration_since(anchor.clone()).num_seconds() as f64;
+    let month_length = next_anchor.signed_duration_since(anchor.clone()).num_seconds();
+
+    rate.monthly() * (months as f64 + elapsed / month_length as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_tz::Cet;
+
+    #[test]
+    fn test_accrual_whole_months() {
+        let start = DateTime::parse_from_rfc3339("2018-01-15T00:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2018-04-15T00:00:00Z").unwrap();
+        assert_eq!(accrual(&start, &end, AccrualRate::PerMonth(2.0)), 6.0);
+        assert_eq!(accrual(&start, &end, AccrualRate::PerYear(24.0)), 6.0);
+    }
+
+    #[test]
+    fn test_accrual_partial_month() {
+        // April 15th to May 15th is 30 days, so April 30th is halfway through.
+        let start = DateTime::parse_from_rfc3339("2018-01-15T00:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2018-04-30T00:00:00Z").unwrap();
+        assert_eq!(accrual(&start, &end, AccrualRate::PerMonth(2.0)), 7.0);
+    }
+
+    #[test]
+    fn test_accrual_day_not_reached() {
+        let start = DateTime::parse_from_rfc3339("2018-01-20T00:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2018-03-10T00:00:00Z").unwrap();
+        // One full month to February 20th, then 18 of the 28 days to March 20th.
+        let expected = 1.0 + 18.0 / 28.0;
+        assert!((accrual(&start, &end, AccrualRate::PerMonth(1.0)) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_accrual_dst_gap() {
+        // 2018-03-25 02:30 doesn't exist in CET, so the first month is complete at 03:30 CEST,
+        // and the second month (to 2018-04-25 02:30) is an hour short of 31 days.
+        let start = Cet.with_ymd_and_hms(2018, 2, 25, 2, 30, 0).unwrap();
+        let end = Cet.with_ymd_and_hms(2018, 4, 1, 0, 0, 0).unwrap();
+        let expected = 1.0 + (6.0 * 24.0 + 20.5) / (31.0 * 24.0 - 1.0);
+        assert!((accrual(&start, &end, AccrualRate::PerMonth(1.0)) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_accrual_end_before_start() {
+        let start = DateTime::parse_from_rfc3339("2018-03-15T00:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2018-01-15T00:00:00Z").unwrap();
+        assert_eq!(accrual(&start, &end, AccrualRate::PerMonth(2.0)), 0.0);
+        assert_eq!(accrual(&start, &start, AccrualRate::PerMonth(2.0)), 0.0);
+    }
+}
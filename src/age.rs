@@ -0,0 +1,111 @@
+extern crate chrono;
+
+use chrono::{Datelike, NaiveDate};
+use month_calc::is_leap_year;
+use year_calc::{LeapDayPolicy, YearCalculations};
+
+/// A person's (or anything else's) age, from their date of birth.  Birthdays are the same month
+/// and day every year, except for leaplings born on February 29th, whose birthday in years which
+/// aren't leap years is set by `leap_day`.  With `LeapDayPolicy::March1`, ages are the same as
+/// `YearCalculations::years_since` gives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Age {
+    pub birthdate: NaiveDate,
+    pub leap_day: LeapDayPolicy,
+}
+
+impl Age {
+    pub fn new(birthdate: NaiveDate, leap_day: LeapDayPolicy) -> Age {
+        Age { birthdate, leap_day }
+    }
+
+    /// Returns the birthday in `year`, or `None` if it is out of range.  (This is the date of
+    /// birth itself in the year of birth, and before then is the date it would have been.)
+    pub fn birthday_in(&self, year: i32) -> Option<NaiveDate> {
+        let (month, day) = (self.birthdate.month(), self.birthdate.day());
+        if month == 2 && day == 29 && !is_leap_year(year) {
+            match self.leap_day {
+                LeapDayPolicy::February28 => NaiveDate::from_ymd_opt(year, 2, 28),
+                LeapDayPolicy::March1 => NaiveDate::from_ymd_opt(year, 3, 1),
+            }
+        } else {
+            NaiveDate::from_ymd_opt(year, month, day)
+        }
+    }
+
+    /// Returns the age in whole years on `as_of`, or `None` if `as_of` is before the date of
+    /// birth.
+    pub fn age_at(&self, as_of: &NaiveDate) -> Option<u32> {
+        if *as_of < self.birthdate {
+            return None;
+        }
+        let years = YearCalculations::years_since(as_of, &self.birthdate) as u32;
+        // `years_since` moves February 29th to March 1st in years which aren't leap years, so a
+        // leapling's February 28th birthday hasn't been counted yet.
+        let moved_earlier = self.leap_day == LeapDayPolicy::February28
+            && (as_of.month(), as_of.day()) == (2, 28)
+            && self.birthday_in(as_of.year()) == Some(*as_of)
+            && self.birthdate.day() == 29;
+        Some(if moved_earlier { years + 1 } else { years })
+    }
+
+    /// Returns true if `as_of` is a birthday (not counting the date of birth itself).
+    pub fn is_birthday(&self, as_of: &NaiveDate) -> bool {
+        *as_of > self.birthdate && self.birthday_in(as_of.year()) == Some(*as_of)
+    }
+
+    /// Returns the first birthday after `as_of` (or after the date of birth, if `as_of` is before
+    /// it), or `None` if it is out of range.
+    pub fn next_birthday(&self, as_of: &NaiveDate) -> Option<NaiveDate> {
+        let after = (*as_of).max(self.birthdate);
+        match self.birthday_in(after.year()) {
+            Some(birthday) if birthday > after => Some(birthday),
+            _ => self.birthday_in(after.year().checked_add(1)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_age_at() {
+        let age = Age::new(date(1990, 3, 15), LeapDayPolicy::February28);
+        assert_eq!(age.age_at(&date(2018, 3, 14)), Some(27));
+        assert_eq!(age.age_at(&date(2018, 3, 15)), Some(28));
+        assert_eq!(age.age_at(&date(1990, 3, 15)), Some(0));
+        assert_eq!(age.age_at(&date(1990, 3, 14)), None);
+        assert!(age.is_birthday(&date(2018, 3, 15)));
+        assert!(!age.is_birthday(&date(1990, 3, 15)));
+        assert_eq!(age.next_birthday(&date(2018, 3, 15)), Some(date(2019, 3, 15)));
+        assert_eq!(age.next_birthday(&date(2018, 1, 1)), Some(date(2018, 3, 15)));
+        assert_eq!(age.next_birthday(&date(1980, 1, 1)), Some(date(1991, 3, 15)));
+    }
+
+    #[test]
+    fn test_leapling() {
+        let feb28 = Age::new(date(2016, 2, 29), LeapDayPolicy::February28);
+        let mar1 = Age::new(date(2016, 2, 29), LeapDayPolicy::March1);
+        assert_eq!(feb28.age_at(&date(2017, 2, 28)), Some(1));
+        assert_eq!(mar1.age_at(&date(2017, 2, 28)), Some(0));
+        assert_eq!(mar1.age_at(&date(2017, 3, 1)), Some(1));
+        assert!(feb28.is_birthday(&date(2017, 2, 28)));
+        assert!(mar1.is_birthday(&date(2017, 3, 1)));
+        assert!(!feb28.is_birthday(&date(2020, 2, 28)));
+        assert!(feb28.is_birthday(&date(2020, 2, 29)));
+        assert_eq!(feb28.next_birthday(&date(2019, 3, 1)), Some(date(2020, 2, 29)));
+        assert_eq!(mar1.next_birthday(&date(2020, 3, 1)), Some(date(2021, 3, 1)));
+        // March 1st birthdays match years_since, and February 28th birthdays are a day earlier.
+        for as_of in date(2016, 2, 29).iter_days().take(2000) {
+            let years = YearCalculations::years_since(&as_of, &date(2016, 2, 29)) as u32;
+            assert_eq!(mar1.age_at(&as_of), Some(years));
+            let early = as_of.month() == 2 && as_of.day() == 28 && !is_leap_year(as_of.year());
+            assert_eq!(feb28.age_at(&as_of), Some(if early { years + 1 } else { years }));
+        }
+    }
+}
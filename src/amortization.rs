@@ -0,0 +1,218 @@
+extern crate chrono;
+
+use business_days::{roll, RollConvention, Workweek};
+use chrono::{Datelike, NaiveDate};
+use daycount::DayCount;
+use holidays::HolidayCalendar;
+use month_calc::OverflowPolicy;
+use period::Period;
+
+/// A payment date of a loan, as returned by `amortization_dates`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaymentDate {
+    /// The payment date, moved to a working day.
+    pub date: NaiveDate,
+    /// The fraction of a year from the previous payment (or the start) to this one.
+    pub year_fraction: f64,
+}
+
+fn is_month_end(date: NaiveDate) -> bool {
+    date.succ_opt().is_some_and(|next| next.month() != date.month())
+}
+
+/// Returns the `n_periods` payment dates of a loan starting on `start` and repaid every
+/// `frequency`, along with the year fraction of each period under `day_count`.
+///
+/// The unadjusted dates are all measured from `start` (the nth payment is `start` plus n times
+/// `frequency`), so a short month doesn't shift the dates after it, and a loan starting on the
+/// last day of a month is repaid on the last day of each month.  Each date is then moved to a
+/// working day with `convention`, and the year fractions are measured between the moved dates.
+/// Returns `None` if any date is out of range.
+pub fn amortization_dates<C>(start: NaiveDate, n_periods: u32, frequency: Period,
+                             convention: RollConvention, calendar: &C, workweek: &Workweek,
+                             day_count: DayCount) -> Option<Vec<PaymentDate>>
+    where C: HolidayCalendar + ?Sized {
+    let month_end = is_month_end(start);
+    let mut previous = start;
+    (1..=n_periods as i32)
+        .map(|n| {
+            let mut date = scaled(frequency, n)?
+                .add_to_date(start, OverflowPolicy::ClampToLastDay)?;
+            if month_end && frequency.days == 0 {
+                while !is_month_end(date) {
+                    date = date.succ_opt()?;
+                }
+            }
+            let date = roll(date, convention, calendar, workweek)?;
+            let year_fraction = day_count.year_fraction(previous, date);
+            previous = date;
+            Some(PaymentDate { date, year_fraction })
+        })
+        .collect()
+}
+
+/// Where the irregular period of a schedule goes when the dates don't divide evenly into whole
+/// periods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StubPolicy {
+    /// A short first period, with regular periods counted back from the termination date.
+    ShortInitial,
+    /// A long first period, made by merging the short first period into the one after it.
+    LongInitial,
+    /// A short last period, with regular periods counted on from the effective date.
+    ShortFinal,
+    /// A long last period, made by merging the short last period into the one before it.
+    LongFinal,
+}
+
+fn scaled(period: Period, n: i32) -> Option<Period> {
+    Some(Period {
+        years: period.years.checked_mul(n)?,
+        months: period.months.checked_mul(n)?,
+        days: period.days.checked_mul(n)?,
+    })
+}
+
+/// Returns the accrual periods from `effective` to `termination` as `(start, end)` pairs, each
+/// period ending where the next begins.  Regular periods are `frequency` long, measured from the
+/// effective date (for final stubs) or the termination date (for initial stubs), clamping to the
+/// end of the month where needed.  If the dates don't divide evenly, `stub` decides where the
+/// irregular period goes and whether it is short or long.
+///
+/// Returns an empty list if `termination` isn't after `effective`, and `None` if `frequency`
+/// doesn't move the dates forwards or a date is out of range.
+pub fn accrual_periods(effective: NaiveDate, termination: NaiveDate, frequency: Period,
+                       stub: StubPolicy) -> Option<Vec<(NaiveDate, NaiveDate)>> {
+    if termination <= effective {
+        return Some(Vec::new());
+    }
+    let backwards = stub == StubPolicy::ShortInitial || stub == StubPolicy::LongInitial;
+    let (anchor, end, sign) = if backwards {
+        (termination, effective, -1)
+    } else {
+        (effective, termination, 1)
+    };
+
+    let mut dates = vec![anchor];
+    let mut has_stub = true;
+    for n in 1.. {
+        let date = scaled(frequency, sign * n)?
+            .add_to_date(anchor, OverflowPolicy::ClampToLastDay)?;
+        let last = *dates.last()?;
+        if (backwards && date >= last) || (!backwards && date <= last) {
+            return None;
+        }
+        if (backwards && date <= end) || (!backwards && date >= end) {
+            has_stub = date != end;
+            break;
+        }
+        dates.push(date);
+    }
+    dates.push(end);
+    if backwards {
+        dates.reverse();
+    }
+
+    if has_stub && dates.len() > 2 {
+        match stub {
+            StubPolicy::LongInitial => {
+                dates.remove(1);
+            }
+            StubPolicy::LongFinal => {
+                dates.remove(dates.len() - 2);
+            }
+            StubPolicy::ShortInitial | StubPolicy::ShortFinal => {}
+        }
+    }
+    Some(dates.windows(2).map(|pair| (pair[0], pair[1])).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holidays::HolidayList;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn dates(payments: &[PaymentDate]) -> Vec<NaiveDate> {
+        payments.iter().map(|payment| payment.date).collect()
+    }
+
+    #[test]
+    fn test_amortization_dates_month_end() {
+        let payments = amortization_dates(date(2018, 1, 31), 4, Period::months(1),
+                                          RollConvention::Unadjusted, &HolidayList::new(),
+                                          &Workweek::default(), DayCount::Thirty360).unwrap();
+        let expected = vec![date(2018, 2, 28), date(2018, 3, 31), date(2018, 4, 30),
+                            date(2018, 5, 31)];
+        assert_eq!(dates(&payments), expected);
+    }
+
+    #[test]
+    fn test_amortization_dates_no_drift() {
+        // Starting on the 30th, February is clamped but later months return to the 30th.
+        let payments = amortization_dates(date(2018, 1, 30), 3, Period::months(1),
+                                          RollConvention::Unadjusted, &HolidayList::new(),
+                                          &Workweek::default(), DayCount::Thirty360).unwrap();
+        assert_eq!(dates(&payments), vec![date(2018, 2, 28), date(2018, 3, 30), date(2018, 4, 30)]);
+    }
+
+    #[test]
+    fn test_amortization_dates_rolled() {
+        // Quarterly from March 31st, 2018: June 30th and September 30th are weekends.
+        let payments = amortization_dates(date(2018, 3, 31), 3, Period::months(3),
+                                          RollConvention::ModifiedFollowing, &HolidayList::new(),
+                                          &Workweek::default(), DayCount::Actual365Fixed).unwrap();
+        let expected = vec![date(2018, 6, 29), date(2018, 9, 28), date(2018, 12, 31)];
+        assert_eq!(dates(&payments), expected);
+        assert_eq!(payments[0].year_fraction, 90.0 / 365.0);
+        assert_eq!(payments[1].year_fraction, 91.0 / 365.0);
+        assert_eq!(payments[2].year_fraction, 94.0 / 365.0);
+    }
+
+    #[test]
+    fn test_accrual_periods_even() {
+        let periods = accrual_periods(date(2018, 1, 15), date(2018, 7, 15), Period::months(3),
+                                      StubPolicy::ShortFinal).unwrap();
+        assert_eq!(periods, vec![(date(2018, 1, 15), date(2018, 4, 15)),
+                                 (date(2018, 4, 15), date(2018, 7, 15))]);
+        let periods = accrual_periods(date(2018, 1, 15), date(2018, 7, 15), Period::months(3),
+                                      StubPolicy::LongInitial).unwrap();
+        assert_eq!(periods, vec![(date(2018, 1, 15), date(2018, 4, 15)),
+                                 (date(2018, 4, 15), date(2018, 7, 15))]);
+    }
+
+    #[test]
+    fn test_accrual_periods_stubs() {
+        let (effective, termination) = (date(2018, 1, 15), date(2018, 9, 1));
+        let quarterly = Period::months(3);
+        let periods = accrual_periods(effective, termination, quarterly, StubPolicy::ShortFinal);
+        assert_eq!(periods.unwrap(), vec![(date(2018, 1, 15), date(2018, 4, 15)),
+                                          (date(2018, 4, 15), date(2018, 7, 15)),
+                                          (date(2018, 7, 15), date(2018, 9, 1))]);
+        let periods = accrual_periods(effective, termination, quarterly, StubPolicy::LongFinal);
+        assert_eq!(periods.unwrap(), vec![(date(2018, 1, 15), date(2018, 4, 15)),
+                                          (date(2018, 4, 15), date(2018, 9, 1))]);
+        let periods = accrual_periods(effective, termination, quarterly, StubPolicy::ShortInitial);
+        assert_eq!(periods.unwrap(), vec![(date(2018, 1, 15), date(2018, 3, 1)),
+                                          (date(2018, 3, 1), date(2018, 6, 1)),
+                                          (date(2018, 6, 1), date(2018, 9, 1))]);
+        let periods = accrual_periods(effective, termination, quarterly, StubPolicy::LongInitial);
+        assert_eq!(periods.unwrap(), vec![(date(2018, 1, 15), date(2018, 6, 1)),
+                                          (date(2018, 6, 1), date(2018, 9, 1))]);
+    }
+
+    #[test]
+    fn test_accrual_periods_degenerate() {
+        // A single short period can't be merged into anything.
+        let periods = accrual_periods(date(2018, 1, 15), date(2018, 2, 1), Period::months(3),
+                                      StubPolicy::LongFinal);
+        assert_eq!(periods, Some(vec![(date(2018, 1, 15), date(2018, 2, 1))]));
+        assert_eq!(accrual_periods(date(2018, 2, 1), date(2018, 1, 15), Period::months(3),
+                                   StubPolicy::ShortFinal), Some(Vec::new()));
+        assert_eq!(accrual_periods(date(2018, 1, 15), date(2018, 9, 1), Period::default(),
+                                   StubPolicy::ShortFinal), None);
+    }
+}
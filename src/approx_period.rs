@@ -0,0 +1,133 @@
+extern crate chrono;
+
+use chrono::Duration;
+use std::fmt;
+
+/// Length of the average Gregorian year (365.2425 days), in seconds.
+const SECONDS_PER_YEAR: i64 = 31_556_952;
+/// Length of an "average" month (30.44 days), in seconds.
+const SECONDS_PER_MONTH: i64 = 2_630_016;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// An approximate breakdown of a `chrono::Duration` into years, months and days.
+///
+/// A Duration is a fixed number of seconds, while months and years vary in length, so there is no
+/// exact conversion between the two.  This type assumes every year is 365.2425 days long and every
+/// month is 30.44 days long, and drops any remainder shorter than a day.  It is meant for display
+/// (e.g. "about 1 year, 2 months ago") and should never be used for calendar arithmetic; use
+/// `MonthCalculations` for that instead.
+///
+/// All fields share the sign of the duration they were built from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ApproxPeriod {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+}
+
+impl ApproxPeriod {
+    /// Estimate the years, months and days in `duration`.
+    pub fn approximate_from(duration: Duration) -> ApproxPeriod {
+        let total = duration.num_seconds();
+        let sign = total.signum();
+        let mut remaining = total.abs();
+
+        let years = remaining / SECONDS_PER_YEAR;
+        remaining %= SECONDS_PER_YEAR;
+        let months = remaining / SECONDS_PER_MONTH;
+        remaining %= SECONDS_PER_MONTH;
+        let days = remaining / SECONDS_PER_DAY;
+
+        ApproxPeriod { years: sign * years, months: sign * months, days: sign * days }
+    }
+
+    /// Convert back to a Duration using the same average year and month lengths as
+    /// `approximate_from`.  Returns `None` if the result is too large for a Duration.
+    pub fn approx_duration(&self) -> Option<Duration> {
+        let seconds = self.years.checked_mul(SECONDS_PER_YEAR)?
+            .checked_add(self.months.checked_mul(SECONDS_PER_MONTH)?)?
+            .checked_add(self.days.checked_mul(SECONDS_PER_DAY)?)?;
+        Duration::try_seconds(seconds)
+    }
+}
+
+impl fmt::Display for ApproxPeriod {
+    /// Writes the non-zero fields as e.g. "1 year, 2 months, 3 days" ("0 days" if all are zero).
+    /// Negative periods are written with a leading "-", with the fields in parentheses if there
+    /// is more than one, e.g. "-(2 years, 1 day)" or "-3 months".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let negative = self.years < 0 || self.months < 0 || self.days < 0;
+        let parts: Vec<String> = [(self.years, "year"), (self.months, "month"), (self.days, "day")]
+            .iter()
+            .filter(|&&(n, _)| n != 0)
+            .map(|&(n, unit)| {
+                format!("{} {}{}", n.abs(), unit, if n.abs() == 1 { "" } else { "s" })
+            })
+            .collect();
+
+        if parts.is_empty() {
+            return write!(f, "0 days");
+        }
+        match (negative, parts.len()) {
+            (false, _) => write!(f, "{}", parts.join(", ")),
+            (true, 1) => write!(f, "-{}", parts[0]),
+            (true, _) => write!(f, "-({})", parts.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approximate_from_days() {
+        let period = ApproxPeriod::approximate_from(Duration::days(10));
+        assert_eq!(period, ApproxPeriod { years: 0, months: 0, days: 10 });
+    }
+
+    #[test]
+    fn test_approximate_from_months() {
+        // 100 days = 3 average months (91.32 days) and 8.68 days.
+        let period = ApproxPeriod::approximate_from(Duration::days(100));
+        assert_eq!(period, ApproxPeriod { years: 0, months: 3, days: 8 });
+    }
+
+    #[test]
+    fn test_approximate_from_years() {
+        // 800 days = 2 average years (730.485 days), 2 average months (60.88 days) and 8.635 days.
+        let period = ApproxPeriod::approximate_from(Duration::days(800));
+        assert_eq!(period, ApproxPeriod { years: 2, months: 2, days: 8 });
+    }
+
+    #[test]
+    fn test_approximate_from_negative() {
+        let period = ApproxPeriod::approximate_from(Duration::days(-800));
+        assert_eq!(period, ApproxPeriod { years: -2, months: -2, days: -8 });
+    }
+
+    #[test]
+    fn test_approx_duration_round_trip() {
+        let period = ApproxPeriod { years: 3, months: 7, days: 12 };
+        assert_eq!(ApproxPeriod::approximate_from(period.approx_duration().unwrap()), period);
+    }
+
+    #[test]
+    fn test_approx_duration_overflow() {
+        assert_eq!(ApproxPeriod { years: i64::MAX, months: 0, days: 0 }.approx_duration(), None);
+        // Within i64 seconds, but beyond the range of a Duration.
+        let period = ApproxPeriod { years: 0, months: 0, days: i64::MAX / 86_400 };
+        assert_eq!(period.approx_duration(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ApproxPeriod { years: 1, months: 2, days: 3 }.to_string(),
+                   "1 year, 2 months, 3 days");
+        assert_eq!(ApproxPeriod { years: 0, months: 1, days: 0 }.to_string(), "1 month");
+        assert_eq!(ApproxPeriod { years: -2, months: 0, days: -1 }.to_string(),
+                   "-(2 years, 1 day)");
+        assert_eq!(ApproxPeriod { years: 0, months: -3, days: 0 }.to_string(), "-3 months");
+        assert_eq!(ApproxPeriod::default().to_string(), "0 days");
+    }
+}
@@ -0,0 +1,160 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Days, NaiveDateTime, TimeZone, Weekday};
+use civil::{resolve_local, DstPolicy};
+use std::ops::Range;
+use time_of_day::TimeOfDayRange;
+
+/// A weekly schedule of bands, such as the peak and off-peak periods of an energy tariff.  Each
+/// band covers a time range starting on a given weekday (running past midnight into the next day
+/// if it crosses midnight, as with `TimeOfDayRange`); times not covered by any band belong to the
+/// default band.  Where bands overlap, the one added first wins.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BandSchedule<Tz: TimeZone, B> {
+    bands: Vec<(Weekday, TimeOfDayRange, B)>,
+    default: B,
+    tz: Tz,
+}
+
+impl<Tz: TimeZone, B> BandSchedule<Tz, B> {
+    /// A schedule where every time belongs to `default`, with times of day in `tz`.
+    pub fn new(default: B, tz: Tz) -> BandSchedule<Tz, B> {
+        BandSchedule { bands: Vec::new(), default, tz }
+    }
+
+    /// Add a band covering `range` on `weekday`.
+    pub fn add(&mut self, weekday: Weekday, range: TimeOfDayRange,
+               band: B) -> &mut BandSchedule<Tz, B> {
+        self.bands.push((weekday, range, band));
+        self
+    }
+
+    fn covers(weekday: Weekday, range: &TimeOfDayRange, local: &NaiveDateTime) -> bool {
+        let time = local.time();
+        if local.weekday() == weekday && time >= range.start
+            && (range.crosses_midnight() || time < range.end) {
+            return true;
+        }
+        range.crosses_midnight() && local.weekday().pred() == weekday && time < range.end
+    }
+
+    /// Returns the band in effect at `dt`.
+    pub fn band_at<Tz2: TimeZone>(&self, dt: &DateTime<Tz2>) -> &B {
+        let local = dt.with_timezone(&self.tz).naive_local();
+        self.bands.iter()
+            .find(|&(weekday, range, _)| Self::covers(*weekday, range, &local))
+            .map_or(&self.default, |(_, _, band)| band)
+    }
+
+    /// Split `interval` where the band changes, returning each part with its band, in order.
+    /// Band boundaries which fall in a DST gap are moved forward by the length of the gap.
+    pub fn split_interval_by_bands<Tz2: TimeZone>(&self, interval: &Range<DateTime<Tz2>>)
+                                                  -> Vec<(Range<DateTime<Tz>>, &B)>
+        where B: PartialEq {
+        let start = interval.start.with_timezone(&self.tz);
+        let end = interval.end.with_timezone(&self.tz);
+        if start >= end {
+            return Vec::new();
+        }
+
+        // Every band's start and end on every day the interval touches, from the day before (for
+        // bands running past midnight).
+        let mut boundaries = Vec::new();
+        let mut date = start.date_naive().pred_opt().unwrap_or(start.date_naive());
+        while date <= end.date_naive() {
+            for &(weekday, range, _) in &self.bands {
+                if date.weekday() != weekday {
+                    continue;
+                }
+                let end_date = if range.crosses_midnight() { date.succ_opt() } else { Some(date) };
+                let locals = [Some(date.and_time(range.start)),
+                              end_date.map(|end_date| end_date.and_time(range.end))];
+                boundaries.extend(locals.iter().flatten()
+                    .filter_map(|local| resolve_local(&self.tz, local, DstPolicy::ShiftForward))
+                    .filter(|boundary| *boundary > start && *boundary < end));
+            }
+            date = match date.checked_add_days(Days::new(1)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        boundaries.sort();
+        boundaries.dedup();
+        boundaries.push(end);
+
+        let mut parts: Vec<(Range<DateTime<Tz>>, &B)> = Vec::new();
+        let mut from = start;
+        for to in boundaries {
+            let band = self.band_at(&from);
+            match parts.last_mut() {
+                Some(last) if last.1 == band => last.0.end = to.clone(),
+                _ => parts.push((from..to.clone(), band)),
+            }
+            from = to;
+        }
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Utc};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn range(start: u32, end: u32) -> TimeOfDayRange {
+        TimeOfDayRange::new(NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+                            NaiveTime::from_hms_opt(end, 0, 0).unwrap())
+    }
+
+    fn tariff() -> BandSchedule<Utc, &'static str> {
+        let mut tariff = BandSchedule::new("standard", Utc);
+        for weekday in &[Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] {
+            tariff.add(*weekday, range(17, 20), "peak");
+        }
+        for weekday in &[Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri,
+                         Weekday::Sat, Weekday::Sun] {
+            tariff.add(*weekday, range(23, 7), "off-peak");
+        }
+        tariff
+    }
+
+    #[test]
+    fn test_band_at() {
+        let tariff = tariff();
+        // Thursday March 15th, 2018.
+        assert_eq!(*tariff.band_at(&at("2018-03-15T12:00:00Z")), "standard");
+        assert_eq!(*tariff.band_at(&at("2018-03-15T17:00:00Z")), "peak");
+        assert_eq!(*tariff.band_at(&at("2018-03-15T20:00:00Z")), "standard");
+        assert_eq!(*tariff.band_at(&at("2018-03-15T23:30:00Z")), "off-peak");
+        assert_eq!(*tariff.band_at(&at("2018-03-16T06:59:00Z")), "off-peak");
+        // No peak at the weekend.
+        assert_eq!(*tariff.band_at(&at("2018-03-17T18:00:00Z")), "standard");
+    }
+
+    #[test]
+    fn test_split_interval_by_bands() {
+        let tariff = tariff();
+        let parts = tariff.split_interval_by_bands(&(at("2018-03-15T16:00:00Z")
+            ..at("2018-03-16T08:00:00Z")));
+        assert_eq!(parts, vec![
+            (at("2018-03-15T16:00:00Z")..at("2018-03-15T17:00:00Z"), &"standard"),
+            (at("2018-03-15T17:00:00Z")..at("2018-03-15T20:00:00Z"), &"peak"),
+            (at("2018-03-15T20:00:00Z")..at("2018-03-15T23:00:00Z"), &"standard"),
+            (at("2018-03-15T23:00:00Z")..at("2018-03-16T07:00:00Z"), &"off-peak"),
+            (at("2018-03-16T07:00:00Z")..at("2018-03-16T08:00:00Z"), &"standard"),
+        ]);
+    }
+
+    #[test]
+    fn test_split_interval_within_one_band() {
+        let tariff = tariff();
+        let interval = at("2018-03-16T01:00:00Z")..at("2018-03-16T02:00:00Z");
+        assert_eq!(tariff.split_interval_by_bands(&interval),
+                   vec![(interval.clone(), &"off-peak")]);
+        assert!(tariff.split_interval_by_bands(&(interval.end..interval.start)).is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+extern crate chrono;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use holidays::HolidayCalendar;
+use std::ops::RangeInclusive;
+
+/// The days of the week which are normally worked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Workweek {
+    days: [bool; 7],
+}
+
+impl Workweek {
+    /// A workweek made up of the given weekdays.
+    pub fn new(days: &[Weekday]) -> Workweek {
+        let mut workweek = Workweek { days: [false; 7] };
+        for day in days {
+            workweek.days[day.num_days_from_monday() as usize] = true;
+        }
+        workweek
+    }
+
+    /// The common Monday to Friday workweek.
+    pub fn monday_to_friday() -> Workweek {
+        Workweek::new(&[Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri])
+    }
+
+    /// Returns true if `weekday` is normally worked.
+    pub fn is_workday(&self, weekday: Weekday) -> bool {
+        self.days[weekday.num_days_from_monday() as usize]
+    }
+}
+
+impl Default for Workweek {
+    fn default() -> Workweek {
+        Workweek::monday_to_friday()
+    }
+}
+
+/// Returns true if `date` is a working day: a workday of `workweek` which is not a holiday (in
+/// the sense of `HolidayCalendar::is_holiday`) in `calendar`.
+pub fn is_working_day<C: HolidayCalendar + ?Sized>(date: NaiveDate, calendar: &C,
+                                                   workweek: &Workweek) -> bool {
+    workweek.is_workday(date.weekday()) && !calendar.is_holiday(date)
+}
+
+/// How a date which falls on a non-working day is moved to a working day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RollConvention {
+    /// Leave the date as it is.
+    Unadjusted,
+    /// Move to the next working day.
+    Following,
+    /// Move to the next working day, unless that is in the next month, in which case move to the
+    /// previous working day instead.
+    ModifiedFollowing,
+    /// Move to the previous working day.
+    Preceding,
+    /// Move to the previous working day, unless that is in the previous month, in which case move
+    /// to the next working day instead.
+    ModifiedPreceding,
+}
+
+fn step_to_working_day<C>(date: NaiveDate, forwards: bool, calendar: &C,
+                          workweek: &Workweek) -> Option<NaiveDate>
+    where C: HolidayCalendar + ?Sized {
+    let mut date = date;
+    while !is_working_day(date, calendar, workweek) {
+        date = if forwards { date.succ_opt()? } else { date.pred_opt()? };
+    }
+    Some(date)
+}
+
+/// Moves `date` to a working day according to `convention`.  Working days are returned
+/// unchanged.  Returns `None` if `workweek` has no workdays, or no working day is found before
+/// the end of the representable range.
+pub fn roll<C: HolidayCalendar + ?Sized>(date: NaiveDate, convention: RollConvention,
+                                         calendar: &C, workweek: &Workweek) -> Option<NaiveDate> {
+    if !workweek.days.iter().any(|&day| day) {
+        return None;
+    }
+    let same_month = |d: &NaiveDate| d.month() == date.month();
+    match convention {
+        RollConvention::Unadjusted => Some(date),
+        RollConvention::Following => step_to_working_day(date, true, calendar, workweek),
+        RollConvention::Preceding => step_to_working_day(date, false, calendar, workweek),
+        RollConvention::ModifiedFollowing => step_to_working_day(date, true, calendar, workweek)
+            .filter(same_month)
+            .or_else(|| step_to_working_day(date, false, calendar, workweek)),
+        RollConvention::ModifiedPreceding => step_to_working_day(date, false, calendar, workweek)
+            .filter(same_month)
+            .or_else(|| step_to_working_day(date, true, calendar, workweek)),
+    }
+}
+
+/// Returns the bridge days of `year`: single working days squeezed between two non-working days,
+/// at least one of which is a holiday (e.g. the Friday after a Thursday holiday).  Taking a bridge
+/// day off joins the holiday and the weekend into one long break.
+///
+/// Neighbouring days in the previous and next years are taken into account, so December 31st
+/// and January 1st can be bridge days.  There are none if `workweek` has no workdays.
+pub fn bridge_days<C: HolidayCalendar + ?Sized>(year: i32, calendar: &C,
+                                                workweek: &Workweek) -> Vec<NaiveDate> {
+    if !workweek.days.iter().any(|&day| day) {
+        return Vec::new();
+    }
+    let first = match NaiveDate::from_ymd_opt(year, 1, 1) {
+        Some(first) => first,
+        None => return Vec::new(),
+    };
+    first.iter_days()
+        .take_while(|date| date.year() == year)
+        .filter(|&date| {
+            let (before, after) = match (date.pred_opt(), date.succ_opt()) {
+                (Some(before), Some(after)) => (before, after),
+                _ => return false,
+            };
+            is_working_day(date, calendar, workweek)
+                && !is_working_day(before, calendar, workweek)
+                && !is_working_day(after, calendar, workweek)
+                && (calendar.is_holiday(before) || calendar.is_holiday(after))
+        })
+        .collect()
+}
+
+/// Returns every run of at least `min_days` consecutive non-working days (weekends and holidays)
+/// which includes at least one day of `year`, as inclusive date ranges in order.  Runs that
+/// cross into the previous or next year are returned in full.
+///
+/// With a Monday to Friday workweek, `min_days` of 3 finds the long weekends, while 2 also
+/// includes every ordinary weekend.  If `workweek` has no workdays, every day is off and there
+/// is no run to return.
+pub fn long_weekends<C>(year: i32, calendar: &C, workweek: &Workweek,
+                        min_days: usize) -> Vec<RangeInclusive<NaiveDate>>
+    where C: HolidayCalendar + ?Sized {
+    if !workweek.days.iter().any(|&day| day) {
+        return Vec::new();
+    }
+    let (first, last) = match (NaiveDate::from_ymd_opt(year, 1, 1),
+                               NaiveDate::from_ymd_opt(year, 12, 31)) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec::new(),
+    };
+    let is_off = |date: &NaiveDate| !is_working_day(*date, calendar, workweek);
+
+    // Extend the search backwards so a run starting in the previous year is found in full.  The
+    // search forwards is naturally extended by finishing the last run.
+    let mut start = first;
+    while is_off(&start) {
+        match start.pred_opt().filter(&is_off) {
+            Some(before) => start = before,
+            None => break,
+        }
+    }
+
+    let mut runs = Vec::new();
+    let mut run_start: Option<NaiveDate> = None;
+    let mut date = start;
+    loop {
+        if is_off(&date) {
+            run_start = run_start.or(Some(date));
+        } else {
+            if let Some(run_first) = run_start.take() {
+                let run_last = date.pred_opt().unwrap_or(date);
+                if (run_last - run_first).num_days() + 1 >= min_days as i64 {
+                    runs.push(run_first..=run_last);
+                }
+            }
+            if date > last {
+                break;
+            }
+        }
+        date = match date.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    runs
+}
+
+/// How a day is classified in a `YearOverview`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DayKind {
+    /// A working day (which may still be an observance).
+    Working,
+    /// A day the workweek doesn't include, which is not a holiday.
+    Weekend,
+    /// A non-working holiday, whether or not it falls on a workday.
+    Holiday,
+}
+
+/// A classified day in a `YearOverview`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OverviewDay {
+    pub date: NaiveDate,
+    pub kind: DayKind,
+    /// The name of the holiday or observance falling on this day, if any.
+    pub holiday_name: Option<String>,
+}
+
+/// Every day of a year, classified by `year_overview`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct YearOverview {
+    pub year: i32,
+    /// The days of the year, in order.
+    pub days: Vec<OverviewDay>,
+}
+
+impl YearOverview {
+    /// Returns the number of days of the given kind.
+    pub fn count(&self, kind: DayKind) -> usize {
+        self.days.iter().filter(|day| day.kind == kind).count()
+    }
+
+    /// Returns the days falling in `month` (1 to 12).
+    pub fn month(&self, month: u32) -> &[OverviewDay] {
+        let start = self.days.iter().position(|day| day.date.month() == month);
+        let start = match start {
+            Some(start) => start,
+            None => return &[],
+        };
+        let len = self.days[start..].iter().take_while(|day| day.date.month() == month).count();
+        &self.days[start..start + len]
+    }
+}
+
+/// Classify every day of `year` as a working day, weekend or holiday, with the names of any
+/// holidays and observances, e.g. for display as a year planner or export to a spreadsheet.  A
+/// holiday falling on a weekend is classified as a holiday.  The overview has no days if the
+/// year is out of range.
+pub fn year_overview<C: HolidayCalendar + ?Sized>(year: i32, calendar: &C,
+                                                  workweek: &Workweek) -> YearOverview {
+    let days = NaiveDate::from_ymd_opt(year, 1, 1).map(|first| {
+        first.iter_days()
+            .take_while(|date| date.year() == year)
+            .map(|date| {
+                let kind = if calendar.is_holiday(date) {
+                    DayKind::Holiday
+                } else if workweek.is_workday(date.weekday()) {
+                    DayKind::Working
+                } else {
+                    DayKind::Weekend
+                };
+                let holiday_name = calendar.holiday_name(date).map(String::from);
+                OverviewDay { date, kind, holiday_name }
+            })
+            .collect()
+    });
+    YearOverview { year, days: days.unwrap_or_default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holidays::{Holiday, HolidayCategory, HolidayList};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar() -> HolidayList {
+        let mut calendar = HolidayList::new();
+        calendar
+            // A Thursday, making Friday May 11th a bridge day.
+            .add(date(2018, 5, 10), Holiday::new("Ascension Day", HolidayCategory::Public))
+            // A Tuesday, making Monday April 30th a bridge day.
+            .add(date(2018, 5, 1), Holiday::new("Labour Day", HolidayCategory::Public))
+            // A Wednesday, so no bridge day.
+            .add(date(2018, 8, 15), Holiday::new("Assumption Day", HolidayCategory::Public))
+            // An observance on a Thursday doesn't make a bridge day.
+            .add(date(2018, 11, 1), Holiday::new("All Saints' Day", HolidayCategory::Observance));
+        calendar
+    }
+
+    #[test]
+    fn test_workweek() {
+        let workweek = Workweek::monday_to_friday();
+        assert!(workweek.is_workday(Weekday::Mon));
+        assert!(workweek.is_workday(Weekday::Fri));
+        assert!(!workweek.is_workday(Weekday::Sat));
+        assert!(!workweek.is_workday(Weekday::Sun));
+
+        let workweek = Workweek::new(&[Weekday::Sun, Weekday::Mon, Weekday::Tue]);
+        assert!(workweek.is_workday(Weekday::Sun));
+        assert!(!workweek.is_workday(Weekday::Fri));
+    }
+
+    #[test]
+    fn test_is_working_day() {
+        let calendar = calendar();
+        let workweek = Workweek::default();
+        assert!(is_working_day(date(2018, 5, 9), &calendar, &workweek));
+        assert!(!is_working_day(date(2018, 5, 10), &calendar, &workweek));
+        assert!(!is_working_day(date(2018, 5, 12), &calendar, &workweek));
+        assert!(is_working_day(date(2018, 11, 1), &calendar, &workweek));
+    }
+
+    #[test]
+    fn test_bridge_days() {
+        let days = bridge_days(2018, &calendar(), &Workweek::default());
+        assert_eq!(days, vec![date(2018, 4, 30), date(2018, 5, 11)]);
+    }
+
+    #[test]
+    fn test_bridge_days_across_year_boundary() {
+        let mut calendar = HolidayList::new();
+        // January 1st, 2019 is a Tuesday, making Monday December 31st, 2018 a bridge day.
+        calendar.add(date(2019, 1, 1), Holiday::new("New Year's Day", HolidayCategory::Public));
+        assert_eq!(bridge_days(2018, &calendar, &Workweek::default()), vec![date(2018, 12, 31)]);
+        assert!(bridge_days(2019, &calendar, &Workweek::default()).is_empty());
+    }
+
+    #[test]
+    fn test_long_weekends() {
+        // Neither the Thursday nor the Tuesday holiday is joined to a weekend by itself.
+        assert!(long_weekends(2018, &calendar(), &Workweek::default(), 3).is_empty());
+
+        let mut calendar = calendar();
+        calendar.add(date(2018, 5, 11), Holiday::new("Company Day", HolidayCategory::Public));
+        let weekends = long_weekends(2018, &calendar, &Workweek::default(), 3);
+        assert_eq!(weekends, vec![date(2018, 5, 10)..=date(2018, 5, 13)]);
+    }
+
+    #[test]
+    fn test_long_weekends_plain_weekends() {
+        let weekends = long_weekends(2018, &HolidayList::new(), &Workweek::default(), 2);
+        assert_eq!(weekends.len(), 52);
+        assert_eq!(weekends[0], date(2018, 1, 6)..=date(2018, 1, 7));
+        assert!(long_weekends(2018, &HolidayList::new(), &Workweek::default(), 3).is_empty());
+    }
+
+    #[test]
+    fn test_long_weekends_across_year_boundary() {
+        let mut calendar = HolidayList::new();
+        // Friday, December 29th, 2017 through Monday, January 1st, 2018.
+        calendar
+            .add(date(2017, 12, 29), Holiday::new("Year-end Holiday", HolidayCategory::Bank))
+            .add(date(2018, 1, 1), Holiday::new("New Year's Day", HolidayCategory::Public));
+        let expected = vec![date(2017, 12, 29)..=date(2018, 1, 1)];
+        assert_eq!(long_weekends(2018, &calendar, &Workweek::default(), 3), expected);
+        assert_eq!(long_weekends(2017, &calendar, &Workweek::default(), 3), expected);
+    }
+
+    #[test]
+    fn test_empty_workweek() {
+        // Every day is off, so the search for the ends of a run would never stop.
+        let workweek = Workweek::new(&[]);
+        assert!(long_weekends(2018, &calendar(), &workweek, 3).is_empty());
+        assert!(bridge_days(2018, &calendar(), &workweek).is_empty());
+    }
+
+    #[test]
+    fn test_bridge_days_none_without_holidays() {
+        assert!(bridge_days(2018, &HolidayList::new(), &Workweek::default()).is_empty());
+    }
+
+    #[test]
+    fn test_roll() {
+        let calendar = calendar();
+        let workweek = Workweek::monday_to_friday();
+        // Saturday June 30th, 2018.
+        let saturday = date(2018, 6, 30);
+        assert_eq!(roll(saturday, RollConvention::Unadjusted, &calendar, &workweek),
+                   Some(saturday));
+        assert_eq!(roll(saturday, RollConvention::Following, &calendar, &workweek),
+                   Some(date(2018, 7, 2)));
+        assert_eq!(roll(saturday, RollConvention::ModifiedFollowing, &calendar, &workweek),
+                   Some(date(2018, 6, 29)));
+        assert_eq!(roll(saturday, RollConvention::Preceding, &calendar, &workweek),
+                   Some(date(2018, 6, 29)));
+        // Working days are left alone.
+        assert_eq!(roll(date(2018, 6, 29), RollConvention::Following, &calendar, &workweek),
+                   Some(date(2018, 6, 29)));
+    }
+
+    #[test]
+    fn test_roll_skips_holidays() {
+        let calendar = calendar();
+        let workweek = Workweek::monday_to_friday();
+        // Labour Day is a Tuesday, and the working day before it is in April.
+        let labour_day = date(2018, 5, 1);
+        assert_eq!(roll(labour_day, RollConvention::Preceding, &calendar, &workweek),
+                   Some(date(2018, 4, 30)));
+        assert_eq!(roll(labour_day, RollConvention::ModifiedPreceding, &calendar, &workweek),
+                   Some(date(2018, 5, 2)));
+        assert_eq!(roll(labour_day, RollConvention::Following, &calendar, &Workweek::new(&[])),
+                   None);
+    }
+
+    #[test]
+    fn test_year_overview() {
+        let overview = year_overview(2018, &calendar(), &Workweek::monday_to_friday());
+        assert_eq!(overview.days.len(), 365);
+        assert_eq!(overview.count(DayKind::Holiday), 3);
+        assert_eq!(overview.count(DayKind::Weekend), 104);
+        assert_eq!(overview.count(DayKind::Working), 258);
+
+        let may = overview.month(5);
+        assert_eq!(may.len(), 31);
+        assert_eq!(may[0], OverviewDay { date: date(2018, 5, 1), kind: DayKind::Holiday,
+                                         holiday_name: Some("Labour Day".to_string()) });
+        assert_eq!(may[4].kind, DayKind::Weekend);
+        let all_saints = &overview.month(11)[0];
+        assert_eq!(all_saints.kind, DayKind::Working);
+        assert_eq!(all_saints.holiday_name.as_deref(), Some("All Saints' Day"));
+        assert!(year_overview(300_000, &calendar(), &Workweek::default()).days.is_empty());
+    }
+}
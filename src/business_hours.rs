@@ -0,0 +1,102 @@
+extern crate chrono;
+
+use business_days::Workweek;
+use chrono::{DateTime, Datelike, Days, NaiveTime, TimeZone};
+use civil::{resolve_local, DstPolicy};
+
+/// The opening hours of a business: the same opening and closing times on every workday of a
+/// workweek, in a given timezone.  The closing time must be after the opening time; hours which
+/// run past midnight are not supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusinessHours<Tz: TimeZone> {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+    pub workweek: Workweek,
+    pub tz: Tz,
+}
+
+impl<Tz: TimeZone> BusinessHours<Tz> {
+    pub fn new(open: NaiveTime, close: NaiveTime, workweek: Workweek, tz: Tz) -> BusinessHours<Tz> {
+        BusinessHours { open, close, workweek, tz }
+    }
+
+    /// Returns true if the business is open at `dt`.  The opening time is included and the
+    /// closing time is not.
+    pub fn is_open<Tz2: TimeZone>(&self, dt: &DateTime<Tz2>) -> bool {
+        let local = dt.with_timezone(&self.tz).naive_local();
+        self.workweek.is_workday(local.weekday())
+            && local.time() >= self.open
+            && local.time() < self.close
+    }
+}
+
+/// Returns `dt` itself if `hours` are open at `dt`, and otherwise the next time they open, in the
+/// business's timezone.  Opening times which fall in a DST gap are moved forward by the length of
+/// the gap.  Returns `None` if the workweek has no workdays or the result is out of range.
+pub fn next_working_instant<Tz, Tz2>(dt: &DateTime<Tz2>,
+                                     hours: &BusinessHours<Tz>) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, Tz2: TimeZone {
+    let dt = dt.with_timezone(&hours.tz);
+    if hours.is_open(&dt) {
+        return Some(dt);
+    }
+    let today = dt.date_naive();
+    (0..8)
+        .filter_map(|days| today.checked_add_days(Days::new(days)))
+        .filter(|date| hours.workweek.is_workday(date.weekday()))
+        .filter_map(|date| resolve_local(&hours.tz, &date.and_time(hours.open),
+                                         DstPolicy::ShiftForward))
+        .find(|opening| *opening > dt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn hours() -> BusinessHours<FixedOffset> {
+        BusinessHours::new(NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                           NaiveTime::from_hms_opt(17, 30, 0).unwrap(),
+                           Workweek::monday_to_friday(),
+                           FixedOffset::east_opt(9 * 3600).unwrap())
+    }
+
+    #[test]
+    fn test_is_open() {
+        let hours = hours();
+        // Thursday March 15th, 2018.
+        assert!(hours.is_open(&at("2018-03-15T09:00:00+09:00")));
+        assert!(hours.is_open(&at("2018-03-15T08:00:00Z")));
+        assert!(!hours.is_open(&at("2018-03-15T17:30:00+09:00")));
+        assert!(!hours.is_open(&at("2018-03-15T08:59:59+09:00")));
+        assert!(!hours.is_open(&at("2018-03-17T12:00:00+09:00")));
+    }
+
+    #[test]
+    fn test_next_working_instant() {
+        let hours = hours();
+        let open = at("2018-03-15T12:00:00+09:00");
+        assert_eq!(next_working_instant(&open, &hours), Some(open));
+        assert_eq!(next_working_instant(&at("2018-03-15T07:00:00+09:00"), &hours),
+                   Some(at("2018-03-15T09:00:00+09:00")));
+        assert_eq!(next_working_instant(&at("2018-03-15T18:00:00+09:00"), &hours),
+                   Some(at("2018-03-16T09:00:00+09:00")));
+        // Friday evening skips the weekend.
+        assert_eq!(next_working_instant(&at("2018-03-16T17:30:00+09:00"), &hours),
+                   Some(at("2018-03-19T09:00:00+09:00")));
+        // Given in UTC, this is Saturday morning in the business's timezone.
+        let saturday = DateTime::parse_from_rfc3339("2018-03-17T01:00:00Z").unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(next_working_instant(&saturday, &hours), Some(at("2018-03-19T09:00:00+09:00")));
+    }
+
+    #[test]
+    fn test_next_working_instant_no_workdays() {
+        let hours = BusinessHours { workweek: Workweek::new(&[]), ..hours() };
+        assert_eq!(next_working_instant(&at("2018-03-15T12:00:00+09:00"), &hours), None);
+    }
+}
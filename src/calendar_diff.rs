@@ -0,0 +1,145 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use month_calc::MonthCalculations;
+use std::fmt;
+
+/// The calendar difference between two dates or datetimes, broken down into whole years, months,
+/// days, hours, minutes and seconds (any fraction of a second is dropped), e.g. "2 years,
+/// 3 months, 12 days".  The fields are all non-negative, with `negative` set if the first value
+/// is before the second.
+///
+/// Whole months are counted first, the same way as `MonthCalculations::add_months`: the months
+/// are added to the earlier value (clamping the day of the month to the end of shorter months),
+/// and the rest is broken down into days and the time of day.  So January 31st to March 1st is
+/// 1 month (to February 28th) and 1 day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CalendarDiff {
+    pub years: u32,
+    pub months: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+    pub negative: bool,
+}
+
+/// This trait defines the calendar difference between two dates or datetimes.  DateTimes can be
+/// compared with DateTimes in any timezone: the other DateTime is first converted to self's
+/// timezone, and the difference is taken between the local dates and times (so the day of a DST
+/// change still counts as one day, and hours are counted by the wall clock).  NaiveDates and
+/// NaiveDateTimes are compared with values of the same type.
+pub trait CalendarDifference<Rhs = Self> {
+    /// Returns the calendar difference from `b` to self (which is negative if self is earlier).
+    fn calendar_diff(&self, b: &Rhs) -> CalendarDiff;
+}
+
+fn calendar_diff_naive(a: &NaiveDateTime, b: &NaiveDateTime) -> CalendarDiff {
+    if a < b {
+        return CalendarDiff { negative: true, ..calendar_diff_naive(b, a) };
+    }
+    let (earlier, later) = (b, a);
+    let shift = |months: i64| {
+        earlier.add_months_checked(months).expect("Both values are in range, and so is the shift")
+    };
+    let mut months = (later.year() as i64 - earlier.year() as i64) * 12
+        + later.month() as i64 - earlier.month() as i64;
+    if shift(months) > *later {
+        months -= 1;
+    }
+    let rest = later.signed_duration_since(shift(months)).num_seconds();
+    CalendarDiff {
+        years: (months / 12) as u32,
+        months: (months % 12) as u32,
+        days: (rest / 86_400) as u32,
+        hours: (rest % 86_400 / 3600) as u32,
+        minutes: (rest % 3600 / 60) as u32,
+        seconds: (rest % 60) as u32,
+        negative: false,
+    }
+}
+
+impl<Tz, Tz2> CalendarDifference<DateTime<Tz2>> for DateTime<Tz>
+    where Tz: TimeZone, Tz2: TimeZone {
+    fn calendar_diff(&self, b: &DateTime<Tz2>) -> CalendarDiff {
+        calendar_diff_naive(&self.naive_local(), &b.with_timezone(&self.timezone()).naive_local())
+    }
+}
+
+impl CalendarDifference for NaiveDateTime {
+    fn calendar_diff(&self, b: &NaiveDateTime) -> CalendarDiff {
+        calendar_diff_naive(self, b)
+    }
+}
+
+impl CalendarDifference for NaiveDate {
+    fn calendar_diff(&self, b: &NaiveDate) -> CalendarDiff {
+        calendar_diff_naive(&self.and_time(NaiveTime::MIN), &b.and_time(NaiveTime::MIN))
+    }
+}
+
+impl fmt::Display for CalendarDiff {
+    /// Writes the non-zero fields as e.g. "2 years, 3 months, 12 days, 4 hours" ("0 seconds" if
+    /// all are zero).  Negative differences are written with a leading "-", with the fields in
+    /// parentheses if there is more than one, e.g. "-(2 years, 3 months)" or "-12 days".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = [(self.years, "year"), (self.months, "month"), (self.days, "day"),
+                                  (self.hours, "hour"), (self.minutes, "minute"),
+                                  (self.seconds, "second")]
+            .iter()
+            .filter(|&&(n, _)| n != 0)
+            .map(|&(n, unit)| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" }))
+            .collect();
+
+        if parts.is_empty() {
+            return write!(f, "0 seconds");
+        }
+        match (self.negative, parts.len()) {
+            (false, _) => write!(f, "{}", parts.join(", ")),
+            (true, 1) => write!(f, "-{}", parts[0]),
+            (true, _) => write!(f, "-({})", parts.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<chrono::FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_calendar_diff_dates() {
+        let diff = date(2018, 6, 27).calendar_diff(&date(2016, 3, 15));
+        assert_eq!(diff, CalendarDiff { years: 2, months: 3, days: 12, ..Default::default() });
+        assert_eq!(diff.to_string(), "2 years, 3 months, 12 days");
+        let diff = date(2016, 3, 15).calendar_diff(&date(2018, 6, 27));
+        assert_eq!(diff.to_string(), "-(2 years, 3 months, 12 days)");
+        assert!(diff.negative);
+        assert_eq!(date(2018, 3, 1).calendar_diff(&date(2018, 3, 13)).to_string(), "-12 days");
+        // January 31st + 1 month is February 28th.
+        let diff = date(2018, 3, 1).calendar_diff(&date(2018, 1, 31));
+        assert_eq!(diff, CalendarDiff { months: 1, days: 1, ..Default::default() });
+        assert_eq!(date(2018, 3, 1).calendar_diff(&date(2018, 3, 1)).to_string(), "0 seconds");
+    }
+
+    #[test]
+    fn test_calendar_diff_times() {
+        // The time of day hasn't been reached yet, so the last month isn't complete.
+        let diff = at("2018-04-15T09:30:05Z").calendar_diff(&at("2018-01-15T12:00:00Z"));
+        assert_eq!(diff, CalendarDiff { months: 2, days: 30, hours: 21, minutes: 30, seconds: 5,
+                                        ..Default::default() });
+        // The other DateTime is converted to self's timezone.
+        let tokyo = at("2018-01-15T21:00:00+09:00");
+        assert_eq!(at("2019-01-15T12:00:00Z").calendar_diff(&tokyo).to_string(), "1 year");
+        let a = NaiveDate::from_ymd_opt(2018, 3, 15).unwrap().and_hms_opt(1, 2, 3).unwrap();
+        assert_eq!(a.calendar_diff(&date(2018, 3, 14).and_time(NaiveTime::MIN)).to_string(),
+                   "1 day, 1 hour, 2 minutes, 3 seconds");
+    }
+}
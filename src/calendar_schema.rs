@@ -0,0 +1,173 @@
+//! Loading and saving `HolidayRules` with serde.  Holiday calendars are stored in the following
+//! schema (shown as JSON; YAML or any other serde format works the same way):
+//!
+//! ```json
+//! {
+//!   "holidays": [
+//!     { "name": "New Year's Day", "category": "public", "rule": "Jan 1 observed" },
+//!     { "name": "Martin Luther King Jr. Day", "category": "public",
+//!       "rule": "3rd Monday of January" },
+//!     { "name": "Easter Monday", "category": "bank", "rule": "Easter +1" },
+//!     { "name": "Orthodox Good Friday", "category": "bank", "rule": "Orthodox Easter -2" },
+//!     { "name": "Year-end Closing", "category": "observance",
+//!       "rule": "last Friday of the year" }
+//!   ]
+//! }
+//! ```
+//!
+//! The category is one of "public", "bank" or "observance", and defaults to "public".  The rule is
+//! written the way `parse_holiday_rule` accepts it: a fixed date with an optional observance
+//! shift, an nth weekday of a month, an nth weekday of the year, or a number of days relative to
+//! Easter or Orthodox Easter.
+
+extern crate serde;
+extern crate serde_json;
+
+use holiday_rules::{parse_holiday_rule, HolidayRule, HolidayRules};
+use holidays::{Holiday, HolidayCategory};
+use self::serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for HolidayRule {
+    /// Fails for rules which aren't valid (see `HolidayRule::is_valid`), so that a calendar which
+    /// couldn't be loaded again is rejected when it is saved.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.is_valid() {
+            return Err(ser::Error::custom(format!("invalid holiday rule: {:?}", self)));
+        }
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HolidayRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HolidayRule, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_holiday_rule(&s)
+            .ok_or_else(|| de::Error::custom(format!("invalid holiday rule: {:?}", s)))
+    }
+}
+
+impl Serialize for HolidayCategory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match *self {
+            HolidayCategory::Public => "public",
+            HolidayCategory::Bank => "bank",
+            HolidayCategory::Observance => "observance",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for HolidayCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HolidayCategory, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "public" => Ok(HolidayCategory::Public),
+            "bank" => Ok(HolidayCategory::Bank),
+            "observance" => Ok(HolidayCategory::Observance),
+            other => Err(de::Error::unknown_variant(other, &["public", "bank", "observance"])),
+        }
+    }
+}
+
+fn default_category() -> HolidayCategory {
+    HolidayCategory::Public
+}
+
+#[derive(Serialize, Deserialize)]
+struct HolidayEntry {
+    name: String,
+    #[serde(default = "default_category")]
+    category: HolidayCategory,
+    rule: HolidayRule,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CalendarFile {
+    holidays: Vec<HolidayEntry>,
+}
+
+impl Serialize for HolidayRules {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let holidays = self.iter().map(|(rule, holiday)| HolidayEntry {
+            name: holiday.name.clone(),
+            category: holiday.category,
+            rule: *rule,
+        }).collect();
+        CalendarFile { holidays }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HolidayRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HolidayRules, D::Error> {
+        let file = CalendarFile::deserialize(deserializer)?;
+        let mut rules = HolidayRules::new();
+        for entry in file.holidays {
+            rules.add(entry.rule, Holiday::new(entry.name, entry.category));
+        }
+        Ok(rules)
+    }
+}
+
+impl HolidayRules {
+    /// Load holiday rules from JSON in the calendar schema described in the `calendar_schema`
+    /// module documentation.
+    pub fn from_json(json: &str) -> Result<HolidayRules, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Save these holiday rules as pretty-printed JSON in the calendar schema.  Returns an error
+    /// if any rule isn't valid (see `HolidayRule::is_valid`).
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use holidays::HolidayCalendar;
+
+    const CALENDAR: &str = r#"{
+        "holidays": [
+            { "name": "New Year's Day", "rule": "Jan 1 observed" },
+            { "name": "Easter Monday", "category": "bank", "rule": "Easter +1" },
+            { "name": "Mother's Day", "category": "observance", "rule": "2nd Sunday of May" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_from_json() {
+        let rules = HolidayRules::from_json(CALENDAR).unwrap();
+        let calendar = rules.to_calendar(2018..=2018);
+        let date = |m, d| NaiveDate::from_ymd_opt(2018, m, d).unwrap();
+        assert_eq!(calendar.holiday_name(date(1, 1)), Some("New Year's Day"));
+        assert_eq!(calendar.holiday_category(date(4, 2)), Some(HolidayCategory::Bank));
+        assert_eq!(calendar.holiday_category(date(5, 13)), Some(HolidayCategory::Observance));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let rules = HolidayRules::from_json(CALENDAR).unwrap();
+        let json = rules.to_json().unwrap();
+        assert!(json.contains(r#""rule": "2nd Sunday of May""#));
+        assert!(json.contains(r#""category": "public""#));
+        assert_eq!(HolidayRules::from_json(&json).unwrap(), rules);
+    }
+
+    #[test]
+    fn test_from_json_invalid() {
+        let bad_rule = r#"{ "holidays": [{ "name": "Someday", "rule": "Feb 30" }] }"#;
+        let error = HolidayRules::from_json(bad_rule).unwrap_err();
+        assert!(error.to_string().contains("invalid holiday rule: \"Feb 30\""));
+        let bad_category = r#"{ "holidays": [{ "name": "X", "category": "?", "rule": "Jan 1" }] }"#;
+        assert!(HolidayRules::from_json(bad_category).is_err());
+    }
+
+    #[test]
+    fn test_to_json_invalid() {
+        let mut rules = HolidayRules::new();
+        rules.add(HolidayRule::Fixed { month: 13, day: 1, observed: false },
+                  Holiday::new("Someday", HolidayCategory::Public));
+        let error = rules.to_json().unwrap_err();
+        assert!(error.to_string().contains("invalid holiday rule: Fixed { month: 13"));
+    }
+}
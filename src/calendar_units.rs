@@ -0,0 +1,110 @@
+extern crate chrono;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use month_calc::MonthCalculations;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// A number of calendar months, for writing month arithmetic with operators, e.g.
+/// `date + Months(3) - Years(1)`.  Adding months clamps the day of the month the same way as
+/// `MonthCalculations::add_months` (January 31st + `Months(1)` is February 28th), and panics if
+/// the result is out of range.  Unlike `chrono::Months`, the number of months may be negative.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Months(pub i32);
+
+/// A number of calendar years, for writing year arithmetic with operators.  Adding years is the
+/// same as adding twelve times as many `Months`, so February 29th + `Years(1)` is February 28th.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Years(pub i32);
+
+impl Months {
+    fn count(self) -> i64 {
+        self.0 as i64
+    }
+}
+
+impl Years {
+    fn count(self) -> i64 {
+        self.0 as i64 * 12
+    }
+}
+
+fn shift<T: MonthCalculations>(value: &T, num_months: i64) -> T {
+    value.add_months_checked(num_months)
+        .expect("Value invalid: the resulting date is out of range")
+}
+
+macro_rules! impl_calendar_unit_ops {
+    ($unit:ty, [$($generics:tt)*], $target:ty) => {
+        impl<$($generics)*> Add<$unit> for $target {
+            type Output = $target;
+
+            fn add(self, rhs: $unit) -> $target {
+                shift(&self, rhs.count())
+            }
+        }
+
+        impl<$($generics)*> Sub<$unit> for $target {
+            type Output = $target;
+
+            fn sub(self, rhs: $unit) -> $target {
+                shift(&self, -rhs.count())
+            }
+        }
+
+        impl<$($generics)*> AddAssign<$unit> for $target {
+            fn add_assign(&mut self, rhs: $unit) {
+                *self = shift(self, rhs.count());
+            }
+        }
+
+        impl<$($generics)*> SubAssign<$unit> for $target {
+            fn sub_assign(&mut self, rhs: $unit) {
+                *self = shift(self, -rhs.count());
+            }
+        }
+    };
+}
+
+impl_calendar_unit_ops!(Months, [Tz: TimeZone], DateTime<Tz>);
+impl_calendar_unit_ops!(Years, [Tz: TimeZone], DateTime<Tz>);
+impl_calendar_unit_ops!(Months, [], NaiveDate);
+impl_calendar_unit_ops!(Years, [], NaiveDate);
+impl_calendar_unit_ops!(Months, [], NaiveDateTime);
+impl_calendar_unit_ops!(Years, [], NaiveDateTime);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_add_and_sub() {
+        assert_eq!(date(2018, 1, 31) + Months(1), date(2018, 2, 28));
+        assert_eq!(date(2018, 3, 15) + Months(3) - Years(1), date(2017, 6, 15));
+        assert_eq!(date(2018, 3, 15) - Months(-10), date(2019, 1, 15));
+        assert_eq!(date(2016, 2, 29) + Years(1), date(2017, 2, 28));
+        assert_eq!(date(2018, 3, 15) + Years(0), date(2018, 3, 15));
+    }
+
+    #[test]
+    fn test_assign() {
+        let mut test_date = DateTime::parse_from_rfc3339("2018-03-31T12:00:00+09:00").unwrap();
+        test_date += Months(1);
+        assert_eq!(test_date.to_rfc3339(), "2018-04-30T12:00:00+09:00");
+        test_date -= Years(2);
+        assert_eq!(test_date.to_rfc3339(), "2016-04-30T12:00:00+09:00");
+
+        let mut noon = date(2018, 3, 31).and_hms_opt(12, 0, 0).unwrap();
+        noon -= Months(1);
+        assert_eq!(noon, date(2018, 2, 28).and_hms_opt(12, 0, 0).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_out_of_range() {
+        let _ = date(2018, 3, 15) + Years(i32::MAX);
+    }
+}
@@ -0,0 +1,202 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use std::fmt;
+use std::ops::Range;
+
+/// How a local time is resolved when it doesn't map to exactly one instant in a timezone,
+/// because it falls in a DST gap (it doesn't exist) or overlap (it is ambiguous).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DstPolicy {
+    /// Use the earlier instant of an ambiguous time; nonexistent times are rejected.
+    Earliest,
+    /// Use the later instant of an ambiguous time; nonexistent times are rejected.
+    Latest,
+    /// Move nonexistent times forward by the length of the gap (so 02:30 becomes 03:30 when
+    /// clocks jump from 02:00 to 03:00), and use the earlier instant of an ambiguous time.
+    ShiftForward,
+    /// Reject both nonexistent and ambiguous times.
+    Reject,
+}
+
+/// Resolve a local time in `tz` according to `policy`.
+pub(crate) fn resolve_local<Tz: TimeZone>(tz: &Tz, local: &NaiveDateTime,
+                                          policy: DstPolicy) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(local) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, latest) => match policy {
+            DstPolicy::Earliest | DstPolicy::ShiftForward => Some(earliest),
+            DstPolicy::Latest => Some(latest),
+            DstPolicy::Reject => None,
+        },
+        LocalResult::None => match policy {
+            DstPolicy::ShiftForward => {
+                // Applying the offset in force before the gap lands the same distance past it.
+                let day_before = local.checked_sub_signed(Duration::days(1))?;
+                let offset = tz.offset_from_utc_datetime(&day_before).fix().local_minus_utc();
+                let utc = local.checked_sub_signed(Duration::seconds(offset as i64))?;
+                Some(tz.from_utc_datetime(&utc))
+            }
+            DstPolicy::Earliest | DstPolicy::Latest | DstPolicy::Reject => None,
+        },
+    }
+}
+
+/// A wall-clock date and time with no timezone, such as "9am on March 15th" in a calendar entry.
+/// Unlike a `NaiveDateTime`, which is often a UTC time with its zone dropped, a CivilDateTime is
+/// explicitly not an instant: it only becomes one when a timezone is chosen with `assume_tz` or
+/// `in_tz_of`, and the caller must say how DST gaps and overlaps are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilDateTime(NaiveDateTime);
+
+impl CivilDateTime {
+    pub fn new(local: NaiveDateTime) -> CivilDateTime {
+        CivilDateTime(local)
+    }
+
+    /// Construct a CivilDateTime from its fields, or `None` if any is out of range.
+    pub fn from_ymd_hms(year: i32, month: u32, day: u32,
+                        hour: u32, min: u32, sec: u32) -> Option<CivilDateTime> {
+        NaiveDate::from_ymd_opt(year, month, day)?
+            .and_hms_opt(hour, min, sec)
+            .map(CivilDateTime)
+    }
+
+    /// Returns the wall-clock time as a `NaiveDateTime`.
+    pub fn naive(&self) -> NaiveDateTime {
+        self.0
+    }
+
+    /// Returns the instant at which the clocks in `tz` show this time, resolving DST gaps and
+    /// overlaps with `policy`.
+    pub fn assume_tz<Tz: TimeZone>(&self, tz: &Tz, policy: DstPolicy) -> Option<DateTime<Tz>> {
+        resolve_local(tz, &self.0, policy)
+    }
+
+    /// Returns this time in the timezone of `event`, resolving DST gaps and overlaps with
+    /// `policy`.  This is useful for times that are relative to another event, such as "9am on
+    /// the day of the meeting, wherever the meeting is".
+    pub fn in_tz_of<Tz: TimeZone>(&self, event: &DateTime<Tz>,
+                                  policy: DstPolicy) -> Option<DateTime<Tz>> {
+        self.assume_tz(&event.timezone(), policy)
+    }
+}
+
+/// Returns the UTC instants at which the clocks in each of `zones` show the wall-clock time
+/// `local`, in the same order as `zones`, for rollouts such as "launch at 9am local time
+/// everywhere".  DST gaps and overlaps are resolved with `policy`, and an entry is `None` if the
+/// time can't be resolved in that zone.  Any `TimeZone` works, including the zones of the
+/// `chrono-tz` crate.
+pub fn local_deadline_in_zones<Tz: TimeZone>(local: &NaiveDateTime, zones: &[Tz],
+                                             policy: DstPolicy) -> Vec<Option<DateTime<Utc>>> {
+    zones.iter()
+        .map(|tz| resolve_local(tz, local, policy).map(|dt| dt.with_timezone(&Utc)))
+        .collect()
+}
+
+/// Returns the half-open interval during which it is `date` somewhere on Earth: from midnight
+/// at the start of the day in UTC+14 (the earliest timezone) to midnight at the end of the day in
+/// UTC-12 (the latest), 50 hours in all.  This makes checks such as "is it still March 15th
+/// anywhere" a single `contains` call.  Returns `None` if the bounds are out of range.
+pub fn date_global_bounds(date: NaiveDate) -> Option<Range<DateTime<Utc>>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    let start = midnight.checked_sub_signed(Duration::hours(14))?;
+    let end = midnight.checked_add_signed(Duration::hours(24 + 12))?;
+    Some(Utc.from_utc_datetime(&start)..Utc.from_utc_datetime(&end))
+}
+
+impl From<NaiveDateTime> for CivilDateTime {
+    fn from(local: NaiveDateTime) -> CivilDateTime {
+        CivilDateTime(local)
+    }
+}
+
+/// The wall-clock time of a DateTime in its own timezone.
+impl<Tz: TimeZone> From<DateTime<Tz>> for CivilDateTime {
+    fn from(dt: DateTime<Tz>) -> CivilDateTime {
+        CivilDateTime(dt.naive_local())
+    }
+}
+
+impl fmt::Display for CivilDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use test_tz::Cet;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_assume_tz() {
+        let civil = CivilDateTime::from_ymd_hms(2018, 3, 15, 9, 0, 0).unwrap();
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let dt = civil.assume_tz(&tz, DstPolicy::Reject).unwrap();
+        assert_eq!(dt.with_timezone(&Utc), utc("2018-03-15T00:00:00Z"));
+        assert_eq!(CivilDateTime::from(dt), civil);
+        assert_eq!(civil.to_string(), "2018-03-15 09:00:00");
+    }
+
+    #[test]
+    fn test_assume_tz_gap() {
+        // Clocks in Cet jump from 02:00 to 03:00 on March 25th, 2018.
+        let civil = CivilDateTime::from_ymd_hms(2018, 3, 25, 2, 30, 0).unwrap();
+        assert_eq!(civil.assume_tz(&Cet, DstPolicy::Earliest), None);
+        assert_eq!(civil.assume_tz(&Cet, DstPolicy::Reject), None);
+        let shifted = civil.assume_tz(&Cet, DstPolicy::ShiftForward).unwrap();
+        assert_eq!(shifted.with_timezone(&Utc), utc("2018-03-25T01:30:00Z"));
+        assert_eq!(shifted.naive_local().to_string(), "2018-03-25 03:30:00");
+    }
+
+    #[test]
+    fn test_assume_tz_overlap() {
+        // Clocks in Cet go back from 03:00 to 02:00 on October 28th, 2018.
+        let civil = CivilDateTime::from_ymd_hms(2018, 10, 28, 2, 30, 0).unwrap();
+        let earliest = civil.assume_tz(&Cet, DstPolicy::Earliest).unwrap();
+        assert_eq!(earliest.with_timezone(&Utc), utc("2018-10-28T00:30:00Z"));
+        let latest = civil.assume_tz(&Cet, DstPolicy::Latest).unwrap();
+        assert_eq!(latest.with_timezone(&Utc), utc("2018-10-28T01:30:00Z"));
+        assert_eq!(civil.assume_tz(&Cet, DstPolicy::Reject), None);
+    }
+
+    #[test]
+    fn test_in_tz_of() {
+        let event = DateTime::parse_from_rfc3339("2018-03-15T18:00:00-05:00").unwrap();
+        let civil = CivilDateTime::from_ymd_hms(2018, 3, 15, 9, 0, 0).unwrap();
+        let dt = civil.in_tz_of(&event, DstPolicy::Reject).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2018-03-15T09:00:00-05:00");
+    }
+
+    #[test]
+    fn test_local_deadline_in_zones() {
+        let local = CivilDateTime::from_ymd_hms(2018, 3, 15, 9, 0, 0).unwrap().naive();
+        let zones: Vec<_> = [9, 0, -5].iter()
+            .map(|&hours| FixedOffset::east_opt(hours * 3600).unwrap())
+            .collect();
+        assert_eq!(local_deadline_in_zones(&local, &zones, DstPolicy::Reject),
+                   vec![Some(utc("2018-03-15T00:00:00Z")), Some(utc("2018-03-15T09:00:00Z")),
+                        Some(utc("2018-03-15T14:00:00Z"))]);
+
+        let in_gap = CivilDateTime::from_ymd_hms(2018, 3, 25, 2, 30, 0).unwrap().naive();
+        assert_eq!(local_deadline_in_zones(&in_gap, &[Cet], DstPolicy::Reject), vec![None]);
+        assert_eq!(local_deadline_in_zones(&in_gap, &[Cet], DstPolicy::ShiftForward),
+                   vec![Some(utc("2018-03-25T01:30:00Z"))]);
+    }
+
+    #[test]
+    fn test_date_global_bounds() {
+        let bounds = date_global_bounds(NaiveDate::from_ymd_opt(2018, 3, 15).unwrap()).unwrap();
+        assert_eq!(bounds, utc("2018-03-14T10:00:00Z")..utc("2018-03-16T12:00:00Z"));
+        assert!(bounds.contains(&utc("2018-03-16T11:59:59Z")));
+        assert!(!bounds.contains(&utc("2018-03-16T12:00:00Z")));
+        assert!(!bounds.contains(&utc("2018-03-14T09:59:59Z")));
+        assert_eq!(date_global_bounds(NaiveDate::MAX), None);
+    }
+}
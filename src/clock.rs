@@ -0,0 +1,130 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, Utc};
+use std::cell::Cell;
+use std::time::Instant;
+
+/// This trait defines a source of the current time, so code which needs "now" can be driven by
+/// a controllable clock in tests instead of the system clock.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system's wall clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock which only moves when told to, for tests and simulations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManualClock {
+    now: Cell<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> ManualClock {
+        ManualClock { now: Cell::new(now) }
+    }
+
+    /// Set the clock to `now` (which may be earlier than the current time).
+    pub fn set(&self, now: DateTime<Utc>) {
+        self.now.set(now);
+    }
+
+    /// Move the clock forward by `by` (or backwards, if `by` is negative).
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.get()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// A wall-clock time and a monotonic instant captured together, for noticing when the wall clock
+/// jumps (e.g. after an NTP correction or a manual change) relative to the monotonic clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimestampPair {
+    pub wall: DateTime<Utc>,
+    pub mono: Instant,
+}
+
+impl TimestampPair {
+    /// Capture the current time from `clock` along with the current monotonic instant.
+    pub fn capture<C: Clock + ?Sized>(clock: &C) -> TimestampPair {
+        TimestampPair { wall: clock.now(), mono: Instant::now() }
+    }
+
+    /// Returns how far the wall clock moved relative to the monotonic clock between this pair
+    /// and `later`: the wall-clock time elapsed minus the monotonic time elapsed.  This is
+    /// positive if the wall clock jumped forward, negative if it jumped back, and close to zero
+    /// if neither clock was adjusted.  Returns `None` if `later` was captured before this pair
+    /// or the difference is out of range.
+    pub fn drift_to(&self, later: &TimestampPair) -> Option<Duration> {
+        let mono = Duration::from_std(later.mono.checked_duration_since(self.mono)?).ok()?;
+        later.wall.signed_duration_since(self.wall).checked_sub(&mono)
+    }
+
+    /// Returns the drift (see `drift_to`) between this pair and a pair captured now from
+    /// `clock`.
+    pub fn elapsed_wall_vs_mono<C: Clock + ?Sized>(&self, clock: &C) -> Option<Duration> {
+        self.drift_to(&TimestampPair::capture(clock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let start = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap()
+            .with_timezone(&Utc);
+        let clock = ManualClock::new(start);
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::seconds(90));
+        assert_eq!(clock.now(), start + Duration::seconds(90));
+        clock.set(start - Duration::days(1));
+        assert_eq!(clock.now(), start - Duration::days(1));
+    }
+
+    #[test]
+    fn test_timestamp_pair_drift() {
+        let start = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap()
+            .with_timezone(&Utc);
+        let first = TimestampPair { wall: start, mono: Instant::now() };
+        let mono = first.mono + std::time::Duration::from_secs(60);
+        let steady = TimestampPair { wall: start + Duration::seconds(60), mono };
+        assert_eq!(first.drift_to(&steady), Some(Duration::zero()));
+        let jumped = TimestampPair { wall: start + Duration::seconds(65), mono };
+        assert_eq!(first.drift_to(&jumped), Some(Duration::seconds(5)));
+        let rewound = TimestampPair { wall: start, mono };
+        assert_eq!(first.drift_to(&rewound), Some(Duration::seconds(-60)));
+        assert_eq!(jumped.drift_to(&first), None);
+    }
+
+    #[test]
+    fn test_elapsed_wall_vs_mono() {
+        let clock = ManualClock::new(DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap()
+            .with_timezone(&Utc));
+        let pair = TimestampPair::capture(&clock);
+        clock.advance(Duration::hours(1));
+        // Hardly any monotonic time passes, so the manual jump shows up as drift.
+        let drift = pair.elapsed_wall_vs_mono(&clock).unwrap();
+        assert!(drift > Duration::minutes(59) && drift <= Duration::hours(1));
+    }
+}
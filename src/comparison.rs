@@ -0,0 +1,219 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, TimeZone};
+use month_calc::MonthCalculations;
+use std::ops::Range;
+
+/// Determines how an interval is moved when it is shifted by whole months or years for
+/// period-over-period comparisons.  Intervals are half-open, so February 2018 is
+/// `2018-02-01T00:00..2018-03-01T00:00`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShiftPolicy {
+    /// Shift the start and end separately with `MonthCalculations::add_months` (clamping to the
+    /// last day of shorter months).  Whole months map to whole months, so February 1-28 compares
+    /// to January 1-31.
+    ShiftEndpoints,
+    /// Shift the start with `MonthCalculations::add_months`, and keep the interval's length, so
+    /// February 1-28 compares to January 1-28.
+    PreserveLength,
+    /// Shift the whole interval by the whole number of weeks closest to the calendar shift of
+    /// its start, so each day is compared with the same weekday.  A year-over-year comparison
+    /// then looks 364 days back, and a month-over-month comparison 28 or 35 days back.  Local
+    /// times of day are kept across DST transitions.
+    AlignWeekdays,
+}
+
+fn shift_days<Tz: TimeZone>(dt: &DateTime<Tz>, days: i64) -> DateTime<Tz> {
+    let shifted = if days >= 0 {
+        dt.clone().checked_add_days(Days::new(days as u64))
+    } else {
+        dt.clone().checked_sub_days(Days::new(days.unsigned_abs()))
+    };
+    shifted.expect("Value invalid: shifted interval is out of range")
+}
+
+/// Shift `interval` by `months` calendar months (backwards if negative), according to `policy`.
+///
+/// # Panics
+///
+/// Panics if the shifted interval is out of chrono's representable range.
+pub fn shift_interval<Tz: TimeZone>(interval: &Range<DateTime<Tz>>, months: i32,
+                                    policy: ShiftPolicy) -> Range<DateTime<Tz>> {
+    let start = interval.start.add_months(months);
+    match policy {
+        ShiftPolicy::ShiftEndpoints => start..interval.end.add_months(months),
+        ShiftPolicy::PreserveLength => {
+            let length = interval.end.clone().signed_duration_since(interval.start.clone());
+            start.clone()..start + length
+        },
+        ShiftPolicy::AlignWeekdays => {
+            let calendar_days = start.date_naive()
+                .signed_duration_since(interval.start.date_naive())
+                .num_days();
+            let days = (calendar_days as f64 / 7.0).round() as i64 * 7;
+            shift_days(&interval.start, days)..shift_days(&interval.end, days)
+        },
+    }
+}
+
+/// Returns the interval to compare `interval` with for a year-over-year comparison.
+pub fn same_period_last_year<Tz: TimeZone>(interval: &Range<DateTime<Tz>>,
+                                           policy: ShiftPolicy) -> Range<DateTime<Tz>> {
+    shift_interval(interval, -12, policy)
+}
+
+/// Returns the interval to compare `interval` with for a month-over-month comparison.
+pub fn same_period_previous_month<Tz: TimeZone>(interval: &Range<DateTime<Tz>>,
+                                                policy: ShiftPolicy) -> Range<DateTime<Tz>> {
+    shift_interval(interval, -1, policy)
+}
+
+/// This trait defines functions which check whether two dates fall in the same calendar day,
+/// month, year or ISO week.  DateTimes can be compared with DateTimes in any timezone: the other
+/// DateTime is first converted to self's timezone, so the comparison is made on the calendar
+/// self is in (and `a.same_day(&b)` may differ from `b.same_day(&a)` if they are in different
+/// timezones).  NaiveDates and NaiveDateTimes are compared with values of the same type.
+pub trait CalendarCompare<Rhs = Self> {
+    /// Returns true if self and `other` are on the same date.
+    fn same_day(&self, other: &Rhs) -> bool;
+
+    /// Returns true if self and `other` are in the same month of the same year.
+    fn same_month(&self, other: &Rhs) -> bool;
+
+    /// Returns true if self and `other` are in the same year.
+    fn same_year(&self, other: &Rhs) -> bool;
+
+    /// Returns true if self and `other` are in the same ISO 8601 week (which may span two
+    /// calendar years).
+    fn same_iso_week(&self, other: &Rhs) -> bool;
+}
+
+macro_rules! impl_calendar_compare {
+    ([$($generics:tt)*], $lhs:ty, $rhs:ty, $dates:expr) => {
+        impl<$($generics)*> CalendarCompare<$rhs> for $lhs {
+            fn same_day(&self, other: &$rhs) -> bool {
+                let (a, b): (NaiveDate, NaiveDate) = $dates(self, other);
+                a == b
+            }
+
+            fn same_month(&self, other: &$rhs) -> bool {
+                let (a, b): (NaiveDate, NaiveDate) = $dates(self, other);
+                a.year() == b.year() && a.month() == b.month()
+            }
+
+            fn same_year(&self, other: &$rhs) -> bool {
+                let (a, b): (NaiveDate, NaiveDate) = $dates(self, other);
+                a.year() == b.year()
+            }
+
+            fn same_iso_week(&self, other: &$rhs) -> bool {
+                let (a, b): (NaiveDate, NaiveDate) = $dates(self, other);
+                a.iso_week() == b.iso_week()
+            }
+        }
+    };
+}
+
+impl_calendar_compare!([Tz: TimeZone, Tz2: TimeZone], DateTime<Tz>, DateTime<Tz2>,
+                       |a: &DateTime<Tz>, b: &DateTime<Tz2>| {
+                           (a.date_naive(), b.with_timezone(&a.timezone()).date_naive())
+                       });
+impl_calendar_compare!([], NaiveDate, NaiveDate, |a: &NaiveDate, b: &NaiveDate| (*a, *b));
+impl_calendar_compare!([], NaiveDateTime, NaiveDateTime,
+                       |a: &NaiveDateTime, b: &NaiveDateTime| (a.date(), b.date()));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn range(start: &str, end: &str) -> Range<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(start).unwrap()..DateTime::parse_from_rfc3339(end).unwrap()
+    }
+
+    #[test]
+    fn test_previous_month_shift_endpoints() {
+        let february = range("2018-02-01T00:00:00Z", "2018-03-01T00:00:00Z");
+        assert_eq!(same_period_previous_month(&february, ShiftPolicy::ShiftEndpoints),
+                   range("2018-01-01T00:00:00Z", "2018-02-01T00:00:00Z"));
+
+        let march = range("2018-03-01T00:00:00Z", "2018-04-01T00:00:00Z");
+        assert_eq!(same_period_previous_month(&march, ShiftPolicy::ShiftEndpoints),
+                   range("2018-02-01T00:00:00Z", "2018-03-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_previous_month_preserve_length() {
+        let february = range("2018-02-01T00:00:00Z", "2018-03-01T00:00:00Z");
+        assert_eq!(same_period_previous_month(&february, ShiftPolicy::PreserveLength),
+                   range("2018-01-01T00:00:00Z", "2018-01-29T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_previous_month_partial_month_clamps() {
+        let period = range("2018-03-15T00:00:00Z", "2018-03-31T00:00:00Z");
+        assert_eq!(same_period_previous_month(&period, ShiftPolicy::ShiftEndpoints),
+                   range("2018-02-15T00:00:00Z", "2018-02-28T00:00:00Z"));
+        assert_eq!(same_period_previous_month(&period, ShiftPolicy::PreserveLength),
+                   range("2018-02-15T00:00:00Z", "2018-03-03T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_last_year() {
+        let period = range("2016-02-01T00:00:00+09:00", "2016-03-01T00:00:00+09:00");
+        assert_eq!(same_period_last_year(&period, ShiftPolicy::ShiftEndpoints),
+                   range("2015-02-01T00:00:00+09:00", "2015-03-01T00:00:00+09:00"));
+        assert_eq!(same_period_last_year(&period, ShiftPolicy::PreserveLength),
+                   range("2015-02-01T00:00:00+09:00", "2015-03-02T00:00:00+09:00"));
+    }
+
+    #[test]
+    fn test_last_year_align_weekdays() {
+        // Thursday to Thursday, compared with the Thursday 52 weeks earlier.
+        let week = range("2018-03-15T00:00:00Z", "2018-03-22T00:00:00Z");
+        assert_eq!(same_period_last_year(&week, ShiftPolicy::AlignWeekdays),
+                   range("2017-03-16T00:00:00Z", "2017-03-23T00:00:00Z"));
+
+        // Across a leap day, the shift is still 364 days.
+        let week = range("2016-03-15T00:00:00Z", "2016-03-22T00:00:00Z");
+        assert_eq!(same_period_last_year(&week, ShiftPolicy::AlignWeekdays),
+                   range("2015-03-17T00:00:00Z", "2015-03-24T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_previous_month_align_weekdays() {
+        let march = range("2018-03-01T00:00:00Z", "2018-04-01T00:00:00Z");
+        assert_eq!(same_period_previous_month(&march, ShiftPolicy::AlignWeekdays),
+                   range("2018-02-01T00:00:00Z", "2018-03-04T00:00:00Z"));
+
+        let day = range("2018-03-31T09:00:00+09:00", "2018-03-31T18:00:00+09:00");
+        assert_eq!(same_period_previous_month(&day, ShiftPolicy::AlignWeekdays),
+                   range("2018-03-03T09:00:00+09:00", "2018-03-03T18:00:00+09:00"));
+    }
+
+    #[test]
+    fn test_calendar_compare_timezones() {
+        let tokyo = DateTime::parse_from_rfc3339("2018-04-01T08:00:00+09:00").unwrap();
+        let new_york = DateTime::parse_from_rfc3339("2018-04-01T00:30:00-04:00").unwrap();
+        // Both are on April 1st in Tokyo, but the Tokyo time is on March 31st in New York.
+        assert!(tokyo.same_day(&new_york));
+        assert!(tokyo.same_month(&new_york));
+        assert!(!new_york.same_day(&tokyo));
+        assert!(!new_york.same_month(&tokyo));
+        assert!(new_york.same_year(&tokyo));
+    }
+
+    #[test]
+    fn test_calendar_compare_naive() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert!(date(2018, 3, 15).same_month(&date(2018, 3, 1)));
+        assert!(!date(2018, 3, 15).same_month(&date(2017, 3, 15)));
+        assert!(!date(2018, 3, 15).same_year(&date(2017, 3, 15)));
+        // ISO week 1 of 2019 starts on Monday 2018-12-31.
+        assert!(date(2018, 12, 31).same_iso_week(&date(2019, 1, 6)));
+        assert!(!date(2018, 12, 30).same_iso_week(&date(2018, 12, 31)));
+        let noon = date(2018, 3, 15).and_hms_opt(12, 0, 0).unwrap();
+        assert!(noon.same_day(&date(2018, 3, 15).and_hms_opt(23, 59, 59).unwrap()));
+        assert!(!noon.same_day(&date(2018, 3, 16).and_hms_opt(0, 0, 0).unwrap()));
+    }
+}
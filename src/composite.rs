@@ -0,0 +1,190 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use schedule::Schedule;
+use std::ops::Range;
+
+/// The number of times `Intersection` advances one of its schedules looking for a common
+/// occurrence before giving up, so that schedules which never coincide don't search forever.
+const MAX_INTERSECTION_STEPS: usize = 10_000;
+
+/// The occurrences of either of two schedules (an instant which is an occurrence of both is only
+/// returned once).
+#[derive(Clone, Debug)]
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Schedule, B: Schedule> Union<A, B> {
+    pub fn new(a: A, b: B) -> Union<A, B> {
+        Union { a, b }
+    }
+}
+
+impl<A: Schedule, B: Schedule> Schedule for Union<A, B> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (self.a.next_after(dt), self.b.next_after(dt)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (self.a.prev_before(dt), self.b.prev_before(dt)) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// The occurrences which two schedules have in common.  Each search alternately advances the
+/// schedule which is behind, and gives up (returning `None`) if no common occurrence is found
+/// after 10,000 steps.
+#[derive(Clone, Debug)]
+pub struct Intersection<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Schedule, B: Schedule> Intersection<A, B> {
+    pub fn new(a: A, b: B) -> Intersection<A, B> {
+        Intersection { a, b }
+    }
+}
+
+impl<A: Schedule, B: Schedule> Schedule for Intersection<A, B> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut a = self.a.next_after(dt)?;
+        let mut b = self.b.next_after(dt)?;
+        for _ in 0..MAX_INTERSECTION_STEPS {
+            if a == b {
+                return Some(a);
+            }
+            // Look for the next occurrence at or after the other schedule's occurrence.
+            if a < b {
+                a = self.a.next_after(&b.checked_sub_signed(Duration::nanoseconds(1))?)?;
+            } else {
+                b = self.b.next_after(&a.checked_sub_signed(Duration::nanoseconds(1))?)?;
+            }
+        }
+        None
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut a = self.a.prev_before(dt)?;
+        let mut b = self.b.prev_before(dt)?;
+        for _ in 0..MAX_INTERSECTION_STEPS {
+            if a == b {
+                return Some(a);
+            }
+            if a > b {
+                a = self.a.prev_before(&b.checked_add_signed(Duration::nanoseconds(1))?)?;
+            } else {
+                b = self.b.prev_before(&a.checked_add_signed(Duration::nanoseconds(1))?)?;
+            }
+        }
+        None
+    }
+}
+
+/// The occurrences of a schedule which don't fall in any of a set of excluded half-open
+/// intervals, such as maintenance windows.  An occurrence at the start of an interval is
+/// excluded, and one at its end is not.
+#[derive(Clone, Debug)]
+pub struct Excluding<S> {
+    schedule: S,
+    excluded: Vec<Range<DateTime<Utc>>>,
+}
+
+impl<S: Schedule> Excluding<S> {
+    pub fn new<Tz: TimeZone>(schedule: S, excluded: &[Range<DateTime<Tz>>]) -> Excluding<S> {
+        let excluded = excluded.iter()
+            .map(|interval| interval.start.with_timezone(&Utc)..interval.end.with_timezone(&Utc))
+            .filter(|interval| interval.start < interval.end)
+            .collect();
+        Excluding { schedule, excluded }
+    }
+
+    fn excluded_by(&self, dt: &DateTime<Utc>) -> Option<&Range<DateTime<Utc>>> {
+        self.excluded.iter().find(|interval| interval.contains(dt))
+    }
+}
+
+impl<S: Schedule> Schedule for Excluding<S> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = self.schedule.next_after(dt)?;
+        // Each excluded interval can be skipped at most once, since the search only moves forward.
+        while let Some(interval) = self.excluded_by(&next) {
+            let just_before_end = interval.end.checked_sub_signed(Duration::nanoseconds(1))?;
+            next = self.schedule.next_after(&just_before_end)?;
+        }
+        Some(next)
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut prev = self.schedule.prev_before(dt)?;
+        while let Some(interval) = self.excluded_by(&prev) {
+            prev = self.schedule.prev_before(&interval.start)?;
+        }
+        Some(prev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Weekday};
+    use schedule::{MonthlySchedule, WeeklySchedule};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn nine() -> NaiveTime {
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_union() {
+        let mondays = WeeklySchedule::new(Weekday::Mon, nine(), Utc);
+        let firsts = MonthlySchedule::new(1, nine(), Utc).unwrap();
+        let union = Union::new(mondays, firsts);
+        let occurrences: Vec<_> = union.iter_from(&at("2018-09-20T00:00:00Z")).take(4).collect();
+        // October 1st 2018 is a Monday, and only occurs once.
+        assert_eq!(occurrences, vec![at("2018-09-24T09:00:00Z"), at("2018-10-01T09:00:00Z"),
+                                     at("2018-10-08T09:00:00Z"), at("2018-10-15T09:00:00Z")]);
+        assert_eq!(union.prev_before(&at("2018-09-03T00:00:00Z")),
+                   Some(at("2018-09-01T09:00:00Z")));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let mondays = WeeklySchedule::new(Weekday::Mon, nine(), Utc);
+        let firsts = MonthlySchedule::new(1, nine(), Utc).unwrap();
+        let both = Intersection::new(mondays, firsts);
+        assert_eq!(both.next_after(&at("2018-03-15T00:00:00Z")), Some(at("2018-10-01T09:00:00Z")));
+        assert_eq!(both.prev_before(&at("2018-03-15T00:00:00Z")), Some(at("2018-01-01T09:00:00Z")));
+        let tuesdays = WeeklySchedule::new(Weekday::Tue, nine(), Utc);
+        let never = Intersection::new(WeeklySchedule::new(Weekday::Mon, nine(), Utc), tuesdays);
+        assert_eq!(never.next_after(&at("2018-03-15T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_excluding() {
+        let mondays = WeeklySchedule::new(Weekday::Mon, nine(), Utc);
+        let firsts = MonthlySchedule::new(1, nine(), Utc).unwrap();
+        let maintenance = [
+            at("2018-09-24T00:00:00Z")..at("2018-10-02T00:00:00Z"),
+            at("2018-10-08T00:00:00Z")..at("2018-10-08T09:00:00Z"),
+        ];
+        let schedule = Excluding::new(Union::new(mondays, firsts), &maintenance);
+        let occurrences: Vec<_> = schedule.iter_from(&at("2018-09-20T00:00:00Z"))
+            .take(3)
+            .collect();
+        assert_eq!(occurrences, vec![at("2018-10-08T09:00:00Z"), at("2018-10-15T09:00:00Z"),
+                                     at("2018-10-22T09:00:00Z")]);
+        assert_eq!(schedule.prev_before(&at("2018-10-08T09:00:00Z")),
+                   Some(at("2018-09-17T09:00:00Z")));
+    }
+}
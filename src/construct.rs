@@ -0,0 +1,101 @@
+extern crate chrono;
+
+use chrono::{DateTime, LocalResult, TimeZone};
+use error::ChronoUtilsError;
+use month_calc::{resolve_day, OverflowPolicy};
+
+/// Construct a DateTime from its calendar fields in the given timezone, applying `policy` when
+/// the day does not exist in the given month.  With `OverflowPolicy::ClampToLastDay`, the day is
+/// capped to the last day of the month the same way `MonthCalculations::with_closest_day` does
+/// (so February 30 becomes February 28 or 29); with `OverflowPolicy::Reject`, such a day results
+/// in `ChronoUtilsError::InvalidDay`; and the other policies behave as documented on
+/// `OverflowPolicy`.
+///
+/// Also returns `ChronoUtilsError::InvalidDay` for a day of 0, `OutOfRange` if any other field is
+/// out of range, and `NonexistentLocalTime` or `AmbiguousLocalTime` if the local time falls in a
+/// DST gap or overlap in `tz`.
+#[allow(clippy::too_many_arguments)]
+pub fn from_ymd_hms_clamped<Tz: TimeZone>(year: i32, month: u32, day: u32,
+                                          hour: u32, min: u32, sec: u32, tz: &Tz,
+                                          policy: OverflowPolicy)
+                                          -> Result<DateTime<Tz>, ChronoUtilsError> {
+    // Clamping also caps days past the 31st, the same way `with_closest_day` does.
+    let day = if policy == OverflowPolicy::ClampToLastDay { day.min(31) } else { day };
+    let date = resolve_day(year, month, day, policy)?;
+    let local = date.and_hms_opt(hour, min, sec).ok_or(ChronoUtilsError::OutOfRange)?;
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => Ok(dt),
+        LocalResult::None => Err(ChronoUtilsError::NonexistentLocalTime),
+        LocalResult::Ambiguous(..) => Err(ChronoUtilsError::AmbiguousLocalTime),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, FixedOffset, Timelike, Utc};
+
+    #[test]
+    fn test_clamped_valid_day() {
+        let new_date = from_ymd_hms_clamped(2018, 3, 15, 12, 30, 45, &Utc,
+                                            OverflowPolicy::ClampToLastDay).unwrap();
+        assert_eq!(new_date.year(), 2018);
+        assert_eq!(new_date.month(), 3);
+        assert_eq!(new_date.day(), 15);
+        assert_eq!(new_date.hour(), 12);
+        assert_eq!(new_date.minute(), 30);
+        assert_eq!(new_date.second(), 45);
+    }
+
+    #[test]
+    fn test_clamped_feb30() {
+        let new_date = from_ymd_hms_clamped(2017, 2, 30, 0, 0, 0, &Utc,
+                                            OverflowPolicy::ClampToLastDay).unwrap();
+        assert_eq!(new_date.month(), 2);
+        assert_eq!(new_date.day(), 28);
+    }
+
+    #[test]
+    fn test_clamped_feb30_with_leapyear() {
+        let new_date = from_ymd_hms_clamped(2016, 2, 30, 0, 0, 0, &Utc,
+                                            OverflowPolicy::ClampToLastDay).unwrap();
+        assert_eq!(new_date.month(), 2);
+        assert_eq!(new_date.day(), 29);
+    }
+
+    #[test]
+    fn test_clamped_day_over_31() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let new_date = from_ymd_hms_clamped(2018, 4, 99, 9, 0, 0, &tz,
+                                            OverflowPolicy::ClampToLastDay).unwrap();
+        assert_eq!(new_date.day(), 30);
+        assert_eq!(new_date.hour(), 9);
+        assert_eq!(new_date.offset(), &tz);
+    }
+
+    #[test]
+    fn test_reject_feb30() {
+        assert_eq!(from_ymd_hms_clamped(2016, 2, 30, 0, 0, 0, &Utc, OverflowPolicy::Reject),
+                   Err(ChronoUtilsError::InvalidDay));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let policy = OverflowPolicy::ClampToLastDay;
+        let error = |y, m, d, h| from_ymd_hms_clamped(y, m, d, h, 0, 0, &Utc, policy).unwrap_err();
+        assert_eq!(error(2018, 3, 0, 0), ChronoUtilsError::InvalidDay);
+        assert_eq!(error(2018, 13, 1, 0), ChronoUtilsError::OutOfRange);
+        assert_eq!(error(2018, 13, 31, 0), ChronoUtilsError::OutOfRange);
+        assert_eq!(error(2018, 3, 1, 24), ChronoUtilsError::OutOfRange);
+    }
+
+    #[test]
+    fn test_dst() {
+        use test_tz::Cet;
+        let policy = OverflowPolicy::ClampToLastDay;
+        assert_eq!(from_ymd_hms_clamped(2018, 3, 25, 2, 30, 0, &Cet, policy),
+                   Err(ChronoUtilsError::NonexistentLocalTime));
+        assert_eq!(from_ymd_hms_clamped(2018, 10, 28, 2, 30, 0, &Cet, policy),
+                   Err(ChronoUtilsError::AmbiguousLocalTime));
+    }
+}
@@ -0,0 +1,60 @@
+extern crate chrono;
+
+use chrono::{Datelike, NaiveDate};
+
+/// A day-count convention, which determines the fraction of a year between two dates for
+/// interest calculations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DayCount {
+    /// Actual days divided by 360.
+    Actual360,
+    /// Actual days divided by 365.
+    Actual365Fixed,
+    /// 30/360 (US bond basis): every month counts as 30 days and the year as 360.
+    Thirty360,
+}
+
+impl DayCount {
+    /// Returns the fraction of a year from `start` to `end` under this convention (negative if
+    /// `end` is before `start`).
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate) -> f64 {
+        match *self {
+            DayCount::Actual360 => (end - start).num_days() as f64 / 360.0,
+            DayCount::Actual365Fixed => (end - start).num_days() as f64 / 365.0,
+            DayCount::Thirty360 => {
+                let d1 = start.day().min(30) as i64;
+                let d2 = if d1 == 30 { end.day().min(30) } else { end.day() } as i64;
+                let days = 360 * (end.year() - start.year()) as i64
+                    + 30 * (end.month() as i64 - start.month() as i64)
+                    + (d2 - d1);
+                days as f64 / 360.0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_actual() {
+        let (start, end) = (date(2018, 1, 1), date(2018, 7, 1));
+        assert_eq!(DayCount::Actual360.year_fraction(start, end), 181.0 / 360.0);
+        assert_eq!(DayCount::Actual365Fixed.year_fraction(start, end), 181.0 / 365.0);
+        assert_eq!(DayCount::Actual365Fixed.year_fraction(end, start), -181.0 / 365.0);
+    }
+
+    #[test]
+    fn test_thirty_360() {
+        let day_count = DayCount::Thirty360;
+        assert_eq!(day_count.year_fraction(date(2018, 1, 1), date(2018, 7, 1)), 0.5);
+        assert_eq!(day_count.year_fraction(date(2018, 1, 31), date(2018, 3, 31)), 60.0 / 360.0);
+        assert_eq!(day_count.year_fraction(date(2018, 1, 15), date(2018, 3, 31)), 76.0 / 360.0);
+        assert_eq!(day_count.year_fraction(date(2018, 2, 28), date(2019, 2, 28)), 1.0);
+    }
+}
@@ -0,0 +1,127 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone};
+use std::fmt::Display;
+
+/// When `format_deadline` switches between relative, day-name and absolute descriptions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DeadlineThresholds {
+    /// Deadlines closer than this to now (either way) are described relative to now, e.g.
+    /// "in 3 hours (today 18:00)".
+    pub relative_within: Duration,
+    /// Deadlines on a day at most this many days from today are described by the day's name,
+    /// e.g. "tomorrow 09:00" or, beyond one day, "Fri 09:00".  Later deadlines are described
+    /// by their date, e.g. "on 2024-07-01".
+    pub named_within_days: i64,
+}
+
+impl Default for DeadlineThresholds {
+    fn default() -> DeadlineThresholds {
+        DeadlineThresholds { relative_within: Duration::hours(12), named_within_days: 1 }
+    }
+}
+
+fn day_name(days: i64, weekday: chrono::Weekday) -> String {
+    match days {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        d if d > 1 => weekday.to_string(),
+        _ => format!("last {}", weekday),
+    }
+}
+
+fn humanize(distance: Duration) -> String {
+    let (count, unit) = if distance < Duration::hours(1) {
+        (distance.num_minutes(), "minute")
+    } else {
+        (distance.num_hours(), "hour")
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Describe the deadline `dt` for display as of `now`, with dates and times shown in `tz`:
+///
+/// * within `relative_within` of now: "in 3 hours (today 18:00)" or "20 minutes ago (today
+///   15:40)", rounded down to whole minutes or hours;
+/// * on a day within `named_within_days` of today: "tomorrow 09:00", "Fri 09:00" or
+///   "last Fri 09:00";
+/// * otherwise: "on 2024-07-01".
+///
+/// Weekday names are abbreviated in English.
+pub fn format_deadline<Tz, Tz2, Tz3>(dt: &DateTime<Tz>, now: &DateTime<Tz2>, tz: &Tz3,
+                                     thresholds: &DeadlineThresholds) -> String
+    where Tz: TimeZone, Tz2: TimeZone, Tz3: TimeZone, Tz3::Offset: Display {
+    let local = dt.with_timezone(tz);
+    let days = (local.date_naive() - now.with_timezone(tz).date_naive()).num_days();
+    let time = local.format("%H:%M");
+    let named = format!("{} {}", day_name(days, local.weekday()), time);
+
+    let distance = dt.clone().signed_duration_since(now.clone());
+    if distance.abs() < thresholds.relative_within {
+        if distance.abs() < Duration::minutes(1) {
+            format!("now ({})", named)
+        } else if distance > Duration::zero() {
+            format!("in {} ({})", humanize(distance), named)
+        } else {
+            format!("{} ago ({})", humanize(-distance), named)
+        }
+    } else if days.abs() <= thresholds.named_within_days {
+        named
+    } else {
+        format!("on {}", local.format("%Y-%m-%d"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_format_deadline_relative() {
+        let now = at("2018-03-15T15:00:00Z");
+        let thresholds = DeadlineThresholds::default();
+        assert_eq!(format_deadline(&at("2018-03-15T18:00:00Z"), &now, &Utc, &thresholds),
+                   "in 3 hours (today 18:00)");
+        assert_eq!(format_deadline(&at("2018-03-15T15:01:30Z"), &now, &Utc, &thresholds),
+                   "in 1 minute (today 15:01)");
+        assert_eq!(format_deadline(&at("2018-03-15T14:40:00Z"), &now, &Utc, &thresholds),
+                   "20 minutes ago (today 14:40)");
+        assert_eq!(format_deadline(&at("2018-03-15T15:00:30Z"), &now, &Utc, &thresholds),
+                   "now (today 15:00)");
+        assert_eq!(format_deadline(&at("2018-03-16T01:00:00Z"), &now, &Utc, &thresholds),
+                   "in 10 hours (tomorrow 01:00)");
+    }
+
+    #[test]
+    fn test_format_deadline_named_and_absolute() {
+        let now = at("2018-03-15T15:00:00Z");
+        let thresholds = DeadlineThresholds::default();
+        assert_eq!(format_deadline(&at("2018-03-16T09:00:00Z"), &now, &Utc, &thresholds),
+                   "tomorrow 09:00");
+        assert_eq!(format_deadline(&at("2018-03-14T09:00:00Z"), &now, &Utc, &thresholds),
+                   "yesterday 09:00");
+        assert_eq!(format_deadline(&at("2018-07-01T09:00:00Z"), &now, &Utc, &thresholds),
+                   "on 2018-07-01");
+        let thresholds = DeadlineThresholds { named_within_days: 6, ..thresholds };
+        assert_eq!(format_deadline(&at("2018-03-20T09:00:00Z"), &now, &Utc, &thresholds),
+                   "Tue 09:00");
+        assert_eq!(format_deadline(&at("2018-03-10T09:00:00Z"), &now, &Utc, &thresholds),
+                   "last Sat 09:00");
+    }
+
+    #[test]
+    fn test_format_deadline_in_timezone() {
+        // 23:00 UTC is already the next day in Tokyo.
+        let now = at("2018-03-15T10:00:00Z");
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        assert_eq!(format_deadline(&at("2018-03-15T23:00:00Z"), &now, &tokyo,
+                                   &DeadlineThresholds::default()),
+                   "tomorrow 08:00");
+    }
+}
@@ -0,0 +1,103 @@
+extern crate chrono;
+
+use chrono::{DateTime, TimeZone};
+
+/// Downsample a time series to at most `target_count` points using the
+/// largest-triangle-three-buckets (LTTB) algorithm, which keeps the visual shape of the series
+/// (including its peaks and troughs) far better than taking every n-th point.
+///
+/// `points` must be sorted by time.  The first and last points are always kept.  If
+/// `target_count` is at least the number of points, or is less than 3, all points are returned
+/// unchanged.
+pub fn downsample_lttb<Tz: TimeZone>(points: &[(DateTime<Tz>, f64)],
+                                     target_count: usize) -> Vec<(DateTime<Tz>, f64)> {
+    if target_count >= points.len() || target_count < 3 {
+        return points.to_vec();
+    }
+
+    // Work with x values relative to the first point, so that precision isn't lost converting
+    // large timestamps to f64.
+    let origin = points[0].0.clone();
+    let x = |i: usize| {
+        points[i].0.clone().signed_duration_since(origin.clone()).num_milliseconds() as f64
+    };
+    let y = |i: usize| points[i].1;
+
+    // The first and last points are kept as-is, and the rest are split into equally-sized
+    // buckets, one per remaining output point.
+    let bucket_size = (points.len() - 2) as f64 / (target_count - 2) as f64;
+    let bucket_start = |b: usize| {
+        if b >= target_count - 2 { points.len() - 1 } else { (b as f64 * bucket_size) as usize + 1 }
+    };
+
+    let mut sampled = Vec::with_capacity(target_count);
+    sampled.push(points[0].clone());
+    let mut selected = 0;
+
+    for bucket in 0..target_count - 2 {
+        // The third vertex of each triangle is the average of the next bucket (or the last point,
+        // for the final bucket).
+        let next_start = bucket_start(bucket + 1);
+        let next_end = bucket_start(bucket + 2).min(points.len() - 1).max(next_start + 1);
+        let next_len = (next_end - next_start) as f64;
+        let avg_x = (next_start..next_end).map(&x).sum::<f64>() / next_len;
+        let avg_y = (next_start..next_end).map(&y).sum::<f64>() / next_len;
+
+        let (selected_x, selected_y) = (x(selected), y(selected));
+        let area = |i: usize| {
+            ((selected_x - avg_x) * (y(i) - selected_y)
+                - (selected_x - x(i)) * (avg_y - selected_y)).abs()
+        };
+
+        let mut best = bucket_start(bucket);
+        for i in bucket_start(bucket)..next_start {
+            if area(i) > area(best) {
+                best = i;
+            }
+        }
+        sampled.push(points[best].clone());
+        selected = best;
+    }
+
+    sampled.push(points[points.len() - 1].clone());
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn series(values: &[f64]) -> Vec<(DateTime<Utc>, f64)> {
+        let start = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap()
+            .with_timezone(&Utc);
+        values.iter().enumerate()
+            .map(|(i, v)| (start + Duration::minutes(i as i64), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_downsample_lttb_keeps_small_series() {
+        let points = series(&[1.0, 2.0, 3.0]);
+        assert_eq!(downsample_lttb(&points, 5), points);
+        assert_eq!(downsample_lttb(&points, 2), points);
+    }
+
+    #[test]
+    fn test_downsample_lttb_keeps_ends() {
+        let points = series(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        let sampled = downsample_lttb(&points, 4);
+        assert_eq!(sampled.len(), 4);
+        assert_eq!(sampled[0], points[0]);
+        assert_eq!(sampled[3], points[9]);
+        assert!(sampled.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_downsample_lttb_keeps_extremes() {
+        let points = series(&[0.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, -10.0, 0.0, 0.0, 0.0, 0.0]);
+        let sampled = downsample_lttb(&points, 4);
+        let values: Vec<f64> = sampled.iter().map(|p| p.1).collect();
+        assert_eq!(values, vec![0.0, 10.0, -10.0, 0.0]);
+    }
+}
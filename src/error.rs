@@ -0,0 +1,29 @@
+use std::error::Error;
+use std::fmt;
+
+/// The errors returned by the fallible (`try_*`) functions in this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChronoUtilsError {
+    /// The result is outside the range chrono can represent, or (for DateTimes, other than from
+    /// `try_add_months`) its local time doesn't exist in the timezone.
+    OutOfRange,
+    /// The day doesn't exist in the month (such as February 30th), or is 0 or over 31.
+    InvalidDay,
+    /// A DateTime's resulting local time falls in a DST gap, so doesn't exist in its timezone.
+    NonexistentLocalTime,
+    /// A DateTime's resulting local time falls in a DST overlap, so exists twice in its timezone.
+    AmbiguousLocalTime,
+}
+
+impl fmt::Display for ChronoUtilsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChronoUtilsError::OutOfRange => write!(f, "date or time out of range"),
+            ChronoUtilsError::InvalidDay => write!(f, "no such day in the month"),
+            ChronoUtilsError::NonexistentLocalTime => write!(f, "local time in a DST gap"),
+            ChronoUtilsError::AmbiguousLocalTime => write!(f, "local time in a DST overlap"),
+        }
+    }
+}
+
+impl Error for ChronoUtilsError {}
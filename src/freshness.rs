@@ -0,0 +1,103 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, TimeZone};
+use std::fmt;
+
+/// How fresh a piece of data is, as classified by `freshness`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Freshness {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+impl fmt::Display for Freshness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Freshness::Fresh => "fresh",
+            Freshness::Stale => "stale",
+            Freshness::Expired => "expired",
+        })
+    }
+}
+
+/// The ages at which data becomes stale and expired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FreshnessThresholds {
+    /// Data this old or older is stale.
+    pub stale_after: Duration,
+    /// Data this old or older is expired.
+    pub expired_after: Duration,
+}
+
+/// Classify data produced at `produced_at` as of `now`.  Data produced after `now` (e.g. because
+/// of clock skew between the producer and this machine) is considered fresh.
+pub fn freshness<Tz, Tz2>(now: &DateTime<Tz>, produced_at: &DateTime<Tz2>,
+                          thresholds: &FreshnessThresholds) -> Freshness
+    where Tz: TimeZone, Tz2: TimeZone {
+    let age = now.clone().signed_duration_since(produced_at.clone());
+    if age >= thresholds.expired_after {
+        Freshness::Expired
+    } else if age >= thresholds.stale_after {
+        Freshness::Stale
+    } else {
+        Freshness::Fresh
+    }
+}
+
+fn humanize_age(age: Duration) -> String {
+    let (count, unit) = if age < Duration::minutes(1) {
+        (age.num_seconds(), "second")
+    } else if age < Duration::hours(1) {
+        (age.num_minutes(), "minute")
+    } else if age < Duration::days(1) {
+        (age.num_hours(), "hour")
+    } else {
+        (age.num_days(), "day")
+    };
+    format!("{} {}{} old", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Classify data as `freshness` does, and describe the result for display, e.g.
+/// "stale (3 hours old)".  Ages are rounded down to the largest whole unit (seconds, minutes,
+/// hours or days).
+pub fn describe_freshness<Tz, Tz2>(now: &DateTime<Tz>, produced_at: &DateTime<Tz2>,
+                                   thresholds: &FreshnessThresholds) -> String
+    where Tz: TimeZone, Tz2: TimeZone {
+    let age = now.clone().signed_duration_since(produced_at.clone()).max(Duration::zero());
+    format!("{} ({})", freshness(now, produced_at, thresholds), humanize_age(age))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: FreshnessThresholds = FreshnessThresholds {
+        stale_after: Duration::minutes(10),
+        expired_after: Duration::days(1),
+    };
+
+    fn at(s: &str) -> DateTime<chrono::FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_freshness() {
+        let now = at("2018-03-15T12:00:00Z");
+        assert_eq!(freshness(&now, &at("2018-03-15T11:55:00Z"), &THRESHOLDS), Freshness::Fresh);
+        assert_eq!(freshness(&now, &at("2018-03-15T11:50:00Z"), &THRESHOLDS), Freshness::Stale);
+        assert_eq!(freshness(&now, &at("2018-03-14T12:00:00Z"), &THRESHOLDS), Freshness::Expired);
+        assert_eq!(freshness(&now, &at("2018-03-15T12:00:05Z"), &THRESHOLDS), Freshness::Fresh);
+    }
+
+    #[test]
+    fn test_describe_freshness() {
+        let now = at("2018-03-15T12:00:00Z");
+        let describe = |produced_at| describe_freshness(&now, &at(produced_at), &THRESHOLDS);
+        assert_eq!(describe("2018-03-15T11:59:59Z"), "fresh (1 second old)");
+        assert_eq!(describe("2018-03-15T11:55:30Z"), "fresh (4 minutes old)");
+        assert_eq!(describe("2018-03-15T09:00:00Z"), "stale (3 hours old)");
+        assert_eq!(describe("2018-03-12T11:00:00Z"), "expired (3 days old)");
+        assert_eq!(describe("2018-03-15T12:00:05Z"), "fresh (0 seconds old)");
+    }
+}
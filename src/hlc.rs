@@ -0,0 +1,202 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, Utc};
+use clock::Clock;
+use std::fmt;
+
+/// A hybrid logical clock (HLC) timestamp: a wall-clock time plus a logical counter which
+/// orders events that share the same wall time.  HLC timestamps order causally related events
+/// correctly across machines, even when their clocks are slightly out of sync, while staying
+/// close to real time.
+///
+/// Timestamps are ordered by wall time, then by logical counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HlcTimestamp {
+    pub wall: DateTime<Utc>,
+    pub logical: u32,
+}
+
+const SIGN_BIT: u64 = 1 << 63;
+
+impl HlcTimestamp {
+    /// Encode as a compact, fixed-width 25-character string (16 hex digits of wall-clock
+    /// nanoseconds, a '-', and 8 hex digits of the logical counter).  Encoded strings sort in the
+    /// same order as the timestamps themselves.  Returns `None` if the wall time is outside the
+    /// range representable in nanoseconds (roughly the years 1677 to 2262).
+    pub fn encode(&self) -> Option<String> {
+        // Flip the sign bit so that negative (pre-1970) times sort before positive ones.
+        let nanos = self.wall.timestamp_nanos_opt()? as u64 ^ SIGN_BIT;
+        Some(format!("{:016x}-{:08x}", nanos, self.logical))
+    }
+
+    /// Decode a string produced by `encode`.
+    pub fn decode(s: &str) -> Option<HlcTimestamp> {
+        if s.len() != 25 || s.as_bytes()[16] != b'-' {
+            return None;
+        }
+        let nanos = u64::from_str_radix(s.get(..16)?, 16).ok()? ^ SIGN_BIT;
+        let logical = u32::from_str_radix(s.get(17..)?, 16).ok()?;
+        Some(HlcTimestamp { wall: DateTime::from_timestamp_nanos(nanos as i64), logical })
+    }
+
+    /// Returns the smallest timestamp greater than self: the next logical count at the same wall
+    /// time or, if the counter is exhausted, the next nanosecond with the counter reset.
+    fn successor(&self) -> HlcTimestamp {
+        match self.logical.checked_add(1) {
+            Some(logical) => HlcTimestamp { wall: self.wall, logical },
+            None => {
+                let wall = self.wall.checked_add_signed(Duration::nanoseconds(1))
+                    .expect("HLC wall time is out of range");
+                HlcTimestamp { wall, logical: 0 }
+            },
+        }
+    }
+}
+
+impl fmt::Display for HlcTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}+{}", self.wall.to_rfc3339(), self.logical)
+    }
+}
+
+/// A hybrid logical clock, generating HlcTimestamps from a physical `Clock`.  Every timestamp
+/// it returns is greater than all timestamps it has returned or received before, even if the
+/// physical clock goes backwards.
+#[derive(Clone, Debug)]
+pub struct HybridLogicalClock<C: Clock> {
+    clock: C,
+    last: HlcTimestamp,
+}
+
+impl<C: Clock> HybridLogicalClock<C> {
+    pub fn new(clock: C) -> HybridLogicalClock<C> {
+        let last = HlcTimestamp { wall: DateTime::<Utc>::MIN_UTC, logical: 0 };
+        HybridLogicalClock { clock, last }
+    }
+
+    /// Returns a timestamp for a local or send event.  If the logical counter would overflow, the
+    /// wall time is moved forward by a nanosecond instead.
+    pub fn now(&mut self) -> HlcTimestamp {
+        let physical = self.clock.now();
+        self.last = if physical > self.last.wall {
+            HlcTimestamp { wall: physical, logical: 0 }
+        } else {
+            self.last.successor()
+        };
+        self.last
+    }
+
+    /// Merge a timestamp received from another node, and return the timestamp for the receive
+    /// event, which is greater than both `received` and every timestamp issued so far.
+    pub fn update(&mut self, received: &HlcTimestamp) -> HlcTimestamp {
+        let physical = self.clock.now();
+        let latest = self.last.max(*received);
+        self.last = if physical > latest.wall {
+            HlcTimestamp { wall: physical, logical: 0 }
+        } else {
+            latest.successor()
+        };
+        self.last
+    }
+
+    /// Returns the last timestamp issued.
+    pub fn last(&self) -> HlcTimestamp {
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::ManualClock;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_now_follows_physical_clock() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:00Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        assert_eq!(hlc.now(), HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 0 });
+        assert_eq!(hlc.now(), HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 1 });
+        clock.advance(Duration::seconds(1));
+        assert_eq!(hlc.now(), HlcTimestamp { wall: at("2018-03-15T12:00:01Z"), logical: 0 });
+    }
+
+    #[test]
+    fn test_now_monotonic_when_clock_goes_back() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:00Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        let first = hlc.now();
+        clock.advance(Duration::seconds(-5));
+        let second = hlc.now();
+        assert!(second > first);
+        assert_eq!(second, HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 1 });
+    }
+
+    #[test]
+    fn test_update_from_node_ahead() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:00Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        hlc.now();
+        let received = HlcTimestamp { wall: at("2018-03-15T12:00:03Z"), logical: 4 };
+        assert_eq!(hlc.update(&received), HlcTimestamp { wall: received.wall, logical: 5 });
+        assert_eq!(hlc.now(), HlcTimestamp { wall: received.wall, logical: 6 });
+    }
+
+    #[test]
+    fn test_update_same_wall() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:00Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        hlc.now();
+        hlc.now();
+        let received = HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 7 };
+        assert_eq!(hlc.update(&received).logical, 8);
+        let received = HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 2 };
+        assert_eq!(hlc.update(&received).logical, 9);
+    }
+
+    #[test]
+    fn test_update_from_node_behind() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:10Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        let received = HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: 3 };
+        let expected = HlcTimestamp { wall: at("2018-03-15T12:00:10Z"), logical: 0 };
+        assert_eq!(hlc.update(&received), expected);
+    }
+
+    #[test]
+    fn test_logical_overflow() {
+        let clock = ManualClock::new(at("2018-03-15T12:00:00Z"));
+        let mut hlc = HybridLogicalClock::new(&clock);
+        let received = HlcTimestamp { wall: at("2018-03-15T12:00:00Z"), logical: u32::MAX };
+        let next = HlcTimestamp { wall: at("2018-03-15T12:00:00.000000001Z"), logical: 0 };
+        assert_eq!(hlc.update(&received), next);
+        assert_eq!(hlc.now(), HlcTimestamp { logical: 1, ..next });
+
+        let mut hlc = HybridLogicalClock::new(&clock);
+        hlc.update(&HlcTimestamp { logical: u32::MAX - 1, ..received });
+        assert_eq!(hlc.now(), next);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let timestamp = HlcTimestamp { wall: at("2018-03-15T12:00:00.123456789Z"), logical: 42 };
+        let encoded = timestamp.encode().unwrap();
+        assert_eq!(encoded.len(), 25);
+        assert_eq!(HlcTimestamp::decode(&encoded), Some(timestamp));
+
+        let early = HlcTimestamp { wall: at("1960-01-01T00:00:00Z"), logical: 0 };
+        assert_eq!(HlcTimestamp::decode(&early.encode().unwrap()), Some(early));
+        assert!(early.encode().unwrap() < encoded);
+        assert!(encoded < HlcTimestamp { logical: 43, ..timestamp }.encode().unwrap());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(HlcTimestamp::decode(""), None);
+        assert_eq!(HlcTimestamp::decode("0123456789abcdef_00000000"), None);
+        assert_eq!(HlcTimestamp::decode("0123456789abcdeg-00000000"), None);
+    }
+}
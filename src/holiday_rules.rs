@@ -0,0 +1,423 @@
+extern crate chrono;
+
+use chrono::{Datelike, Days, Month, NaiveDate, Weekday};
+use holidays::{Holiday, HolidayList};
+use month_calc::nth_weekday;
+use week_calc::nth_weekday_of_year;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A rule giving the date of a recurring holiday in any year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HolidayRule {
+    /// The same month and day every year, e.g. "Dec 25".  If `observed` is true, a holiday falling
+    /// on a Saturday is observed on the Friday before, and one falling on a Sunday on the Monday
+    /// after ("Dec 25 observed").
+    Fixed { month: u32, day: u32, observed: bool },
+    /// The `nth` (from 1) `weekday` of the month, or counting from the end of the month if `nth`
+    /// is negative, so -1 is the last, e.g. "3rd Monday of January" or "last Monday of May".
+    NthWeekday { nth: i32, weekday: Weekday, month: u32 },
+    /// The `nth` `weekday` of the year, counting from the end of the year if `nth` is negative,
+    /// e.g. "2nd Sunday of the year" or "last Friday of the year".
+    NthWeekdayOfYear { nth: i32, weekday: Weekday },
+    /// A number of days after (or before, if negative) Western Easter Sunday, e.g. "Easter +1"
+    /// for Easter Monday or "Easter -2" for Good Friday.
+    Easter { offset: i32 },
+    /// A number of days after (or before, if negative) Eastern Orthodox Easter Sunday, e.g.
+    /// "Orthodox Easter +50" for Orthodox Whit Monday.
+    OrthodoxEaster { offset: i32 },
+}
+
+/// Returns the date of (Western) Easter Sunday in the Gregorian calendar, or `None` if the year
+/// is out of range.
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    // The anonymous Gregorian algorithm (Meeus/Jones/Butcher).
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let h = (19 * a + b - b / 4 - (8 * b + 13) / 25 + 15).rem_euclid(30);
+    let l = (32 + 2 * (b % 4) + 2 * (c / 4) - h - c % 4).rem_euclid(7);
+    let m = (a + 11 * h + 19 * l) / 433;
+    let month = (h + l - 7 * m + 90) / 25;
+    let day = (h + l - 7 * m + 33 * month + 19).rem_euclid(32);
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Returns the date of Eastern Orthodox Easter Sunday, which is calculated in the Julian
+/// calendar, converted to the Gregorian calendar, or `None` if the year is out of range.
+pub fn orthodox_easter_sunday(year: i32) -> Option<NaiveDate> {
+    // Meeus' Julian algorithm.
+    let a = year.rem_euclid(4);
+    let b = year.rem_euclid(7);
+    let c = year.rem_euclid(19);
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    let month = (d + e + 114) / 31;
+    let day = (d + e + 114) % 31 + 1;
+    julian_to_gregorian(year, month as u32, day as u32)
+}
+
+/// Returns the Gregorian date of the given date in the (proleptic) Julian calendar, or `None` if
+/// the date is invalid or out of range.  In the Julian calendar every fourth year is a leap year,
+/// so February 29th exists in e.g. 1900.
+pub fn julian_to_gregorian(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    let days_in_month = match month {
+        2 if year.rem_euclid(4) == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if day == 0 || day > days_in_month {
+        return None;
+    }
+    // The Julian Day Number of the date, counting years from March so that leap days come last.
+    let shift = if month <= 2 { 1 } else { 0 };
+    let y = year as i64 + 4800 - shift;
+    let m = month as i64 + 12 * shift - 3;
+    let jdn = day as i64 + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4) - 32083;
+    // 0001-01-01 (Gregorian) is Julian Day Number 1721426.
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(jdn - 1721425).ok()?)
+}
+
+fn offset_days(date: NaiveDate, offset: i32) -> Option<NaiveDate> {
+    if offset >= 0 {
+        date.checked_add_days(Days::new(offset as u64))
+    } else {
+        date.checked_sub_days(Days::new(-(offset as i64) as u64))
+    }
+}
+
+impl HolidayRule {
+    /// Returns the date of the holiday in `year` (after applying any observance shift), or `None`
+    /// if it doesn't occur that year (e.g. a 5th Monday the month doesn't have, or February 29th
+    /// in a non-leap year).
+    pub fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            HolidayRule::Fixed { month, day, observed } => {
+                let date = NaiveDate::from_ymd_opt(year, month, day)?;
+                match date.weekday() {
+                    Weekday::Sat if observed => date.pred_opt(),
+                    Weekday::Sun if observed => date.succ_opt(),
+                    _ => Some(date),
+                }
+            },
+            HolidayRule::NthWeekday { nth, weekday, month } => {
+                nth_weekday(year, month, weekday, nth)
+            },
+            HolidayRule::NthWeekdayOfYear { nth, weekday } => {
+                nth_weekday_of_year(year, weekday, nth)
+            },
+            HolidayRule::Easter { offset } => offset_days(easter_sunday(year)?, offset),
+            HolidayRule::OrthodoxEaster { offset } => {
+                offset_days(orthodox_easter_sunday(year)?, offset)
+            },
+        }
+    }
+
+    /// Returns true if the rule can be written in a form `parse_holiday_rule` accepts: its month
+    /// is 1 to 12, a fixed day exists in that month (in a leap year), and `nth` isn't 0.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            HolidayRule::Fixed { month, day, .. } => {
+                NaiveDate::from_ymd_opt(2000, month, day).is_some()
+            },
+            HolidayRule::NthWeekday { nth, month, .. } => {
+                nth != 0 && nth != i32::MIN && (1..=12).contains(&month)
+            },
+            HolidayRule::NthWeekdayOfYear { nth, .. } => nth != 0 && nth != i32::MIN,
+            HolidayRule::Easter { .. } | HolidayRule::OrthodoxEaster { .. } => true,
+        }
+    }
+}
+
+fn month_name(month: u32) -> &'static str {
+    Month::try_from(month as u8).map(|m| m.name()).unwrap_or("?")
+}
+
+fn write_nth_weekday(f: &mut fmt::Formatter, nth: i32, weekday: Weekday) -> fmt::Result {
+    match nth {
+        -1 => f.write_str("last")?,
+        n if n > 0 => write!(f, "{}{}", n, ordinal_suffix(n))?,
+        n => write!(f, "{}{} last", -n, ordinal_suffix(-n))?,
+    }
+    f.write_str(match weekday {
+        Weekday::Mon => " Monday",
+        Weekday::Tue => " Tuesday",
+        Weekday::Wed => " Wednesday",
+        Weekday::Thu => " Thursday",
+        Weekday::Fri => " Friday",
+        Weekday::Sat => " Saturday",
+        Weekday::Sun => " Sunday",
+    })
+}
+
+impl fmt::Display for HolidayRule {
+    /// Writes the rule in the form `parse_holiday_rule` accepts.  Rules which aren't valid (see
+    /// `HolidayRule::is_valid`) are written in their Debug form instead, which doesn't parse.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.is_valid() {
+            return write!(f, "{:?}", self);
+        }
+        match *self {
+            HolidayRule::Fixed { month, day, observed } => {
+                write!(f, "{} {}", &month_name(month)[..3], day)?;
+                if observed { f.write_str(" observed") } else { Ok(()) }
+            },
+            HolidayRule::NthWeekday { nth, weekday, month } => {
+                write_nth_weekday(f, nth, weekday)?;
+                write!(f, " of {}", month_name(month))
+            },
+            HolidayRule::NthWeekdayOfYear { nth, weekday } => {
+                write_nth_weekday(f, nth, weekday)?;
+                f.write_str(" of the year")
+            },
+            HolidayRule::Easter { offset: 0 } => f.write_str("Easter"),
+            HolidayRule::Easter { offset } => write!(f, "Easter {:+}", offset),
+            HolidayRule::OrthodoxEaster { offset: 0 } => f.write_str("Orthodox Easter"),
+            HolidayRule::OrthodoxEaster { offset } => write!(f, "Orthodox Easter {:+}", offset),
+        }
+    }
+}
+
+fn ordinal_suffix(n: i32) -> &'static str {
+    match n % 100 {
+        11..=13 => "th",
+        _ => match n % 10 {
+            1 => "st",
+            2 => "nd",
+            3 => "rd",
+            _ => "th",
+        },
+    }
+}
+
+fn parse_nth(s: &str) -> Option<i32> {
+    match s {
+        "first" => Some(1),
+        "second" => Some(2),
+        "third" => Some(3),
+        "fourth" => Some(4),
+        "fifth" => Some(5),
+        "last" => Some(-1),
+        _ => {
+            let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            let suffix = &s[digits.len()..];
+            let n: i32 = digits.parse().ok()?;
+            if n > 0 && suffix == ordinal_suffix(n) { Some(n) } else { None }
+        },
+    }
+}
+
+/// Parse a holiday rule as written by `HolidayRule`'s Display implementation, ignoring case and
+/// extra whitespace.  The accepted forms are:
+///
+/// * a fixed date: "Dec 25", "December 25", optionally followed by "observed";
+/// * a weekday of the month: "3rd Monday of January", "first Monday of Sep", "last Monday of May"
+///   or "2nd last Friday of Nov";
+/// * a weekday of the year: "2nd Sunday of the year" or "last Friday of the year";
+/// * a day relative to Easter Sunday: "Easter", "Easter +1", "Easter -2";
+/// * a day relative to Orthodox Easter Sunday: "Orthodox Easter", "Orthodox Easter -2".
+///
+/// Returns `None` if the string isn't a valid rule.
+pub fn parse_holiday_rule(s: &str) -> Option<HolidayRule> {
+    let lower = s.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    match words.as_slice() {
+        ["easter"] => Some(HolidayRule::Easter { offset: 0 }),
+        ["easter", offset] => {
+            let offset = offset.strip_prefix('+').unwrap_or(offset);
+            Some(HolidayRule::Easter { offset: offset.parse().ok()? })
+        },
+        ["orthodox", "easter"] => Some(HolidayRule::OrthodoxEaster { offset: 0 }),
+        ["orthodox", "easter", offset] => {
+            let offset = offset.strip_prefix('+').unwrap_or(offset);
+            Some(HolidayRule::OrthodoxEaster { offset: offset.parse().ok()? })
+        },
+        [month, day] | [month, day, "observed"] => {
+            let month = month.parse::<Month>().ok()?.number_from_month();
+            let day: u32 = day.parse().ok()?;
+            // Check the day against a leap year, so that "Feb 29" is accepted.
+            NaiveDate::from_ymd_opt(2000, month, day)?;
+            Some(HolidayRule::Fixed { month, day, observed: words.len() == 3 })
+        },
+        [nth, weekday, "of", "the", "year"] => Some(HolidayRule::NthWeekdayOfYear {
+            nth: parse_nth(nth)?,
+            weekday: weekday.parse().ok()?,
+        }),
+        [nth, "last", weekday, "of", "the", "year"] if *nth != "last" => {
+            Some(HolidayRule::NthWeekdayOfYear {
+                nth: -parse_nth(nth)?,
+                weekday: weekday.parse().ok()?,
+            })
+        },
+        [nth, weekday, "of", month] => Some(HolidayRule::NthWeekday {
+            nth: parse_nth(nth)?,
+            weekday: weekday.parse().ok()?,
+            month: month.parse::<Month>().ok()?.number_from_month(),
+        }),
+        [nth, "last", weekday, "of", month] if *nth != "last" => Some(HolidayRule::NthWeekday {
+            nth: -parse_nth(nth)?,
+            weekday: weekday.parse().ok()?,
+            month: month.parse::<Month>().ok()?.number_from_month(),
+        }),
+        _ => None,
+    }
+}
+
+/// A set of recurring holidays defined by rules, which can be compiled into a `HolidayList` for
+/// any range of years.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HolidayRules {
+    rules: Vec<(HolidayRule, Holiday)>,
+}
+
+impl HolidayRules {
+    pub fn new() -> HolidayRules {
+        HolidayRules::default()
+    }
+
+    /// Add a holiday falling on the date given by `rule`.
+    pub fn add(&mut self, rule: HolidayRule, holiday: Holiday) -> &mut HolidayRules {
+        self.rules.push((rule, holiday));
+        self
+    }
+
+    /// Iterate over the rules in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &(HolidayRule, Holiday)> {
+        self.rules.iter()
+    }
+
+    /// Returns a HolidayList with the dates of every holiday in `years`.  If two holidays fall on
+    /// the same date, the one added last wins.
+    pub fn to_calendar(&self, years: RangeInclusive<i32>) -> HolidayList {
+        let mut list = HolidayList::new();
+        for year in years {
+            for (rule, holiday) in &self.rules {
+                if let Some(date) = rule.date_in(year) {
+                    list.add(date, holiday.clone());
+                }
+            }
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holidays::{HolidayCalendar, HolidayCategory};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(easter_sunday(2018), Some(date(2018, 4, 1)));
+        assert_eq!(easter_sunday(2019), Some(date(2019, 4, 21)));
+        assert_eq!(easter_sunday(2008), Some(date(2008, 3, 23)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+        assert_eq!(easter_sunday(1900), Some(date(1900, 4, 15)));
+    }
+
+    #[test]
+    fn test_orthodox_easter_sunday() {
+        assert_eq!(orthodox_easter_sunday(2018), Some(date(2018, 4, 8)));
+        assert_eq!(orthodox_easter_sunday(2019), Some(date(2019, 4, 28)));
+        assert_eq!(orthodox_easter_sunday(2021), Some(date(2021, 5, 2)));
+        assert_eq!(orthodox_easter_sunday(2024), Some(date(2024, 5, 5)));
+        // Some years share Easter with the Western churches.
+        assert_eq!(orthodox_easter_sunday(2017), easter_sunday(2017));
+        assert_eq!(orthodox_easter_sunday(1900), Some(date(1900, 4, 22)));
+    }
+
+    #[test]
+    fn test_julian_to_gregorian() {
+        // The Julian calendar is 13 days behind from 1900 to 2099, and 10 days behind in 1582.
+        assert_eq!(julian_to_gregorian(2018, 12, 25), Some(date(2019, 1, 7)));
+        assert_eq!(julian_to_gregorian(1900, 2, 29), Some(date(1900, 3, 13)));
+        assert_eq!(julian_to_gregorian(1582, 10, 5), Some(date(1582, 10, 15)));
+        assert_eq!(julian_to_gregorian(2018, 2, 29), None);
+        assert_eq!(julian_to_gregorian(2018, 13, 1), None);
+    }
+
+    #[test]
+    fn test_date_in() {
+        let mlk = HolidayRule::NthWeekday { nth: 3, weekday: Weekday::Mon, month: 1 };
+        assert_eq!(mlk.date_in(2018), Some(date(2018, 1, 15)));
+        let memorial = HolidayRule::NthWeekday { nth: -1, weekday: Weekday::Mon, month: 5 };
+        assert_eq!(memorial.date_in(2018), Some(date(2018, 5, 28)));
+        let fifth = HolidayRule::NthWeekday { nth: 5, weekday: Weekday::Mon, month: 2 };
+        assert_eq!(fifth.date_in(2018), None);
+        let christmas = HolidayRule::Fixed { month: 12, day: 25, observed: true };
+        assert_eq!(christmas.date_in(2016), Some(date(2016, 12, 26)));
+        assert_eq!(christmas.date_in(2021), Some(date(2021, 12, 24)));
+        assert_eq!(christmas.date_in(2018), Some(date(2018, 12, 25)));
+        assert_eq!(HolidayRule::Easter { offset: -2 }.date_in(2018), Some(date(2018, 3, 30)));
+        let orthodox_good_friday = HolidayRule::OrthodoxEaster { offset: -2 };
+        assert_eq!(orthodox_good_friday.date_in(2018), Some(date(2018, 4, 6)));
+    }
+
+    #[test]
+    fn test_parse_holiday_rule() {
+        assert_eq!(parse_holiday_rule("3rd Monday of January"),
+                   Some(HolidayRule::NthWeekday { nth: 3, weekday: Weekday::Mon, month: 1 }));
+        assert_eq!(parse_holiday_rule("last mon of may"),
+                   Some(HolidayRule::NthWeekday { nth: -1, weekday: Weekday::Mon, month: 5 }));
+        assert_eq!(parse_holiday_rule("2nd last Friday of Nov"),
+                   Some(HolidayRule::NthWeekday { nth: -2, weekday: Weekday::Fri, month: 11 }));
+        assert_eq!(parse_holiday_rule("last Friday of the year"),
+                   Some(HolidayRule::NthWeekdayOfYear { nth: -1, weekday: Weekday::Fri }));
+        assert_eq!(parse_holiday_rule("Dec 25 observed"),
+                   Some(HolidayRule::Fixed { month: 12, day: 25, observed: true }));
+        assert_eq!(parse_holiday_rule("February 29"),
+                   Some(HolidayRule::Fixed { month: 2, day: 29, observed: false }));
+        assert_eq!(parse_holiday_rule("Easter +1"), Some(HolidayRule::Easter { offset: 1 }));
+        assert_eq!(parse_holiday_rule("easter"), Some(HolidayRule::Easter { offset: 0 }));
+        assert_eq!(parse_holiday_rule("Orthodox Easter +1"),
+                   Some(HolidayRule::OrthodoxEaster { offset: 1 }));
+        assert_eq!(parse_holiday_rule("Feb 30"), None);
+        assert_eq!(parse_holiday_rule("3th Monday of January"), None);
+        assert_eq!(parse_holiday_rule("Easter Monday"), None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for s in &["Dec 25 observed", "Jan 1", "3rd Monday of January", "last Monday of May",
+                   "2nd last Friday of November", "22nd Sunday of March", "Easter",
+                   "2nd Sunday of the year", "3rd last Friday of the year",
+                   "Easter +1", "Easter -2", "Orthodox Easter", "Orthodox Easter +50"] {
+            let rule = parse_holiday_rule(s).unwrap();
+            assert_eq!(rule.to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn test_display_invalid() {
+        let rule = HolidayRule::Fixed { month: 13, day: 1, observed: false };
+        assert!(!rule.is_valid());
+        assert_eq!(rule.to_string(), "Fixed { month: 13, day: 1, observed: false }");
+        let rule = HolidayRule::NthWeekday { nth: 0, weekday: Weekday::Mon, month: 5 };
+        assert!(!rule.is_valid());
+        assert_eq!(parse_holiday_rule(&rule.to_string()), None);
+        assert!(HolidayRule::Fixed { month: 2, day: 29, observed: true }.is_valid());
+    }
+
+    #[test]
+    fn test_to_calendar() {
+        let mut rules = HolidayRules::new();
+        rules
+            .add(parse_holiday_rule("Jan 1 observed").unwrap(),
+                 Holiday::new("New Year's Day", HolidayCategory::Public))
+            .add(parse_holiday_rule("Easter +1").unwrap(),
+                 Holiday::new("Easter Monday", HolidayCategory::Bank));
+        let calendar = rules.to_calendar(2017..=2018);
+        assert_eq!(calendar.holiday_name(date(2017, 1, 2)), Some("New Year's Day"));
+        assert_eq!(calendar.holiday_name(date(2018, 1, 1)), Some("New Year's Day"));
+        assert_eq!(calendar.holiday_name(date(2017, 4, 17)), Some("Easter Monday"));
+        assert_eq!(calendar.holiday_name(date(2018, 4, 2)), Some("Easter Monday"));
+        assert_eq!(calendar.iter().count(), 4);
+    }
+}
@@ -0,0 +1,129 @@
+extern crate chrono;
+
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// The kind of a holiday, which determines whether it is a day off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HolidayCategory {
+    /// A public (national or regional) holiday; a day off for most workers.
+    Public,
+    /// A bank holiday; banks and markets are closed.
+    Bank,
+    /// An observance, which is marked on the calendar but is normally a working day.
+    Observance,
+}
+
+impl HolidayCategory {
+    /// Returns true if holidays of this category are non-working days.
+    pub fn is_day_off(&self) -> bool {
+        *self != HolidayCategory::Observance
+    }
+}
+
+/// A named holiday.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Holiday {
+    pub name: String,
+    pub category: HolidayCategory,
+}
+
+impl Holiday {
+    pub fn new<S: Into<String>>(name: S, category: HolidayCategory) -> Holiday {
+        Holiday { name: name.into(), category }
+    }
+}
+
+/// This trait defines a source of holidays.  Implementors only need to provide `holiday`; the
+/// other functions are derived from it.
+pub trait HolidayCalendar {
+    /// Returns the holiday falling on `date`, if any.
+    fn holiday(&self, date: NaiveDate) -> Option<&Holiday>;
+
+    /// Returns true if `date` is a non-working holiday (observances are not counted).
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holiday(date).is_some_and(|h| h.category.is_day_off())
+    }
+
+    /// Returns the name of the holiday falling on `date`, if any, so UIs can show why a date is
+    /// marked.
+    fn holiday_name(&self, date: NaiveDate) -> Option<&str> {
+        self.holiday(date).map(|h| h.name.as_str())
+    }
+
+    /// Returns the category of the holiday falling on `date`, if any.
+    fn holiday_category(&self, date: NaiveDate) -> Option<HolidayCategory> {
+        self.holiday(date).map(|h| h.category)
+    }
+}
+
+/// A HolidayCalendar backed by an explicit list of dated holidays.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HolidayList {
+    holidays: BTreeMap<NaiveDate, Holiday>,
+}
+
+impl HolidayList {
+    pub fn new() -> HolidayList {
+        HolidayList::default()
+    }
+
+    /// Add a holiday on `date`, replacing any holiday already set on that date.
+    pub fn add(&mut self, date: NaiveDate, holiday: Holiday) -> &mut HolidayList {
+        self.holidays.insert(date, holiday);
+        self
+    }
+
+    /// Iterate over all holidays in date order.
+    pub fn iter(&self) -> impl Iterator<Item = (&NaiveDate, &Holiday)> {
+        self.holidays.iter()
+    }
+}
+
+impl HolidayCalendar for HolidayList {
+    fn holiday(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.holidays.get(&date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar() -> HolidayList {
+        let mut calendar = HolidayList::new();
+        calendar
+            .add(date(2018, 1, 1), Holiday::new("New Year's Day", HolidayCategory::Public))
+            .add(date(2018, 8, 27), Holiday::new("Summer Bank Holiday", HolidayCategory::Bank))
+            .add(date(2018, 2, 14), Holiday::new("Valentine's Day", HolidayCategory::Observance));
+        calendar
+    }
+
+    #[test]
+    fn test_holiday_name() {
+        let calendar = calendar();
+        assert_eq!(calendar.holiday_name(date(2018, 1, 1)), Some("New Year's Day"));
+        assert_eq!(calendar.holiday_name(date(2018, 1, 2)), None);
+    }
+
+    #[test]
+    fn test_holiday_category() {
+        let calendar = calendar();
+        assert_eq!(calendar.holiday_category(date(2018, 8, 27)), Some(HolidayCategory::Bank));
+        assert_eq!(calendar.holiday_category(date(2018, 2, 14)), Some(HolidayCategory::Observance));
+        assert_eq!(calendar.holiday_category(date(2018, 2, 15)), None);
+    }
+
+    #[test]
+    fn test_is_holiday_ignores_observances() {
+        let calendar = calendar();
+        assert!(calendar.is_holiday(date(2018, 1, 1)));
+        assert!(calendar.is_holiday(date(2018, 8, 27)));
+        assert!(!calendar.is_holiday(date(2018, 2, 14)));
+        assert!(!calendar.is_holiday(date(2018, 3, 15)));
+    }
+}
@@ -0,0 +1,54 @@
+extern crate chrono;
+extern crate humantime;
+
+use chrono::Duration;
+
+/// Parse a humantime-formatted duration string (e.g. "1h 30m", "2days 4h") into a
+/// `chrono::Duration`.  Values too large for chrono are reported as
+/// `humantime::DurationError::NumberOverflow`.
+pub fn parse_duration(s: &str) -> Result<Duration, humantime::DurationError> {
+    let std_duration = humantime::parse_duration(s)?;
+    Duration::from_std(std_duration).map_err(|_| humantime::DurationError::NumberOverflow)
+}
+
+/// Format a `chrono::Duration` the way humantime does (e.g. "1h 30m").  Returns `None` for
+/// negative durations, which humantime can't represent.
+pub fn format_duration(duration: Duration) -> Option<String> {
+    to_humantime(duration).map(|d| d.to_string())
+}
+
+/// Convert a `chrono::Duration` to a `humantime::Duration`.  Returns `None` for negative
+/// durations.
+pub fn to_humantime(duration: Duration) -> Option<humantime::Duration> {
+    duration.to_std().ok().map(humantime::Duration::from)
+}
+
+/// Convert a `humantime::Duration` to a `chrono::Duration`.  Returns `None` if the value is too
+/// large for chrono.
+pub fn from_humantime(duration: humantime::Duration) -> Option<Duration> {
+    Duration::from_std(*duration).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1h 30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_duration("2days 4h").unwrap(), Duration::hours(52));
+        assert!(parse_duration("soon").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(90)).unwrap(), "1h 30m");
+        assert_eq!(format_duration(Duration::minutes(-90)), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let duration = Duration::days(3) + Duration::milliseconds(250);
+        assert_eq!(from_humantime(to_humantime(duration).unwrap()).unwrap(), duration);
+    }
+}
@@ -0,0 +1,329 @@
+extern crate chrono;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use clock::Clock;
+use holiday_rules::HolidayRules;
+use holidays::HolidayList;
+use schedule::Schedule;
+use std::collections::HashMap;
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum IcsWhen {
+    AllDay(NaiveDate),
+    Timed(Range<DateTime<Utc>>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct IcsEvent {
+    when: IcsWhen,
+    summary: String,
+}
+
+/// A list of events which can be exported as an iCalendar (RFC 5545) document, so that generated
+/// dates such as billing dates, rotas or holidays can be imported into calendar applications.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IcsCalendar {
+    events: Vec<IcsEvent>,
+}
+
+impl IcsCalendar {
+    pub fn new() -> IcsCalendar {
+        IcsCalendar::default()
+    }
+
+    /// Returns a calendar with an all-day event for each holiday in `holidays`, named after it.
+    pub fn from_holidays(holidays: &HolidayList) -> IcsCalendar {
+        let mut calendar = IcsCalendar::new();
+        for (date, holiday) in holidays.iter() {
+            calendar.add_date(*date, &holiday.name);
+        }
+        calendar
+    }
+
+    /// Returns a calendar with an all-day event for each holiday `rules` gives in `years`, the
+    /// same holidays as `HolidayRules::to_calendar`.
+    pub fn from_rules(rules: &HolidayRules, years: RangeInclusive<i32>) -> IcsCalendar {
+        IcsCalendar::from_holidays(&rules.to_calendar(years))
+    }
+
+    /// Returns a calendar with an event at each occurrence of `schedule` in the half-open
+    /// interval `interval`.  Occurrences are instants, so the events have no duration.
+    pub fn from_schedule<S, Tz>(schedule: &S, interval: &Range<DateTime<Tz>>,
+                                summary: &str) -> IcsCalendar
+        where S: Schedule + ?Sized, Tz: TimeZone {
+        let mut calendar = IcsCalendar::new();
+        let end = interval.end.with_timezone(&Utc);
+        for occurrence in schedule.iter_from(&interval.start.with_timezone(&Utc))
+            .take_while(|occurrence| *occurrence < end) {
+            calendar.add_event(&(occurrence..occurrence), summary);
+        }
+        calendar
+    }
+
+    /// Add an all-day event on `date`.
+    pub fn add_date(&mut self, date: NaiveDate, summary: &str) -> &mut IcsCalendar {
+        self.events.push(IcsEvent { when: IcsWhen::AllDay(date), summary: summary.to_string() });
+        self
+    }
+
+    /// Add an all-day event with the same summary on each of `dates`.
+    pub fn add_dates<I>(&mut self, dates: I, summary: &str) -> &mut IcsCalendar
+        where I: IntoIterator<Item = NaiveDate> {
+        for date in dates {
+            self.add_date(date, summary);
+        }
+        self
+    }
+
+    /// Add an event covering the half-open interval `interval`.  A reversed interval (ending
+    /// before it starts) is swapped round, so that the event never ends before it starts.
+    pub fn add_event<Tz: TimeZone>(&mut self, interval: &Range<DateTime<Tz>>,
+                                   summary: &str) -> &mut IcsCalendar {
+        let (start, end) = (interval.start.with_timezone(&Utc), interval.end.with_timezone(&Utc));
+        let utc = if end < start { end..start } else { start..end };
+        self.events.push(IcsEvent { when: IcsWhen::Timed(utc), summary: summary.to_string() });
+        self
+    }
+
+    /// Returns the number of events in the calendar.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if the calendar has no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the calendar as an iCalendar document, with `clock`'s current time as the time
+    /// stamp of each event.  Events are given UIDs from their start and a hash of their summary,
+    /// so exporting the same events again (in any order, or with others added or removed)
+    /// produces the same UIDs and calendar applications update them rather than adding
+    /// duplicates.  Repeats of an event with the same start and summary are told apart by a
+    /// count.  Events with no duration are written without an end.  Times are written in UTC.
+    pub fn to_ics<C: Clock + ?Sized>(&self, clock: &C) -> String {
+        let stamp = clock.now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//chrono-utils//EN".to_string(),
+        ];
+        let mut seen: HashMap<String, u32> = HashMap::new();
+        for event in &self.events {
+            let (start, end) = match event.when {
+                IcsWhen::AllDay(date) => {
+                    let end = date.succ_opt().unwrap_or(date);
+                    (format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+                     Some(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d"))))
+                },
+                IcsWhen::Timed(ref interval) => {
+                    let end = interval.end.format("%Y%m%dT%H%M%SZ");
+                    (format!("DTSTART:{}", interval.start.format("%Y%m%dT%H%M%SZ")),
+                     if interval.is_empty() { None } else { Some(format!("DTEND:{}", end)) })
+                },
+            };
+            let mut uid = format!("{}-{:016x}", start.rsplit(':').next().unwrap_or(""),
+                                  fnv1a(&event.summary));
+            let count = seen.entry(uid.clone()).or_insert(0);
+            *count += 1;
+            if *count > 1 {
+                uid = format!("{}-{}", uid, count);
+            }
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}@chrono-utils", uid));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(start);
+            lines.extend(end);
+            lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| fold_line(line)).collect()
+    }
+}
+
+/// The 64-bit FNV-1a hash of `text`, which (unlike `DefaultHasher`) is the same in every build, so
+/// UIDs stay stable between releases.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Escape the characters which have a special meaning in iCalendar text values.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {},
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line into lines of at most 75 octets, as iCalendar requires, and terminate it
+/// with CRLF.  Continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Weekday};
+    use clock::ManualClock;
+    use holiday_rules::parse_holiday_rule;
+    use holidays::{Holiday, HolidayCategory};
+    use schedule::WeeklySchedule;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn clock() -> ManualClock {
+        ManualClock::new(Utc.with_ymd_and_hms(2018, 3, 1, 9, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_to_ics() {
+        let start = DateTime::parse_from_rfc3339("2018-03-15T09:00:00+09:00").unwrap();
+        let end = DateTime::parse_from_rfc3339("2018-03-15T17:30:00+09:00").unwrap();
+        let mut calendar = IcsCalendar::new();
+        calendar
+            .add_date(date(2018, 3, 31), "Invoice; March, 2018")
+            .add_event(&(start..end), "Support rota");
+        let expected = "BEGIN:VCALENDAR\r\n\
+                        VERSION:2.0\r\n\
+                        PRODID:-//chrono-utils//EN\r\n\
+                        BEGIN:VEVENT\r\n\
+                        UID:20180331-37e4b22c6677d8cd@chrono-utils\r\n\
+                        DTSTAMP:20180301T090000Z\r\n\
+                        DTSTART;VALUE=DATE:20180331\r\n\
+                        DTEND;VALUE=DATE:20180401\r\n\
+                        SUMMARY:Invoice\\; March\\, 2018\r\n\
+                        END:VEVENT\r\n\
+                        BEGIN:VEVENT\r\n\
+                        UID:20180315T000000Z-05c02042d656099c@chrono-utils\r\n\
+                        DTSTAMP:20180301T090000Z\r\n\
+                        DTSTART:20180315T000000Z\r\n\
+                        DTEND:20180315T083000Z\r\n\
+                        SUMMARY:Support rota\r\n\
+                        END:VEVENT\r\n\
+                        END:VCALENDAR\r\n";
+        assert_eq!(calendar.to_ics(&clock()), expected);
+    }
+
+    fn uids(calendar: &IcsCalendar) -> Vec<String> {
+        let mut uids: Vec<String> = calendar.to_ics(&clock()).split("\r\n")
+            .filter(|line| line.starts_with("UID:"))
+            .map(|line| line.to_string())
+            .collect();
+        uids.sort();
+        uids
+    }
+
+    #[test]
+    fn test_uids_are_stable() {
+        let mut calendar = IcsCalendar::new();
+        calendar.add_date(date(2018, 3, 31), "Invoice").add_date(date(2018, 3, 31), "Payroll");
+        let mut reordered = IcsCalendar::new();
+        reordered.add_date(date(2018, 3, 31), "Payroll").add_date(date(2018, 3, 31), "Invoice");
+        assert_eq!(uids(&calendar), uids(&reordered));
+        // Events on the same day are told apart by their summaries.
+        assert_ne!(uids(&calendar)[0], uids(&calendar)[1]);
+    }
+
+    #[test]
+    fn test_repeated_events_get_distinct_uids() {
+        let mut calendar = IcsCalendar::new();
+        calendar.add_dates(vec![date(2018, 3, 31); 3], "Invoice");
+        let uids = uids(&calendar);
+        assert_eq!(uids[0], "UID:20180331-45771306645eac50-2@chrono-utils");
+        assert_eq!(uids[1], "UID:20180331-45771306645eac50-3@chrono-utils");
+        assert_eq!(uids[2], "UID:20180331-45771306645eac50@chrono-utils");
+    }
+
+    #[test]
+    fn test_reversed_and_empty_events() {
+        let start = Utc.with_ymd_and_hms(2018, 3, 15, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2018, 3, 15, 17, 0, 0).unwrap();
+        let mut calendar = IcsCalendar::new();
+        calendar.add_event(&(end..start), "Reversed");
+        assert_eq!(calendar, {
+            let mut forwards = IcsCalendar::new();
+            forwards.add_event(&(start..end), "Reversed");
+            forwards
+        });
+        let mut calendar = IcsCalendar::new();
+        calendar.add_event(&(start..start), "Instant");
+        let ics = calendar.to_ics(&clock());
+        assert!(ics.contains("DTSTART:20180315T090000Z\r\n"));
+        assert!(!ics.contains("DTEND"));
+    }
+
+    #[test]
+    fn test_from_rules() {
+        let mut rules = HolidayRules::new();
+        rules.add(parse_holiday_rule("Dec 25").unwrap(),
+                  Holiday::new("Christmas Day", HolidayCategory::Public));
+        let calendar = IcsCalendar::from_rules(&rules, 2018..=2020);
+        assert_eq!(calendar.len(), 3);
+        let ics = calendar.to_ics(&clock());
+        assert!(ics.contains("DTSTART;VALUE=DATE:20191225\r\n"));
+    }
+
+    #[test]
+    fn test_from_schedule() {
+        let schedule = WeeklySchedule::new(Weekday::Mon, NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                                           Utc);
+        // March 2018 starts on a Thursday; the Monday of April 2nd is excluded.
+        let interval = Utc.with_ymd_and_hms(2018, 3, 1, 0, 0, 0).unwrap()
+            ..Utc.with_ymd_and_hms(2018, 4, 2, 9, 0, 0).unwrap();
+        let calendar = IcsCalendar::from_schedule(&schedule, &interval, "Stand-up");
+        assert_eq!(calendar.len(), 4);
+        let ics = calendar.to_ics(&clock());
+        assert!(ics.contains("DTSTART:20180305T090000Z\r\n"));
+        assert!(ics.contains("DTSTART:20180326T090000Z\r\n"));
+        assert!(!ics.contains("DTEND"));
+    }
+
+    #[test]
+    fn test_from_holidays() {
+        let mut holidays = HolidayList::new();
+        holidays
+            .add(date(2018, 12, 25), Holiday::new("Christmas Day", HolidayCategory::Public))
+            .add(date(2018, 1, 1), Holiday::new("New Year's Day", HolidayCategory::Public));
+        let calendar = IcsCalendar::from_holidays(&holidays);
+        assert_eq!(calendar.len(), 2);
+        let ics = calendar.to_ics(&clock());
+        assert!(ics.find("SUMMARY:New Year's Day").unwrap() < ics.find("Christmas").unwrap());
+        assert!(IcsCalendar::new().is_empty());
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut calendar = IcsCalendar::new();
+        calendar.add_dates(vec![date(2018, 3, 31)], &"x".repeat(100));
+        let ics = calendar.to_ics(&clock());
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ics.contains(&format!("SUMMARY:{}\r\n {}\r\n", "x".repeat(67), "x".repeat(33))));
+    }
+}
@@ -0,0 +1,240 @@
+extern crate chrono;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, ParseError, ParseResult,
+             TimeZone};
+use precision::PrecisionPolicy;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+
+/// The three date representations defined by ISO 8601.  Each style only changes how the date
+/// part is written; the time and offset are always written as `HH:MM:SS[.fff]+HH:MM`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IsoStyle {
+    /// Calendar date, e.g. `2018-03-15T12:00:00+00:00`.
+    Calendar,
+    /// Ordinal date (year and day of the year), e.g. `2018-074T12:00:00+00:00`.
+    Ordinal,
+    /// Week date (ISO week-year, week number and weekday), e.g. `2018-W11-4T12:00:00+00:00`.
+    WeekDate,
+}
+
+impl IsoStyle {
+    fn format_str(&self) -> &'static str {
+        match *self {
+            IsoStyle::Calendar => "%Y-%m-%dT%H:%M:%S%.f%:z",
+            IsoStyle::Ordinal => "%Y-%jT%H:%M:%S%.f%:z",
+            IsoStyle::WeekDate => "%G-W%V-%uT%H:%M:%S%.f%:z",
+        }
+    }
+}
+
+/// This trait defines ISO 8601 formatting for all three of the standard's date representations.
+pub trait IsoFormatting {
+    /// Format self as an ISO 8601 string using the given date representation.
+    fn to_iso(&self, style: IsoStyle) -> String;
+
+    /// Format self as an ISO 8601 string using the given date representation, writing the
+    /// fraction of a second according to `precision`.
+    fn to_iso_with(&self, style: IsoStyle, precision: &PrecisionPolicy) -> String;
+}
+
+impl<Tz> IsoFormatting for DateTime<Tz> where Tz: TimeZone, Tz::Offset: Display {
+    fn to_iso(&self, style: IsoStyle) -> String {
+        self.format(style.format_str()).to_string()
+    }
+
+    fn to_iso_with(&self, style: IsoStyle, precision: &PrecisionPolicy) -> String {
+        let format = style.format_str().replace("%.f", &precision.format_fraction(self));
+        precision.apply(self).format(&format).to_string()
+    }
+}
+
+/// Parse an ISO 8601 string written in the given date representation.  This is the inverse of
+/// `IsoFormatting::to_iso`, so any string produced by `to_iso` with the same style will parse
+/// back to the same instant.
+pub fn parse_iso(s: &str, style: IsoStyle) -> ParseResult<DateTime<FixedOffset>> {
+    DateTime::parse_from_str(s, style.format_str())
+}
+
+/// Parse an ISO 8601 string as `parse_iso` does, then reduce it to the precision of `precision`,
+/// so that values written by systems with more precision compare equal to those written with
+/// `IsoFormatting::to_iso_with` and the same policy.
+pub fn parse_iso_with(s: &str, style: IsoStyle,
+                      precision: &PrecisionPolicy) -> ParseResult<DateTime<FixedOffset>> {
+    parse_iso(s, style).map(|dt| precision.apply(&dt))
+}
+
+/// Options for `parse_with_options`, controlling what may be left out of the input.  The default
+/// options are strict: both the time and the offset must be given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// The offset to assume if the input has none, or `None` to reject such input.
+    pub assume_offset: Option<FixedOffset>,
+    /// The time to assume if the input is only a date, or `None` to reject such input.
+    pub assume_time: Option<NaiveTime>,
+    /// The precision to reduce the result to, or `None` to keep it as given.
+    pub precision: Option<PrecisionPolicy>,
+}
+
+impl ParseOptions {
+    /// Options which require both the time and the offset.
+    pub fn strict() -> ParseOptions {
+        ParseOptions::default()
+    }
+}
+
+/// The error returned by `parse_with_options`, saying what was missing from the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrictParseError {
+    /// The input is a date and time without a UTC offset or `Z`.
+    MissingOffset,
+    /// The input is a date without a time (or offset).
+    MissingTime,
+    /// The input isn't a date and time at all.
+    Invalid(ParseError),
+}
+
+impl Display for StrictParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StrictParseError::MissingOffset => write!(f, "missing UTC offset"),
+            StrictParseError::MissingTime => write!(f, "missing time of day"),
+            StrictParseError::Invalid(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for StrictParseError {}
+
+/// Parse an RFC 3339 date and time (e.g. `2018-03-15T12:00:00+09:00`), reporting what is missing
+/// instead of guessing when the input has no offset or no time.  Inputs without an offset (e.g.
+/// `2018-03-15T12:00:00`, or with a space instead of the `T`) and bare dates (`2018-03-15`) are
+/// only accepted if `options` says what to assume for the missing parts.
+pub fn parse_with_options(s: &str, options: &ParseOptions)
+                          -> Result<DateTime<FixedOffset>, StrictParseError> {
+    let error = match DateTime::parse_from_rfc3339(s) {
+        Ok(dt) => return Ok(with_precision(dt, options)),
+        Err(e) => e,
+    };
+
+    let local = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"));
+    let local = match (local, NaiveDate::parse_from_str(s, "%Y-%m-%d")) {
+        (Ok(local), _) => local,
+        (Err(_), Ok(date)) => {
+            date.and_time(options.assume_time.ok_or(StrictParseError::MissingTime)?)
+        }
+        (Err(_), Err(_)) => return Err(StrictParseError::Invalid(error)),
+    };
+
+    let offset = options.assume_offset.ok_or(StrictParseError::MissingOffset)?;
+    let dt = offset.from_local_datetime(&local).single().ok_or(StrictParseError::Invalid(error))?;
+    Ok(with_precision(dt, options))
+}
+
+fn with_precision(dt: DateTime<FixedOffset>, options: &ParseOptions) -> DateTime<FixedOffset> {
+    match options.precision {
+        Some(ref precision) => precision.apply(&dt),
+        None => dt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use precision::{Precision, Rounding};
+
+    #[test]
+    fn test_to_iso_calendar() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.to_iso(IsoStyle::Calendar), "2018-03-15T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_to_iso_ordinal() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        assert_eq!(test_date.to_iso(IsoStyle::Ordinal), "2018-074T12:00:00+09:00");
+    }
+
+    #[test]
+    fn test_to_iso_week_date() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.to_iso(IsoStyle::WeekDate), "2018-W11-4T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_to_iso_week_date_previous_week_year() {
+        // January 1st, 2021 is a Friday, so it belongs to the last ISO week of 2020.
+        let test_date = DateTime::parse_from_rfc3339("2021-01-01T08:30:00Z").unwrap();
+        assert_eq!(test_date.to_iso(IsoStyle::WeekDate), "2020-W53-5T08:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_iso_round_trip() {
+        let test_date = DateTime::parse_from_rfc3339("2016-12-31T23:59:59.250-05:00").unwrap();
+        for style in &[IsoStyle::Calendar, IsoStyle::Ordinal, IsoStyle::WeekDate] {
+            let formatted = test_date.to_iso(*style);
+            assert_eq!(parse_iso(&formatted, *style).unwrap(), test_date);
+        }
+    }
+
+    #[test]
+    fn test_parse_iso_wrong_style() {
+        assert!(parse_iso("2018-074T12:00:00+00:00", IsoStyle::Calendar).is_err());
+    }
+
+    #[test]
+    fn test_parse_with_options_strict() {
+        let options = ParseOptions::strict();
+        let expected = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        assert_eq!(parse_with_options("2018-03-15T12:00:00+09:00", &options), Ok(expected));
+        assert_eq!(parse_with_options("2018-03-15T12:00:00", &options),
+                   Err(StrictParseError::MissingOffset));
+        assert_eq!(parse_with_options("2018-03-15 12:00:00.5", &options),
+                   Err(StrictParseError::MissingOffset));
+        assert_eq!(parse_with_options("2018-03-15", &options), Err(StrictParseError::MissingTime));
+        match parse_with_options("15/03/2018", &options) {
+            Err(StrictParseError::Invalid(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_with_options_assumed() {
+        let options = ParseOptions {
+            assume_offset: Some(FixedOffset::east_opt(0).unwrap()),
+            assume_time: Some(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+            ..ParseOptions::default()
+        };
+        let expected = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(parse_with_options("2018-03-15T12:00:00", &options), Ok(expected));
+        let expected = DateTime::parse_from_rfc3339("2018-03-15T00:00:00Z").unwrap();
+        assert_eq!(parse_with_options("2018-03-15", &options), Ok(expected));
+        assert_eq!(StrictParseError::MissingOffset.to_string(), "missing UTC offset");
+    }
+
+    #[test]
+    fn test_to_iso_with_precision() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00.1239Z").unwrap();
+        let millis = PrecisionPolicy::new(Precision::Millis, Rounding::Round, true);
+        assert_eq!(test_date.to_iso_with(IsoStyle::Calendar, &millis),
+                   "2018-03-15T12:00:00.124+00:00");
+        let micros = PrecisionPolicy::new(Precision::Micros, Rounding::Truncate, false);
+        assert_eq!(test_date.to_iso_with(IsoStyle::Ordinal, &micros),
+                   "2018-074T12:00:00.1239+00:00");
+        let seconds = PrecisionPolicy::new(Precision::Seconds, Rounding::Truncate, true);
+        assert_eq!(test_date.to_iso_with(IsoStyle::WeekDate, &seconds),
+                   "2018-W11-4T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_with_precision() {
+        let millis = PrecisionPolicy::new(Precision::Millis, Rounding::Truncate, true);
+        let expected = DateTime::parse_from_rfc3339("2018-03-15T12:00:00.123Z").unwrap();
+        assert_eq!(parse_iso_with("2018-03-15T12:00:00.123456+00:00", IsoStyle::Calendar, &millis),
+                   Ok(expected));
+        let options = ParseOptions { precision: Some(millis), ..ParseOptions::strict() };
+        assert_eq!(parse_with_options("2018-03-15T12:00:00.123999Z", &options), Ok(expected));
+    }
+}
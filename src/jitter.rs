@@ -0,0 +1,158 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, Utc};
+use schedule::Schedule;
+
+/// A schedule whose occurrences are each moved by a pseudo-random offset of up to `window`
+/// either way, to spread out the load of many jobs on the same schedule (such as every cron job
+/// running on the hour).  The offset of each occurrence is derived from the seed and the
+/// occurrence itself, so the same seed always gives the same occurrences, which keeps tests
+/// reproducible, while jobs with different seeds are spread across the window.
+///
+/// If the window is more than half the time between occurrences, jittered occurrences may be in
+/// a different order from the original ones; they are always returned in time order.
+#[derive(Clone, Debug)]
+pub struct Jittered<S> {
+    schedule: S,
+    window: Duration,
+    seed: u64,
+}
+
+impl<S: Schedule> Jittered<S> {
+    /// Returns `schedule` with each occurrence moved by up to `window` earlier or later.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is negative, or too long to be represented in nanoseconds (about 292
+    /// years).
+    pub fn new(schedule: S, window: Duration, seed: u64) -> Jittered<S> {
+        assert!(window >= Duration::zero(), "Jitter window must not be negative");
+        assert!(window.num_nanoseconds().is_some(), "Jitter window is too long");
+        Jittered { schedule, window, seed }
+    }
+
+    /// Returns the offset applied to the occurrence at `occurrence`.
+    pub fn offset_of(&self, occurrence: &DateTime<Utc>) -> Duration {
+        let window = self.window.num_nanoseconds().expect("Checked in new") as u64;
+        let key = mix(occurrence.timestamp() as u64) ^ occurrence.timestamp_subsec_nanos() as u64;
+        let offset = (mix(self.seed ^ key) % (2 * window + 1)) as i64 - window as i64;
+        Duration::nanoseconds(offset)
+    }
+
+    fn jittered(&self, occurrence: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        occurrence.checked_add_signed(self.offset_of(&occurrence))
+    }
+}
+
+/// The SplitMix64 finalizer, which scrambles the bits of `x` so that nearby inputs give
+/// unrelated outputs.
+fn mix(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+impl<S: Schedule> Schedule for Jittered<S> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Occurrences up to `window` before dt may be moved after it.  Once an original
+        // occurrence is more than `window` after the best candidate, no later one can beat it.
+        let mut occurrence = self.schedule.next_after(&dt.checked_sub_signed(self.window)?)?;
+        let mut best: Option<DateTime<Utc>> = None;
+        while best.is_none_or(|best| occurrence.signed_duration_since(best) <= self.window) {
+            if let Some(jittered) = self.jittered(occurrence) {
+                if jittered > *dt && best.is_none_or(|best| jittered < best) {
+                    best = Some(jittered);
+                }
+            }
+            occurrence = match self.schedule.next_after(&occurrence) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        best
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut occurrence = self.schedule.prev_before(&dt.checked_add_signed(self.window)?)?;
+        let mut best: Option<DateTime<Utc>> = None;
+        while best.is_none_or(|best| best.signed_duration_since(occurrence) <= self.window) {
+            if let Some(jittered) = self.jittered(occurrence) {
+                if jittered < *dt && best.is_none_or(|best| jittered > best) {
+                    best = Some(jittered);
+                }
+            }
+            occurrence = match self.schedule.prev_before(&occurrence) {
+                Some(prev) => prev,
+                None => break,
+            };
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ticks::ticks;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn hourly() -> impl Schedule {
+        ticks(at("2018-03-15T00:00:00Z"), Duration::hours(1))
+    }
+
+    #[test]
+    fn test_jittered_is_reproducible() {
+        let start = at("2018-03-15T12:00:00Z");
+        let jittered = Jittered::new(hourly(), Duration::minutes(5), 42);
+        let first: Vec<_> = jittered.iter_from(&start).take(24).collect();
+        let again: Vec<_> = Jittered::new(hourly(), Duration::minutes(5), 42)
+            .iter_from(&start)
+            .take(24)
+            .collect();
+        assert_eq!(first, again);
+        let other: Vec<_> = Jittered::new(hourly(), Duration::minutes(5), 43)
+            .iter_from(&start)
+            .take(24)
+            .collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_jittered_stays_in_window() {
+        let start = at("2018-03-15T12:00:00Z");
+        let jittered = Jittered::new(hourly(), Duration::minutes(5), 7);
+        let originals = hourly().iter_from(&start).skip(1).take(100).collect::<Vec<_>>();
+        let occurrences = jittered.iter_from(&start).skip(1).take(100).collect::<Vec<_>>();
+        for (original, occurrence) in originals.iter().zip(&occurrences) {
+            assert!(occurrence.signed_duration_since(*original).num_seconds().abs() <= 300);
+            assert_eq!(*occurrence, *original + jittered.offset_of(original));
+        }
+        // The offsets actually spread the occurrences out.
+        assert!(originals.iter().zip(&occurrences).filter(|(a, b)| a != b).count() > 90);
+    }
+
+    #[test]
+    fn test_jittered_navigation() {
+        // With a window this wide, occurrences are often reordered.
+        let jittered = Jittered::new(hourly(), Duration::minutes(50), 1);
+        let start = at("2018-03-16T00:00:00Z");
+        let occurrences: Vec<_> = jittered.iter_from(&start).take(50).collect();
+        assert!(occurrences.windows(2).all(|pair| pair[0] < pair[1]));
+        for pair in occurrences.windows(2) {
+            assert_eq!(jittered.next_after(&pair[0]), Some(pair[1]));
+            assert_eq!(jittered.prev_before(&pair[1]), Some(pair[0]));
+        }
+        let unjittered = Jittered::new(hourly(), Duration::zero(), 1);
+        assert_eq!(unjittered.next_after(&start), Some(at("2018-03-16T01:00:00Z")));
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be negative")]
+    fn test_jittered_negative_window() {
+        Jittered::new(hourly(), Duration::minutes(-5), 1);
+    }
+}
@@ -1,8 +1,127 @@
 extern crate chrono;
 
+pub mod accrual;
+pub mod age;
+pub mod amortization;
+pub mod approx_period;
+pub mod bands;
+pub mod business_days;
+pub mod business_hours;
+pub mod calendar_diff;
+#[cfg(feature = "serde")]
+pub mod calendar_schema;
+pub mod calendar_units;
+pub mod civil;
+pub mod clock;
+pub mod comparison;
+pub mod composite;
+pub mod construct;
+pub mod daycount;
+pub mod deadline;
+pub mod downsample;
+pub mod error;
+pub mod freshness;
+pub mod hlc;
+pub mod holiday_rules;
+pub mod holidays;
+#[cfg(feature = "humantime")]
+pub mod humantime_interop;
+pub mod ics;
+pub mod iso_format;
+pub mod jitter;
+pub mod lunar;
 pub mod month_calc;
+pub mod month_iter;
+pub mod payment_terms;
+pub mod period;
+pub mod period_key;
+pub mod precision;
+pub mod privacy;
+pub mod quiet_hours;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod retail_calendar;
+pub mod rollup;
+pub mod roman_format;
+pub mod schedule;
+pub mod search;
+pub mod season;
+pub mod skew;
+pub mod stats;
+pub mod tenure;
+#[cfg(test)]
+mod test_tz;
+pub mod ticks;
+pub mod time_of_day;
+pub mod week_calc;
+pub mod windows;
 pub mod year_calc;
 
-pub use month_calc::MonthCalculations;
-pub use year_calc::YearCalculations;
+pub use accrual::{accrual, AccrualRate};
+pub use age::Age;
+pub use amortization::{accrual_periods, amortization_dates, PaymentDate, StubPolicy};
+pub use approx_period::ApproxPeriod;
+pub use bands::BandSchedule;
+pub use business_days::{bridge_days, is_working_day, long_weekends, roll, year_overview, DayKind,
+                        OverviewDay, RollConvention, Workweek, YearOverview};
+pub use business_hours::{next_working_instant, BusinessHours};
+pub use calendar_diff::{CalendarDiff, CalendarDifference};
+pub use calendar_units::{Months, Years};
+pub use civil::{date_global_bounds, local_deadline_in_zones, CivilDateTime, DstPolicy};
+pub use clock::{Clock, ManualClock, SystemClock, TimestampPair};
+pub use comparison::{same_period_last_year, same_period_previous_month, shift_interval,
+                     CalendarCompare, ShiftPolicy};
+pub use composite::{Excluding, Intersection, Union};
+pub use construct::from_ymd_hms_clamped;
+pub use daycount::DayCount;
+pub use deadline::{format_deadline, DeadlineThresholds};
+pub use downsample::downsample_lttb;
+pub use error::ChronoUtilsError;
+pub use freshness::{describe_freshness, freshness, Freshness, FreshnessThresholds};
+pub use hlc::{HlcTimestamp, HybridLogicalClock};
+pub use holiday_rules::{easter_sunday, julian_to_gregorian, orthodox_easter_sunday,
+                        parse_holiday_rule, HolidayRule, HolidayRules};
+pub use holidays::{Holiday, HolidayCalendar, HolidayCategory, HolidayList};
+#[cfg(feature = "humantime")]
+pub use humantime_interop::{format_duration, from_humantime, parse_duration, to_humantime};
+pub use ics::IcsCalendar;
+pub use iso_format::{parse_iso, parse_iso_with, parse_with_options, IsoFormatting, IsoStyle,
+                     ParseOptions, StrictParseError};
+pub use jitter::Jittered;
+pub use lunar::new_moons_in;
+pub use month_calc::{add_months_all, add_months_in_place, add_months_local, add_months_on,
+                     add_months_to_year_month, days_in_month, is_leap_year, try_with_closest_day,
+                     with_closest_day, CalendarBasis, MonthCalculations, MonthDifference,
+                     MonthShift, OverflowPolicy};
+pub use month_iter::{MonthAnchor, MonthIterator, MonthsFrom};
+pub use payment_terms::{dunning_schedule, parse_payment_terms, PaymentTerms};
+pub use period::{period_end, InclusionRule, Period};
+pub use period_key::{from_months_since_epoch, from_period_key, from_weeks_since_epoch, period_key,
+                     DayPolicy, EpochCalculations, Unit};
+pub use precision::{Precision, PrecisionPolicy, Rounding};
+pub use privacy::anonymize;
+#[cfg(feature = "rand")]
+pub use privacy::jitter;
+pub use quiet_hours::QuietHours;
+#[cfg(feature = "rand")]
+pub use random::{random_business_datetime_in, random_datetime_in, random_weighted_datetime_in,
+                 DiurnalWeights};
+pub use retail_calendar::{RetailCalendar, RetailDate, RetailPattern, YearEndMethod, YearNaming};
+pub use rollup::{accumulate_over_days, group_by_shift_date, rollup_boundaries, shift_date};
+pub use roman_format::{month_from_roman, month_to_roman, parse_roman_date, RomanMonthFormatting};
+pub use schedule::{missed_occurrences, occurrences_localized, snap_to, CatchUpPolicy, Direction,
+                   MonthlySchedule, Schedule, WeeklySchedule};
+pub use search::{partition_point_by_instant, range_indices};
+pub use season::{season, Hemisphere, Season, SeasonConvention};
+pub use skew::{approx_eq, estimate_skew, is_before_with_skew, ordering_with_skew, SkewEstimate};
+pub use stats::{mean_time_of_day, median_instant, occurrence_stats, std_dev_time_of_day,
+                weighted_mean_instant, OccurrenceStats};
+pub use tenure::{tenure_band, TenureBand};
+pub use ticks::{ticks, Ticks};
+pub use time_of_day::TimeOfDayRange;
+pub use week_calc::{budget_weeks, gestational_age, nth_weekday_of_year, week_of_month, weeks_from,
+                    BudgetWeek, MonthWeek, WeekOfMonthConvention};
+pub use windows::{window_bounds, windows_by, windows_by_key, Window, Windows};
+pub use year_calc::{anniversaries_of, FractionalYears, LeapDayPolicy, YearArithmetic, YearBoundary,
+                    YearCalculations};
 
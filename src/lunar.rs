@@ -0,0 +1,112 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use season::UNIX_EPOCH_JD;
+
+/// The mean length of a lunation (from one new moon to the next), in days.
+const SYNODIC_MONTH: f64 = 29.530588861;
+
+// Periodic terms for the new moon, from Meeus' chapter 49: the coefficient, the power of the
+// eccentricity factor E it is multiplied by, and the multiples of M, M', F and Omega in the sine's
+// argument.
+const NEW_MOON_TERMS: [(f64, i32, [f64; 4]); 25] = [
+    (-0.40720, 0, [0.0, 1.0, 0.0, 0.0]), (0.17241, 1, [1.0, 0.0, 0.0, 0.0]),
+    (0.01608, 0, [0.0, 2.0, 0.0, 0.0]), (0.01039, 0, [0.0, 0.0, 2.0, 0.0]),
+    (0.00739, 1, [-1.0, 1.0, 0.0, 0.0]), (-0.00514, 1, [1.0, 1.0, 0.0, 0.0]),
+    (0.00208, 2, [2.0, 0.0, 0.0, 0.0]), (-0.00111, 0, [0.0, 1.0, -2.0, 0.0]),
+    (-0.00057, 0, [0.0, 1.0, 2.0, 0.0]), (0.00056, 1, [1.0, 2.0, 0.0, 0.0]),
+    (-0.00042, 0, [0.0, 3.0, 0.0, 0.0]), (0.00042, 1, [1.0, 0.0, 2.0, 0.0]),
+    (0.00038, 1, [1.0, 0.0, -2.0, 0.0]), (-0.00024, 1, [-1.0, 2.0, 0.0, 0.0]),
+    (-0.00017, 0, [0.0, 0.0, 0.0, 1.0]), (-0.00007, 0, [2.0, 1.0, 0.0, 0.0]),
+    (0.00004, 0, [0.0, 2.0, -2.0, 0.0]), (0.00004, 0, [3.0, 0.0, 0.0, 0.0]),
+    (0.00003, 0, [1.0, 1.0, -2.0, 0.0]), (0.00003, 0, [0.0, 2.0, 2.0, 0.0]),
+    (-0.00003, 0, [1.0, 1.0, 2.0, 0.0]), (0.00003, 0, [-1.0, 1.0, 2.0, 0.0]),
+    (-0.00002, 0, [-1.0, 1.0, -2.0, 0.0]), (-0.00002, 0, [1.0, 3.0, 0.0, 0.0]),
+    (0.00002, 0, [0.0, 4.0, 0.0, 0.0]),
+];
+
+// The planetary arguments (A1 to A14) as a constant and a multiple of k, and their coefficients,
+// from Meeus' chapter 49.  (A1 also has a T squared term, which is added separately.)
+const PLANETARY_TERMS: [(f64, f64, f64); 14] = [
+    (299.77, 0.107408, 0.000325), (251.88, 0.016321, 0.000165), (251.83, 26.651886, 0.000164),
+    (349.42, 36.412478, 0.000126), (84.66, 18.206239, 0.000110), (141.74, 53.303771, 0.000062),
+    (207.14, 2.453732, 0.000060), (154.84, 7.306860, 0.000056), (34.52, 27.261239, 0.000047),
+    (207.19, 0.121824, 0.000042), (291.34, 1.844379, 0.000040), (161.72, 24.198154, 0.000037),
+    (239.56, 25.513099, 0.000035), (331.55, 3.592518, 0.000023),
+];
+
+/// Returns the instants of the new moons in `year` (in UTC), or `None` if the year is outside
+/// 1000 to 3000.  The new moons are calculated with the algorithm from Meeus' Astronomical
+/// Algorithms, which is accurate to within a couple of minutes.
+pub fn new_moons_in(year: i32) -> Option<Vec<DateTime<Utc>>> {
+    if !(1000..=3000).contains(&year) {
+        return None;
+    }
+    // k counts lunations from the new moon of January 6th 2000; start a lunation early to be
+    // sure of catching the first new moon of the year.
+    let first = ((year - 2000) as f64 * 12.3685).floor() as i64 - 1;
+    let new_moons = (first..first + 15)
+        .filter_map(new_moon)
+        .filter(|instant| instant.year() == year)
+        .collect();
+    Some(new_moons)
+}
+
+/// Returns the instant of the new moon `k` lunations after the one of January 6th 2000.
+fn new_moon(k: i64) -> Option<DateTime<Utc>> {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+    let mean = 2451550.09766 + SYNODIC_MONTH * k + 0.00015437 * t2 - 0.000000150 * t3
+        + 0.00000000073 * t4;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3;
+    let m_prime = 201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3
+        - 0.000000058 * t4;
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4;
+    let omega = 124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3;
+    let periodic: f64 = NEW_MOON_TERMS.iter()
+        .map(|(coefficient, e_power, multiples)| {
+            let argument = multiples[0] * m + multiples[1] * m_prime + multiples[2] * f
+                + multiples[3] * omega;
+            coefficient * e.powi(*e_power) * argument.to_radians().sin()
+        })
+        .sum();
+    let planetary: f64 = PLANETARY_TERMS.iter()
+        .enumerate()
+        .map(|(i, (constant, per_lunation, coefficient))| {
+            let t2_term = if i == 0 { -0.009173 * t2 } else { 0.0 };
+            coefficient * (constant + per_lunation * k + t2_term).to_radians().sin()
+        })
+        .sum();
+    let jde = mean + periodic + planetary;
+    let millis = ((jde - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    DateTime::<Utc>::UNIX_EPOCH.checked_add_signed(Duration::milliseconds(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_new_moons_in() {
+        let new_moons = new_moons_in(2018).unwrap();
+        // The first new moons of 2018 were on January 17th and February 15th, and the last of
+        // its 12 on December 7th.
+        assert_eq!(new_moons.len(), 12);
+        let error = |actual: &DateTime<Utc>, expected: &str| {
+            actual.signed_duration_since(at(expected)).num_seconds().abs()
+        };
+        assert!(error(&new_moons[0], "2018-01-17T02:17:00Z") < 120);
+        assert!(error(&new_moons[1], "2018-02-15T21:05:00Z") < 120);
+        assert!(error(&new_moons[11], "2018-12-07T07:20:00Z") < 120);
+        // The total solar eclipse of April 8th 2024.
+        let new_moons = new_moons_in(2024).unwrap();
+        assert!(new_moons.iter().any(|new_moon| error(new_moon, "2024-04-08T18:21:00Z") < 120));
+        assert_eq!(new_moons_in(999), None);
+    }
+}
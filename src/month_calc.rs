@@ -1,76 +1,930 @@
 extern crate chrono;
 
-use chrono::{DateTime, Datelike, TimeZone};
+use chrono::{DateTime, Datelike, Days, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+             Offset, TimeZone, Utc, Weekday};
+use civil::{resolve_local, DstPolicy};
+use error::ChronoUtilsError;
+use month_iter::{MonthAnchor, MonthIterator, MonthsFrom};
+use std::convert::TryFrom;
+
+/// Determines what happens when a requested day of the month does not exist in the target month
+/// (for example, the 30th of February).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Use the last day of the target month instead (February 30 becomes February 28 or 29).
+    ClampToLastDay,
+    /// Carry the extra days over into the following month (February 30 becomes March 2 or 1).
+    RollForwardToNextMonth,
+    /// Treat the day as invalid and produce no result.
+    Reject,
+    /// Skip ahead to the next month which has the day (February 30 becomes March 30).
+    Skip,
+}
+
+/// The number of days in each month of a non-leap year.
+const DAYS_IN_MONTH: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Returns true if `year` is a leap year in the proleptic Gregorian calendar (as used by chrono):
+/// a multiple of 4, except for multiples of 100 which are not multiples of 400.
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in `month` (1 to 12) of `year`, or `None` if the month is invalid.
+pub fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let days = *DAYS_IN_MONTH.get(month.checked_sub(1)? as usize)?;
+    Some(if month == 2 && is_leap_year(year) { 29 } else { days })
+}
+
+/// Returns the year and month (1 to 12) `num_months` months after (or before, if negative) the
+/// given year and month, or `None` if the year would overflow.  This uses only integer
+/// arithmetic, counting months from year 0 so that negative results borrow from the year
+/// correctly.
+pub(crate) const fn shift_year_month(year: i32, month: u32, num_months: i64) -> Option<(i32, u32)> {
+    let total_months = match (year as i64 * 12 + month as i64 - 1).checked_add(num_months) {
+        Some(total_months) => total_months,
+        None => return None,
+    };
+    let shifted_year = total_months.div_euclid(12);
+    if shifted_year < i32::MIN as i64 || shifted_year > i32::MAX as i64 {
+        return None;
+    }
+    Some((shifted_year as i32, total_months.rem_euclid(12) as u32 + 1))
+}
+
+/// Returns the `nth` `weekday` of the given month, counting from the end of the month if `nth`
+/// is negative (-1 being the last), or `None` if there is no such day (such as a 5th Monday in a
+/// month with only four, or `nth` being 0).
+pub(crate) fn nth_weekday(year: i32, month: u32, weekday: Weekday, nth: i32) -> Option<NaiveDate> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    if nth > 0 {
+        let offset = (weekday.num_days_from_monday() + 7
+            - first.weekday().num_days_from_monday()) % 7;
+        let date = first.checked_add_days(Days::new(offset as u64 + (nth as u64 - 1) * 7))?;
+        if date.month() == month { Some(date) } else { None }
+    } else if nth < 0 {
+        let last = first.with_day(days_in_month(year, month)?)?;
+        let offset = (last.weekday().num_days_from_monday() + 7
+            - weekday.num_days_from_monday()) % 7;
+        let back = offset as u64 + (nth.unsigned_abs() as u64 - 1) * 7;
+        let date = last.checked_sub_days(Days::new(back))?;
+        if date.month() == month { Some(date) } else { None }
+    } else {
+        None
+    }
+}
+
+/// Returns the date with the given fields, applying `policy` if `day` doesn't exist in the month.
+/// Returns `ChronoUtilsError::InvalidDay` if the day doesn't exist and the policy is `Reject`, or
+/// is 0 or over 31; and `ChronoUtilsError::OutOfRange` if the year or month is invalid.
+pub(crate) fn resolve_day(year: i32, month: u32, day: u32,
+                          policy: OverflowPolicy) -> Result<NaiveDate, ChronoUtilsError> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or(ChronoUtilsError::OutOfRange)?;
+    if day == 0 || day > 31 {
+        return Err(ChronoUtilsError::InvalidDay);
+    }
+    if let Some(date) = first.with_day(day) {
+        return Ok(date);
+    }
+    let resolved = match policy {
+        OverflowPolicy::Reject => return Err(ChronoUtilsError::InvalidDay),
+        OverflowPolicy::ClampToLastDay => {
+            days_in_month(year, month).and_then(|last| first.with_day(last))
+        },
+        OverflowPolicy::RollForwardToNextMonth => first.checked_add_days(Days::new(day as u64 - 1)),
+        // A month without the 29th, 30th or 31st is always followed by one with it within two
+        // months (February is followed by March, and each 30 day month by a 31 day month).
+        OverflowPolicy::Skip => (1..3)
+            .filter_map(|n| first.checked_add_months(Months::new(n)))
+            .find_map(|next| next.with_day(day)),
+    };
+    resolved.ok_or(ChronoUtilsError::OutOfRange)
+}
 
 /// This trait defines functions which allow for by-month calculation and transformations.
 /// Implementors of this trait should return new instances of themselves after applying
 /// the appropriate month-based transformation.
 pub trait MonthCalculations {
     /// Add a positive or negative number of months to self and return a new instance of self
-    /// with the transformation applied.
+    /// with the transformation applied.  Panics if the result is out of range (see
+    /// `try_add_months`).  DateTimes keep their local date and time of day in their own timezone
+    /// (see `add_months_on` to count months in UTC instead).
+    ///
+    /// `d.add_months(n).sub_months(n)` is always `d` unless the day of the month was clamped on
+    /// the way (e.g. January 31st + 1 month is February 28th, which goes back to January 28th).
+    /// Use `add_months_reversible` to be able to undo a clamped addition.
     fn add_months(&self, num_months: i32) -> Self;
 
+    /// Subtract a positive or negative number of months from self; the same as `add_months` with
+    /// the sign of `num_months` flipped.  Panics if the result is out of range.
+    fn sub_months(&self, num_months: i32) -> Self;
+
+    /// Add months to self the same way as `add_months`, also recording the day of the month if it
+    /// had to be clamped, so that `MonthShift::undo` can always get back to self.  Panics if the
+    /// result is out of range.
+    fn add_months_reversible(&self, num_months: i32) -> MonthShift<Self> where Self: Sized;
+
+    /// Add a positive or negative number of months to self the same way as `add_months`, but
+    /// return an error instead of panicking: `ChronoUtilsError::OutOfRange` if the result is out
+    /// of range, and for DateTimes `NonexistentLocalTime` or `AmbiguousLocalTime` if the resulting
+    /// local time falls in a DST gap or overlap (see `add_months_local` to resolve those).
+    fn try_add_months(&self, num_months: i32) -> Result<Self, ChronoUtilsError> where Self: Sized;
+
+    /// Add a positive or negative number of months to self the same way as `add_months`, but
+    /// taking an `i64` so that very long horizons can be used, and returning `None` instead of
+    /// panicking if the result is out of range.  (This is not named `checked_add_months` since
+    /// chrono's types already have an inherent method of that name taking `chrono::Months`.)
+    fn add_months_checked(&self, num_months: i64) -> Option<Self> where Self: Sized;
+
+    /// Add a positive or negative number of months to self the same way as `add_months`, but
+    /// clamp the result to the earliest or latest representable value instead of panicking if it
+    /// is out of range, so that "far future" sentinel dates can be computed without any risk of
+    /// aborting.  For DateTimes, a resulting local time in a DST gap is moved forward by the
+    /// length of the gap (as with `DstPolicy::ShiftForward`) and the range is that of
+    /// `DateTime::<Utc>::MIN_UTC` to `MAX_UTC`.
+    fn saturating_add_months(&self, num_months: i64) -> Self;
+
+    /// Add a positive or negative number of months to self like `add_months`, except that if self
+    /// is the last day of its month, the result is the last day of the resulting month (so
+    /// January 31st + 1 month is February 28th, and February 28th + 1 month is March 31st).  This
+    /// suits schedules such as "the last day of every month".  Panics if the result is out of
+    /// range.
+    fn add_months_eom(&self, num_months: i32) -> Self;
+
+    /// Add a positive or negative number of months to self, applying `policy` if the day of the
+    /// month doesn't exist in the resulting month.  Returns `ChronoUtilsError::InvalidDay` if it
+    /// doesn't and the policy is `OverflowPolicy::Reject`, and `ChronoUtilsError::OutOfRange` if
+    /// the result is out of range.
+    fn add_months_with(&self, num_months: i32,
+                       policy: OverflowPolicy) -> Result<Self, ChronoUtilsError> where Self: Sized;
+
     /// Set the day of the month and return the resulting DateTime.  If the day cannot be set,
     /// because there is no such day in the month, etc., it will instead be set to the last
     /// day of the month (for example, using `with_closest_day(30)` on a February DateTime will
     /// result in a DateTime set to February 28 (non-leap year) or February 29 (leap year).
+    ///
+    /// Panics if `day` is 0 (see `try_with_closest_day`).
     fn with_closest_day(&self, day: u32) -> Self;
+
+    /// Set the day of the month the same way as `with_closest_day`, but return
+    /// `ChronoUtilsError::InvalidDay` instead of panicking if `day` is 0 (or `OutOfRange` if a
+    /// DateTime's resulting local time doesn't exist).  Days over 31 are treated as 31.
+    fn try_with_closest_day(&self, day: u32) -> Result<Self, ChronoUtilsError> where Self: Sized;
+
+    /// Returns the number of days in self's month (28 to 31).
+    fn days_in_month(&self) -> u32;
+
+    /// Returns self moved to the first day of its month, keeping the time of day.
+    fn first_day_of_month(&self) -> Self;
+
+    /// Returns self moved to the last day of its month (28th to 31st, including February 29th in
+    /// leap years), keeping the time of day.
+    fn last_day_of_month(&self) -> Self;
+
+    /// Returns self moved to the `n`th `weekday` of its month (so `nth_weekday_of_month(Tue, 2)`
+    /// is the second Tuesday), keeping the time of day.  Returns `None` if the month has no such
+    /// day (such as a 5th Monday in a month with only four, or `n` being 0), or a DateTime's
+    /// resulting local time doesn't exist.
+    fn nth_weekday_of_month(&self, weekday: Weekday, n: u32) -> Option<Self> where Self: Sized;
+
+    /// Returns self moved to the first `weekday` of its month, keeping the time of day.  Panics
+    /// if a DateTime's resulting local time doesn't exist.
+    fn first_weekday_of_month(&self, weekday: Weekday) -> Self;
+
+    /// Returns self moved to the last `weekday` of its month, keeping the time of day.  Panics if
+    /// a DateTime's resulting local time doesn't exist.
+    fn last_weekday_of_month(&self, weekday: Weekday) -> Self;
+
+    /// Returns the number of times `weekday` occurs in self's month (4 or 5).
+    fn weekday_occurrences_in_month(&self, weekday: Weekday) -> u32;
+
+    /// Returns the next value after self (keeping the time of day) on `day` of the month, or on
+    /// the last day of months which are too short, as for billing anchored on the 31st.  If self
+    /// is on or past that day this month, the result is in the following month.  Panics if `day`
+    /// is 0 or the result is out of range.
+    fn next_day_of_month(&self, day: u32) -> Self;
+
+    /// Returns the last value before self (keeping the time of day) on `day` of the month, or on
+    /// the last day of months which are too short.  Panics if `day` is 0 or the result is out of
+    /// range.
+    fn previous_day_of_month(&self, day: u32) -> Self;
+
+    /// Returns midnight at the start of the first day of self's month.  For DateTimes, if
+    /// midnight doesn't exist because of a DST transition, the first instant of the day is
+    /// returned instead.  (For NaiveDates this is the same as `first_day_of_month`.)
+    fn start_of_month(&self) -> Self;
+
+    /// Returns midnight at the start of the first day of the following month, so that
+    /// `start_of_month()..start_of_next_month()` is the half-open interval covering the whole
+    /// month.  DST transitions are handled as in `start_of_month`.  Panics if the result is out of
+    /// range.
+    fn start_of_next_month(&self) -> Self;
+
+    /// Returns an iterator over one value per month from self's month, anchored to the first of
+    /// each month or to self's day of the month (see `MonthAnchor`), stopping before the first
+    /// value which is not before `end`.  Values are always computed from self, so clamped days
+    /// don't drift.
+    fn iter_months(&self, end: &Self, anchor: MonthAnchor) -> MonthIterator<Self>
+        where Self: Sized + Clone {
+        MonthIterator::new(self.clone(), end.clone(), anchor)
+    }
+
+    /// Returns an unbounded iterator over one value per month from self's month, anchored as for
+    /// `iter_months`.  It only ends if the values go out of range, so use it with `take` or
+    /// `take_while`.
+    fn iter_months_from(&self, anchor: MonthAnchor) -> MonthsFrom<Self> where Self: Sized + Clone {
+        MonthsFrom::new(self.clone(), anchor)
+    }
+}
+
+/// This trait defines the number of whole months between two values, for the same pairs of types
+/// as `YearCalculations`: DateTimes with DateTimes in any timezone (as UTC dates), NaiveDates and
+/// NaiveDateTimes with values of the same type, chrono's deprecated Dates with other Dates (as the
+/// UTC dates of their local midnights), and `(year, month)` pairs with other pairs.
+pub trait MonthDifference<Rhs = Self> {
+    /// Returns the number of whole months between self and `b`, counting only completed months
+    /// in the same way `YearCalculations::years_since` counts years: a month is completed once
+    /// the day of the month is reached (or on the last day of a shorter month).  The result is
+    /// negative if `b` is later than self.
+    fn months_since(&self, b: &Rhs) -> i32;
+}
+
+/// Returns the first day of the month of `date` and the first day of the following month.
+fn month_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first = date.with_day(1).expect("Every month has a first day");
+    let next = first.checked_add_months(Months::new(1))
+        .expect("Value invalid: the resulting date is out of range");
+    (first, next)
+}
+
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Tz> {
+    resolve_local(tz, &date.and_time(NaiveTime::MIN), DstPolicy::ShiftForward)
+        .expect("Value invalid: the resulting date is out of range")
+}
+
+/// The result of `MonthCalculations::add_months_reversible`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MonthShift<T> {
+    /// The result of the addition, as returned by `add_months`.
+    pub result: T,
+    /// The number of months which were added.
+    pub num_months: i32,
+    /// The original day of the month, if it didn't exist in the resulting month and was clamped
+    /// to the last day.
+    pub clamped_from: Option<u32>,
+}
+
+impl<T: MonthCalculations> MonthShift<T> {
+    /// Returns true if the day of the month was clamped by the addition.
+    pub fn was_clamped(&self) -> bool {
+        self.clamped_from.is_some()
+    }
+
+    /// Subtract the added months from the result again, restoring the original day of the month
+    /// if it was clamped, and so returning the value the months were added to.
+    pub fn undo(&self) -> T {
+        let back = self.result.sub_months(self.num_months);
+        match self.clamped_from {
+            Some(day) => back.with_closest_day(day),
+            None => back,
+        }
+    }
+}
+
+fn add_months_datelike<T: Datelike>(dt: &T, num_months: i64) -> T {
+    try_add_months_datelike(dt, num_months)
+        .expect("Value invalid: the resulting date is out of range")
+}
+
+fn add_months_reversible_datelike<T: Datelike>(dt: &T, num_months: i32) -> MonthShift<T> {
+    let result = add_months_datelike(dt, num_months as i64);
+    let clamped_from = if result.day() != dt.day() { Some(dt.day()) } else { None };
+    MonthShift { result, num_months, clamped_from }
+}
+
+fn try_add_months_datelike<T: Datelike>(dt: &T, num_months: i64) -> Result<T, ChronoUtilsError> {
+    let (year, month) = shift_year_month(dt.year(), dt.month(), num_months)
+        .ok_or(ChronoUtilsError::OutOfRange)?;
+    move_to_month(dt, year, month)
+}
+
+/// Returns `dt` moved to the given year and month, keeping the day of the month where possible
+/// and otherwise clamping it to the last day of the month.
+fn move_to_month<T: Datelike>(dt: &T, year: i32, month: u32) -> Result<T, ChronoUtilsError> {
+    // Move to the first of the month before changing the year and month, so that the day always
+    // exists in between (e.g. February 29th moving to a non-leap year).
+    dt.with_day(1)
+        .and_then(|first| first.with_year(year))
+        .and_then(|first| first.with_month(month))
+        .and_then(|first| closest_day_datelike(&first, dt.day()))
+        .ok_or(ChronoUtilsError::OutOfRange)
+}
+
+/// A number of months split into whole years and remaining months, so that it can be applied to
+/// many values without splitting it again for each.
+#[derive(Clone, Copy, Debug)]
+struct MonthDelta {
+    years: i32,
+    months: u32,
+}
+
+impl MonthDelta {
+    fn new(num_months: i32) -> MonthDelta {
+        MonthDelta { years: num_months.div_euclid(12), months: num_months.rem_euclid(12) as u32 }
+    }
+
+    fn apply<T: Datelike>(&self, dt: &T) -> T {
+        let month0 = dt.month0() + self.months;
+        let year = dt.year().checked_add(self.years)
+            .and_then(|year| year.checked_add(if month0 >= 12 { 1 } else { 0 }))
+            .ok_or(ChronoUtilsError::OutOfRange);
+        year.and_then(|year| move_to_month(dt, year, month0 % 12 + 1))
+            .expect("Value invalid: the resulting date is out of range")
+    }
+}
+
+/// Add the same number of months to each of `dates`, the same way as
+/// `MonthCalculations::add_months`, splitting the offset into years and months only once for the
+/// whole batch.  The dates are shifted lazily as the iterator is consumed, and it panics if a
+/// result is out of range.
+pub fn add_months_all<T, I>(dates: I, num_months: i32) -> impl Iterator<Item = T>
+    where T: Datelike, I: IntoIterator<Item = T> {
+    let delta = MonthDelta::new(num_months);
+    dates.into_iter().map(move |dt| delta.apply(&dt))
+}
+
+/// Add the same number of months to each of `dates` in place, as `add_months_all` does.  Panics
+/// if a result is out of range, leaving the dates before it shifted and the rest unchanged.
+pub fn add_months_in_place<T: Datelike>(dates: &mut [T], num_months: i32) {
+    let delta = MonthDelta::new(num_months);
+    for dt in dates.iter_mut() {
+        *dt = delta.apply(dt);
+    }
+}
+
+fn saturating_add_months_datelike<T: Datelike>(dt: &T, num_months: i64, min: T, max: T) -> T {
+    try_add_months_datelike(dt, num_months).unwrap_or(if num_months < 0 { min } else { max })
+}
+
+/// Returns the earliest representable DateTime if `num_months` is negative, and otherwise the
+/// latest.
+fn utc_bound(num_months: i64) -> DateTime<Utc> {
+    if num_months < 0 { DateTime::<Utc>::MIN_UTC } else { DateTime::<Utc>::MAX_UTC }
+}
+
+fn add_months_eom_datelike<T: Datelike>(dt: &T, num_months: i32) -> T {
+    let shifted = add_months_datelike(dt, num_months as i64);
+    let is_month_end = NaiveDate::from_ymd_opt(dt.year(), dt.month(), dt.day())
+        .is_some_and(|date| is_last_day_of_month(&date));
+    if is_month_end { with_closest_day(&shifted, 31) } else { shifted }
+}
+
+pub(crate) fn add_months_with_datelike<T: Datelike>(dt: &T, num_months: i32, policy: OverflowPolicy)
+                                                    -> Result<T, ChronoUtilsError> {
+    let (year, month) = shift_year_month(dt.year(), dt.month(), num_months as i64)
+        .ok_or(ChronoUtilsError::OutOfRange)?;
+    let date = resolve_day(year, month, dt.day(), policy)?;
+    dt.with_day(1)
+        .and_then(|first| first.with_year(date.year()))
+        .and_then(|first| first.with_month(date.month()))
+        .and_then(|first| first.with_day(date.day()))
+        .ok_or(ChronoUtilsError::OutOfRange)
+}
+
+/// Set the day of the month of any `Datelike` value, clamping it to the last day of the month
+/// the same way as `MonthCalculations::with_closest_day`, so that wrapper types implementing
+/// `Datelike` get it without implementing `MonthCalculations`.  Panics if `day` is 0, or the
+/// value's `with_day` fails for the clamped day (see `try_with_closest_day`).
+pub fn with_closest_day<T: Datelike>(dt: &T, day: u32) -> T {
+    try_with_closest_day(dt, day)
+        .unwrap_or_else(|e| panic!("Cannot set the day of the month to {}: {}", day, e))
+}
+
+/// Set the day of the month of any `Datelike` value the same way as `with_closest_day`, but
+/// return `ChronoUtilsError::InvalidDay` instead of panicking if `day` is 0, and
+/// `ChronoUtilsError::OutOfRange` if the value's `with_day` fails (for example, because the
+/// resulting local time doesn't exist in a DateTime's timezone).  Days over 31 are treated as 31.
+pub fn try_with_closest_day<T: Datelike>(dt: &T, day: u32) -> Result<T, ChronoUtilsError> {
+    if day == 0 {
+        return Err(ChronoUtilsError::InvalidDay);
+    }
+    closest_day_datelike(dt, day).ok_or(ChronoUtilsError::OutOfRange)
+}
+
+fn next_day_of_month_datelike<T: Datelike>(dt: &T, day: u32) -> T {
+    let this_month = with_closest_day(dt, day);
+    if this_month.day() > dt.day() {
+        this_month
+    } else {
+        with_closest_day(&add_months_datelike(dt, 1), day)
+    }
+}
+
+fn previous_day_of_month_datelike<T: Datelike>(dt: &T, day: u32) -> T {
+    let this_month = with_closest_day(dt, day);
+    if this_month.day() < dt.day() {
+        this_month
+    } else {
+        with_closest_day(&add_months_datelike(dt, -1), day)
+    }
+}
+
+fn weekday_occurrences_datelike<T: Datelike>(dt: &T, weekday: Weekday) -> u32 {
+    let (year, month) = (dt.year(), dt.month());
+    let first = nth_weekday(year, month, weekday, 1).expect("Every month has every weekday");
+    let last_day = days_in_month(year, month).expect("Every date has a valid month");
+    (last_day - first.day()) / 7 + 1
+}
+
+fn nth_weekday_of_month_datelike<T: Datelike>(dt: &T, weekday: Weekday, nth: i32) -> Option<T> {
+    dt.with_day(nth_weekday(dt.year(), dt.month(), weekday, nth)?.day())
+}
+
+fn closest_day_datelike<T: Datelike>(dt: &T, day: u32) -> Option<T> {
+    dt.with_day(day.min(days_in_month(dt.year(), dt.month())?))
 }
 
 impl<Tz> MonthCalculations for DateTime<Tz> where Tz: TimeZone {
     fn add_months(&self, num_months: i32) -> Self {
-        let abs_new_month = self.month0() as i32 + num_months;
+        add_months_datelike(self, num_months as i64)
+    }
+
+    fn sub_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, -(num_months as i64))
+    }
+
+    fn add_months_reversible(&self, num_months: i32) -> MonthShift<Self> {
+        add_months_reversible_datelike(self, num_months)
+    }
+
+    fn try_add_months(&self, num_months: i32) -> Result<Self, ChronoUtilsError> {
+        let local = try_add_months_datelike(&self.naive_local(), num_months as i64)?;
+        match self.timezone().from_local_datetime(&local) {
+            LocalResult::Single(dt) => Ok(dt),
+            LocalResult::None => Err(ChronoUtilsError::NonexistentLocalTime),
+            LocalResult::Ambiguous(..) => Err(ChronoUtilsError::AmbiguousLocalTime),
+        }
+    }
+
+    fn add_months_checked(&self, num_months: i64) -> Option<Self> {
+        try_add_months_datelike(self, num_months).ok()
+    }
+
+    fn saturating_add_months(&self, num_months: i64) -> Self {
+        let tz = self.timezone();
+        try_add_months_datelike(&self.naive_local(), num_months).ok()
+            .and_then(|local| resolve_local(&tz, &local, DstPolicy::ShiftForward))
+            .unwrap_or_else(|| utc_bound(num_months).with_timezone(&tz))
+    }
+
+    fn add_months_eom(&self, num_months: i32) -> Self {
+        add_months_eom_datelike(self, num_months)
+    }
+
+    fn add_months_with(&self, num_months: i32,
+                       policy: OverflowPolicy) -> Result<Self, ChronoUtilsError> {
+        add_months_with_datelike(self, num_months, policy)
+    }
+
+    fn with_closest_day(&self, day: u32) -> Self {
+        with_closest_day(self, day)
+    }
+
+    fn try_with_closest_day(&self, day: u32) -> Result<Self, ChronoUtilsError> {
+        try_with_closest_day(self, day)
+    }
+
+    fn days_in_month(&self) -> u32 {
+        days_in_month(self.year(), self.month()).expect("Every date has a valid month")
+    }
+
+    fn first_day_of_month(&self) -> Self {
+        with_closest_day(self, 1)
+    }
+
+    fn last_day_of_month(&self) -> Self {
+        with_closest_day(self, 31)
+    }
+
+    fn nth_weekday_of_month(&self, weekday: Weekday, n: u32) -> Option<Self> {
+        nth_weekday_of_month_datelike(self, weekday, i32::try_from(n).ok()?)
+    }
+
+    fn first_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, 1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn last_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, -1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn weekday_occurrences_in_month(&self, weekday: Weekday) -> u32 {
+        weekday_occurrences_datelike(self, weekday)
+    }
+
+    fn next_day_of_month(&self, day: u32) -> Self {
+        next_day_of_month_datelike(self, day)
+    }
+
+    fn previous_day_of_month(&self, day: u32) -> Self {
+        previous_day_of_month_datelike(self, day)
+    }
+
+    fn start_of_month(&self) -> Self {
+        start_of_day(self.date_naive().with_day(1).expect("Every month has a first day"),
+                     &self.timezone())
+    }
+
+    fn start_of_next_month(&self) -> Self {
+        start_of_day(month_bounds(self.date_naive()).1, &self.timezone())
+    }
+}
+
+impl MonthCalculations for NaiveDate {
+    fn add_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, num_months as i64)
+    }
+
+    fn sub_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, -(num_months as i64))
+    }
 
-        // This will be positive to move years forward, negative to move the years back.  In
-        // the negative case, a full year will have to be moved back in addition to how many
-        // are set here, because a negative value means we have to "borrow" a year (and hence
-        // move the clock back an extra year to compensate) in order to make the months value
-        // positive again.  The floor function will take care of this by lowering the value to the
-        // next lower (i.e. higher absolute value) negative value.
-        let years_change = (abs_new_month as f64 / 12f64).floor() as i32;
+    fn add_months_reversible(&self, num_months: i32) -> MonthShift<Self> {
+        add_months_reversible_datelike(self, num_months)
+    }
+
+    fn try_add_months(&self, num_months: i32) -> Result<Self, ChronoUtilsError> {
+        try_add_months_datelike(self, num_months as i64)
+    }
 
-        // If start month < 0, add 12 to the modulus of the month (to make up for the year we
-        // borrowed in the "floor" function above); since start month < 0, this will end up in a
-        // value lower than 12).
-        let actual_new_month = abs_new_month % 12 + { if abs_new_month >= 0 { 0 } else { 12 }};
+    fn add_months_checked(&self, num_months: i64) -> Option<Self> {
+        try_add_months_datelike(self, num_months).ok()
+    }
 
-        let new_date_year = self.with_year(self.year() + years_change).unwrap();
+    fn saturating_add_months(&self, num_months: i64) -> Self {
+        saturating_add_months_datelike(self, num_months, NaiveDate::MIN, NaiveDate::MAX)
+    }
+
+    fn add_months_eom(&self, num_months: i32) -> Self {
+        add_months_eom_datelike(self, num_months)
+    }
 
-        new_date_year.with_day(1).unwrap()
-            .with_month0(actual_new_month as u32)
-            .expect("Value invalid: This means there is a very bad bug in the calculations!")
-            .with_closest_day(new_date_year.day())
+    fn add_months_with(&self, num_months: i32,
+                       policy: OverflowPolicy) -> Result<Self, ChronoUtilsError> {
+        add_months_with_datelike(self, num_months, policy)
     }
 
     fn with_closest_day(&self, day: u32) -> Self {
-        // Make sure the limit is 31 (as no month has more than 31 days)
-        let check_day = if day > 31 { 31 } else { day };
-
-        // Now check the day.  If the new month is :
-        // * 0, 2, 4, 6, 7, 9, 11 (Jan, Mar, May, Jul, Aug, Oct, Dec)=> Use the day as-is,
-        // * 3, 5, 8, or 10 (Feb, Apr, Jun, Sept, Nov) => Day is capped at 30,
-        // * 1 (Feb) => Check leap year.  If yes, cap the day at 29, otherwise cap at 28.
-        let actual_day = match self.month0() {
-            0 | 2 | 4 | 6 | 7 | 9 | 11 => check_day,
-            3 | 5 | 8 | 10 => if check_day > 30 { 30 } else { check_day },
-            1 => {
-                let is_leapyear = self
-                    .with_day(1).unwrap()
-                    .with_month(2).unwrap()
-                    .with_day(29).is_some();
-                if is_leapyear {
-                    if check_day >= 30 { 29 } else { check_day }
-                } else {
-                    if check_day >= 29 { 28 } else { check_day }
-                }
-            },
-            m => panic!("Month value of {} is invalid!", m),
-        };
-        self.with_day(actual_day)
-            .expect("Value invalid: This means there is a very bad bug in the calculations!")
+        with_closest_day(self, day)
+    }
+
+    fn try_with_closest_day(&self, day: u32) -> Result<Self, ChronoUtilsError> {
+        try_with_closest_day(self, day)
+    }
+
+    fn days_in_month(&self) -> u32 {
+        days_in_month(self.year(), self.month()).expect("Every date has a valid month")
+    }
+
+    fn first_day_of_month(&self) -> Self {
+        with_closest_day(self, 1)
+    }
+
+    fn last_day_of_month(&self) -> Self {
+        with_closest_day(self, 31)
+    }
+
+    fn nth_weekday_of_month(&self, weekday: Weekday, n: u32) -> Option<Self> {
+        nth_weekday_of_month_datelike(self, weekday, i32::try_from(n).ok()?)
+    }
+
+    fn first_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, 1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn last_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, -1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn weekday_occurrences_in_month(&self, weekday: Weekday) -> u32 {
+        weekday_occurrences_datelike(self, weekday)
+    }
+
+    fn next_day_of_month(&self, day: u32) -> Self {
+        next_day_of_month_datelike(self, day)
+    }
+
+    fn previous_day_of_month(&self, day: u32) -> Self {
+        previous_day_of_month_datelike(self, day)
+    }
+
+    fn start_of_month(&self) -> Self {
+        self.first_day_of_month()
+    }
+
+    fn start_of_next_month(&self) -> Self {
+        month_bounds(*self).1
+    }
+}
+
+impl MonthCalculations for NaiveDateTime {
+    fn add_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, num_months as i64)
+    }
+
+    fn sub_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, -(num_months as i64))
+    }
+
+    fn add_months_reversible(&self, num_months: i32) -> MonthShift<Self> {
+        add_months_reversible_datelike(self, num_months)
+    }
+
+    fn try_add_months(&self, num_months: i32) -> Result<Self, ChronoUtilsError> {
+        try_add_months_datelike(self, num_months as i64)
+    }
+
+    fn add_months_checked(&self, num_months: i64) -> Option<Self> {
+        try_add_months_datelike(self, num_months).ok()
+    }
+
+    fn saturating_add_months(&self, num_months: i64) -> Self {
+        saturating_add_months_datelike(self, num_months, NaiveDateTime::MIN, NaiveDateTime::MAX)
+    }
+
+    fn add_months_eom(&self, num_months: i32) -> Self {
+        add_months_eom_datelike(self, num_months)
+    }
+
+    fn add_months_with(&self, num_months: i32,
+                       policy: OverflowPolicy) -> Result<Self, ChronoUtilsError> {
+        add_months_with_datelike(self, num_months, policy)
+    }
+
+    fn with_closest_day(&self, day: u32) -> Self {
+        with_closest_day(self, day)
+    }
+
+    fn try_with_closest_day(&self, day: u32) -> Result<Self, ChronoUtilsError> {
+        try_with_closest_day(self, day)
+    }
+
+    fn days_in_month(&self) -> u32 {
+        days_in_month(self.year(), self.month()).expect("Every date has a valid month")
+    }
+
+    fn first_day_of_month(&self) -> Self {
+        with_closest_day(self, 1)
+    }
+
+    fn last_day_of_month(&self) -> Self {
+        with_closest_day(self, 31)
+    }
+
+    fn nth_weekday_of_month(&self, weekday: Weekday, n: u32) -> Option<Self> {
+        nth_weekday_of_month_datelike(self, weekday, i32::try_from(n).ok()?)
+    }
+
+    fn first_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, 1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn last_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, -1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn weekday_occurrences_in_month(&self, weekday: Weekday) -> u32 {
+        weekday_occurrences_datelike(self, weekday)
+    }
+
+    fn next_day_of_month(&self, day: u32) -> Self {
+        next_day_of_month_datelike(self, day)
+    }
+
+    fn previous_day_of_month(&self, day: u32) -> Self {
+        previous_day_of_month_datelike(self, day)
+    }
+
+    fn start_of_month(&self) -> Self {
+        self.date().first_day_of_month().and_time(NaiveTime::MIN)
+    }
+
+    fn start_of_next_month(&self) -> Self {
+        month_bounds(self.date()).1.and_time(NaiveTime::MIN)
+    }
+}
+
+// `Date` is deprecated in chrono, but is still implemented for code which hasn't moved off it.
+#[allow(deprecated)]
+impl<Tz> MonthCalculations for chrono::Date<Tz> where Tz: TimeZone {
+    fn add_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, num_months as i64)
+    }
+
+    fn sub_months(&self, num_months: i32) -> Self {
+        add_months_datelike(self, -(num_months as i64))
+    }
+
+    fn add_months_reversible(&self, num_months: i32) -> MonthShift<Self> {
+        add_months_reversible_datelike(self, num_months)
+    }
+
+    fn try_add_months(&self, num_months: i32) -> Result<Self, ChronoUtilsError> {
+        try_add_months_datelike(self, num_months as i64)
+    }
+
+    fn add_months_checked(&self, num_months: i64) -> Option<Self> {
+        try_add_months_datelike(self, num_months).ok()
+    }
+
+    fn saturating_add_months(&self, num_months: i64) -> Self {
+        try_add_months_datelike(self, num_months)
+            .unwrap_or_else(|_| utc_bound(num_months).date().with_timezone(&self.timezone()))
+    }
+
+    fn add_months_eom(&self, num_months: i32) -> Self {
+        add_months_eom_datelike(self, num_months)
+    }
+
+    fn add_months_with(&self, num_months: i32,
+                       policy: OverflowPolicy) -> Result<Self, ChronoUtilsError> {
+        add_months_with_datelike(self, num_months, policy)
+    }
+
+    fn with_closest_day(&self, day: u32) -> Self {
+        with_closest_day(self, day)
+    }
+
+    fn try_with_closest_day(&self, day: u32) -> Result<Self, ChronoUtilsError> {
+        try_with_closest_day(self, day)
+    }
+
+    fn days_in_month(&self) -> u32 {
+        days_in_month(self.year(), self.month()).expect("Every date has a valid month")
+    }
+
+    fn first_day_of_month(&self) -> Self {
+        with_closest_day(self, 1)
+    }
+
+    fn last_day_of_month(&self) -> Self {
+        with_closest_day(self, 31)
+    }
+
+    fn nth_weekday_of_month(&self, weekday: Weekday, n: u32) -> Option<Self> {
+        nth_weekday_of_month_datelike(self, weekday, i32::try_from(n).ok()?)
+    }
+
+    fn first_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, 1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn last_weekday_of_month(&self, weekday: Weekday) -> Self {
+        nth_weekday_of_month_datelike(self, weekday, -1)
+            .expect("Value invalid: the resulting date is out of range")
+    }
+
+    fn weekday_occurrences_in_month(&self, weekday: Weekday) -> u32 {
+        weekday_occurrences_datelike(self, weekday)
+    }
+
+    fn next_day_of_month(&self, day: u32) -> Self {
+        next_day_of_month_datelike(self, day)
+    }
+
+    fn previous_day_of_month(&self, day: u32) -> Self {
+        previous_day_of_month_datelike(self, day)
+    }
+
+    fn start_of_month(&self) -> Self {
+        self.first_day_of_month()
+    }
+
+    fn start_of_next_month(&self) -> Self {
+        add_months_datelike(&self.first_day_of_month(), 1)
+    }
+}
+
+/// Add a positive or negative number of months to a `(year, month)` pair, for code which tracks
+/// months without a day or time.  Returns `None` if the month is not 1 to 12, or the resulting
+/// year doesn't fit in an `i32`.
+pub fn add_months_to_year_month(year_month: (i32, u32),
+                                num_months: i32) -> Option<(i32, u32)> {
+    let (year, month) = year_month;
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+    shift_year_month(year, month, num_months as i64)
+}
+
+/// Add a positive or negative number of months to the wall-clock time of `dt` in its own
+/// timezone, the same way as `add_months`, and then find the resulting wall-clock time in that
+/// timezone again.  If the time doesn't exist there (it falls in a DST gap) or exists twice (it
+/// falls in a DST overlap), `policy` decides the result.  Returns `None` if the result is out of
+/// range or `policy` rejects it.
+///
+/// `add_months` itself panics in those cases, since chrono can't set the date of a DateTime to a
+/// local time which doesn't map to a single instant.
+pub fn add_months_local<Tz: TimeZone>(dt: &DateTime<Tz>, num_months: i32,
+                                      policy: DstPolicy) -> Option<DateTime<Tz>> {
+    let local = try_add_months_datelike(&dt.naive_local(), num_months as i64).ok()?;
+    resolve_local(&dt.timezone(), &local, policy)
+}
+
+/// Which calendar months are counted in when adding months to a DateTime, as chosen with
+/// `add_months_on`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CalendarBasis {
+    /// Count months in the DateTime's own timezone, keeping its local date and time of day (as
+    /// `add_months` does).  March 30th 22:00 in UTC-5 + 1 month is April 30th 22:00 in UTC-5.
+    Local,
+    /// Count months on the UTC date and time of the DateTime, and then convert the result back to
+    /// its timezone.  March 30th 22:00 in UTC-5 is March 31st 03:00 UTC, so + 1 month is April
+    /// 30th 03:00 UTC, which is April 29th 22:00 in UTC-5.  Across DST changes, the UTC time of
+    /// day is kept instead of the local one.
+    Utc,
+}
+
+/// Add a positive or negative number of months to `dt`, counting months on the calendar given by
+/// `basis`, so that code scheduling across timezones can choose which date and time of day are
+/// kept.  Returns `None` if the result is out of range, or (with `CalendarBasis::Local`) the
+/// local result doesn't map to a single instant (see `add_months_local` to resolve that).
+pub fn add_months_on<Tz: TimeZone>(dt: &DateTime<Tz>, num_months: i32,
+                                   basis: CalendarBasis) -> Option<DateTime<Tz>> {
+    match basis {
+        CalendarBasis::Local => dt.try_add_months(num_months).ok(),
+        CalendarBasis::Utc => {
+            let utc = try_add_months_datelike(&dt.naive_utc(), num_months as i64).ok()?;
+            Some(dt.timezone().from_utc_datetime(&utc))
+        },
     }
 }
 
+fn is_last_day_of_month(date: &NaiveDate) -> bool {
+    date.day() == date.days_in_month()
+}
+
+impl<Tz, Tz2> MonthDifference<DateTime<Tz2>> for DateTime<Tz>
+    where Tz: TimeZone, Tz2: TimeZone {
+    fn months_since(&self, b: &DateTime<Tz2>) -> i32 {
+        completed_months(&self.with_timezone(&Utc).date_naive(),
+                         &b.with_timezone(&Utc).date_naive())
+    }
+}
+
+impl MonthDifference for NaiveDate {
+    fn months_since(&self, b: &NaiveDate) -> i32 {
+        completed_months(self, b)
+    }
+}
+
+impl MonthDifference for NaiveDateTime {
+    fn months_since(&self, b: &NaiveDateTime) -> i32 {
+        completed_months(&self.date(), &b.date())
+    }
+}
+
+/// Returns the UTC date of `date`'s local midnight, like a DateTime at the start of the day.
+#[allow(deprecated)]
+fn utc_date_of<Tz: TimeZone>(date: &chrono::Date<Tz>) -> NaiveDate {
+    let midnight = date.naive_local().and_time(NaiveTime::MIN);
+    midnight.checked_sub_offset(date.offset().fix()).map_or(date.naive_local(), |utc| utc.date())
+}
+
+#[allow(deprecated)]
+impl<Tz, Tz2> MonthDifference<chrono::Date<Tz2>> for chrono::Date<Tz>
+    where Tz: TimeZone, Tz2: TimeZone {
+    fn months_since(&self, b: &chrono::Date<Tz2>) -> i32 {
+        completed_months(&utc_date_of(self), &utc_date_of(b))
+    }
+}
+
+/// `(year, month)` pairs count every month, having no day of the month to wait for.
+impl MonthDifference for (i32, u32) {
+    fn months_since(&self, b: &(i32, u32)) -> i32 {
+        (self.0 - b.0) * 12 + self.1 as i32 - b.1 as i32
+    }
+}
+
+/// Returns the number of whole months from `b` to `a` (negative if `a` is earlier), counting only
+/// completed months.  A month is completed once the day of the month of the earlier date is
+/// reached, or on the last day of the month if that day doesn't exist (so the 31st completes a
+/// month on April 30th, the same way `add_months` clamps).
+pub(crate) fn completed_months(a: &NaiveDate, b: &NaiveDate) -> i32 {
+    if a < b {
+        return -completed_months(b, a);
+    }
+    let months = (a.year() - b.year()) * 12 + a.month() as i32 - b.month() as i32;
+    if a.day() >= b.day() || is_last_day_of_month(a) { months } else { months - 1 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,4 +1010,472 @@ mod tests {
         assert_eq!(new_date.month(), 3);
         assert_eq!(new_date.year(), 2018);
     }
+
+    #[test]
+    fn test_naive_date_time() {
+        let noon = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(12, 0, 0).unwrap()
+        };
+        let test_date = noon(2016, 1, 31);
+        assert_eq!(test_date.add_months(1), noon(2016, 2, 29));
+        assert_eq!(test_date.add_months(-14), noon(2014, 11, 30));
+        assert_eq!(test_date.with_closest_day(45), noon(2016, 1, 31));
+        assert_eq!(noon(2014, 11, 3).with_closest_day(31), noon(2014, 11, 30));
+    }
+
+    #[test]
+    fn test_naive_date() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2017, 3, 31).add_months(1), date(2017, 4, 30));
+        assert_eq!(date(2016, 1, 31).add_months(1), date(2016, 2, 29));
+        assert_eq!(date(2018, 3, 15).add_months(-23), date(2016, 4, 15));
+        assert_eq!(date(2018, 2, 10).with_closest_day(31), date(2018, 2, 28));
+    }
+
+    #[test]
+    fn test_leap_day_whole_years() {
+        let test_date = DateTime::parse_from_rfc3339("2016-02-29T12:00:00Z").unwrap();
+        let new_date = test_date.add_months(12);
+        assert_eq!(new_date.month(), 2);
+        assert_eq!(new_date.day(), 28);
+        assert_eq!(new_date.year(), 2017);
+    }
+
+    #[test]
+    fn test_try_add_months() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.try_add_months(-23), Ok(test_date.add_months(-23)));
+        assert_eq!(test_date.try_add_months(i32::MAX), Err(ChronoUtilsError::OutOfRange));
+        let max = NaiveDate::MAX;
+        assert_eq!(max.try_add_months(1), Err(ChronoUtilsError::OutOfRange));
+        assert_eq!(max.try_add_months(-1).map(|d| d.month()), Ok(11));
+    }
+
+    #[test]
+    fn test_add_months_with() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let jan_31 = date(2018, 1, 31);
+        assert_eq!(jan_31.add_months_with(1, OverflowPolicy::ClampToLastDay),
+                   Ok(date(2018, 2, 28)));
+        assert_eq!(jan_31.add_months_with(1, OverflowPolicy::RollForwardToNextMonth),
+                   Ok(date(2018, 3, 3)));
+        assert_eq!(date(2016, 1, 31).add_months_with(1, OverflowPolicy::RollForwardToNextMonth),
+                   Ok(date(2016, 3, 2)));
+        assert_eq!(jan_31.add_months_with(1, OverflowPolicy::Reject),
+                   Err(ChronoUtilsError::InvalidDay));
+        assert_eq!(jan_31.add_months_with(1, OverflowPolicy::Skip), Ok(date(2018, 3, 31)));
+        assert_eq!(jan_31.add_months_with(3, OverflowPolicy::Skip), Ok(date(2018, 5, 31)));
+        assert_eq!(jan_31.add_months_with(2, OverflowPolicy::Reject), Ok(date(2018, 3, 31)));
+        assert_eq!(jan_31.add_months_with(i32::MAX, OverflowPolicy::Reject),
+                   Err(ChronoUtilsError::OutOfRange));
+    }
+
+    #[test]
+    fn test_add_months_with_date_time() {
+        let test_date = DateTime::parse_from_rfc3339("2018-08-31T12:00:00+09:00").unwrap();
+        let expected = DateTime::parse_from_rfc3339("2018-10-01T12:00:00+09:00").unwrap();
+        assert_eq!(test_date.add_months_with(1, OverflowPolicy::RollForwardToNextMonth),
+                   Ok(expected));
+    }
+
+    #[test]
+    fn test_add_months_eom() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let schedule: Vec<_> = (0..4).map(|n| date(2018, 1, 31).add_months_eom(n)).collect();
+        assert_eq!(schedule, vec![date(2018, 1, 31), date(2018, 2, 28), date(2018, 3, 31),
+                                  date(2018, 4, 30)]);
+        assert_eq!(date(2018, 2, 28).add_months_eom(1), date(2018, 3, 31));
+        assert_eq!(date(2016, 2, 28).add_months_eom(1), date(2016, 3, 28));
+        assert_eq!(date(2018, 4, 30).add_months_eom(-2), date(2018, 2, 28));
+        assert_eq!(date(2018, 1, 30).add_months_eom(2), date(2018, 3, 30));
+
+        let test_date = DateTime::parse_from_rfc3339("2018-06-30T12:00:00Z").unwrap();
+        let expected = DateTime::parse_from_rfc3339("2018-07-31T12:00:00Z").unwrap();
+        assert_eq!(test_date.add_months_eom(1), expected);
+    }
+
+    #[test]
+    fn test_months_since() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        let earlier = DateTime::parse_from_rfc3339("2017-01-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.months_since(&earlier), 14);
+        assert_eq!(earlier.months_since(&test_date), -14);
+        let earlier = DateTime::parse_from_rfc3339("2017-01-16T12:00:00Z").unwrap();
+        assert_eq!(test_date.months_since(&earlier), 13);
+        assert_eq!(earlier.months_since(&test_date), -13);
+        assert_eq!(test_date.months_since(&test_date), 0);
+    }
+
+    #[test]
+    fn test_months_since_utc_and_month_end() {
+        // 2018-03-01T01:00:00+09:00 is still February 28th in UTC.
+        let test_date = DateTime::parse_from_rfc3339("2018-03-01T01:00:00+09:00").unwrap();
+        let jan_31 = DateTime::parse_from_rfc3339("2018-01-31T12:00:00Z").unwrap();
+        assert_eq!(test_date.months_since(&jan_31), 1);
+        let date = NaiveDate::from_ymd_opt(2018, 2, 27).unwrap();
+        assert_eq!(date.months_since(&jan_31.date_naive()), 0);
+        assert_eq!(date.and_hms_opt(0, 0, 0).unwrap().months_since(&jan_31.naive_utc()), 0);
+        assert_eq!((2018, 2).months_since(&(2017, 11)), 3);
+        assert_eq!((2017, 11).months_since(&(2018, 2)), -3);
+    }
+
+    #[test]
+    fn test_sub_months() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 3, 15).sub_months(3), date(2017, 12, 15));
+        assert_eq!(date(2018, 3, 31).sub_months(1), date(2018, 2, 28));
+        assert_eq!(date(2018, 3, 15).sub_months(-3), date(2018, 6, 15));
+        assert_eq!(date(2018, 3, 15).add_months(7).sub_months(7), date(2018, 3, 15));
+        assert_eq!(date(2018, 1, 31).add_months(1).sub_months(1), date(2018, 1, 28));
+    }
+
+    #[test]
+    fn test_add_months_reversible() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let shift = date(2018, 1, 31).add_months_reversible(1);
+        assert_eq!(shift.result, date(2018, 2, 28));
+        assert_eq!(shift.clamped_from, Some(31));
+        assert!(shift.was_clamped());
+        assert_eq!(shift.undo(), date(2018, 1, 31));
+
+        let shift = date(2018, 1, 15).add_months_reversible(-13);
+        assert_eq!(shift.result, date(2016, 12, 15));
+        assert!(!shift.was_clamped());
+        assert_eq!(shift.undo(), date(2018, 1, 15));
+
+        let test_date = DateTime::parse_from_rfc3339("2016-03-30T12:00:00Z").unwrap();
+        assert_eq!(test_date.add_months_reversible(-1).undo(), test_date);
+    }
+
+    #[test]
+    fn test_add_months_checked() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 1, 31).add_months_checked(1), Some(date(2018, 2, 28)));
+        assert_eq!(date(2018, 1, 15).add_months_checked(-3_000_000), Some(date(-247982, 1, 15)));
+        assert_eq!(date(2018, 1, 15).add_months_checked(4_000_000), None);
+        assert_eq!(date(2018, 1, 15).add_months_checked(i64::MAX), None);
+        assert_eq!(date(2018, 1, 15).add_months_checked(i64::MIN), None);
+        assert_eq!(NaiveDate::MIN.add_months_checked(-1), None);
+
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.add_months_checked(4_000_000), None);
+        assert_eq!(test_date.add_months_checked(-24_000).map(|d| d.year()), Some(18));
+    }
+
+    #[test]
+    fn test_try_with_closest_day() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2016, 2, 10).try_with_closest_day(31), Ok(date(2016, 2, 29)));
+        assert_eq!(date(2018, 4, 10).try_with_closest_day(45), Ok(date(2018, 4, 30)));
+        assert_eq!(date(2018, 4, 10).try_with_closest_day(1), Ok(date(2018, 4, 1)));
+        assert_eq!(date(2018, 4, 10).try_with_closest_day(0), Err(ChronoUtilsError::InvalidDay));
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.try_with_closest_day(0), Err(ChronoUtilsError::InvalidDay));
+    }
+
+    #[test]
+    fn test_try_with_closest_day_dst_gap() {
+        use test_tz::Cet;
+        let test_date = Cet.with_ymd_and_hms(2018, 3, 10, 2, 30, 0).unwrap();
+        // 02:30 doesn't exist on March 25th in CET, when clocks go forward.
+        assert_eq!(test_date.try_with_closest_day(25), Err(ChronoUtilsError::OutOfRange));
+        assert_eq!(test_date.try_with_closest_day(24).map(|dt| dt.day()), Ok(24));
+    }
+
+    /// A wrapper type which only implements `Datelike`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Payday(NaiveDate);
+
+    impl Datelike for Payday {
+        fn year(&self) -> i32 { self.0.year() }
+        fn month(&self) -> u32 { self.0.month() }
+        fn month0(&self) -> u32 { self.0.month0() }
+        fn day(&self) -> u32 { self.0.day() }
+        fn day0(&self) -> u32 { self.0.day0() }
+        fn ordinal(&self) -> u32 { self.0.ordinal() }
+        fn ordinal0(&self) -> u32 { self.0.ordinal0() }
+        fn weekday(&self) -> chrono::Weekday { self.0.weekday() }
+        fn iso_week(&self) -> chrono::IsoWeek { self.0.iso_week() }
+        fn with_year(&self, year: i32) -> Option<Payday> { self.0.with_year(year).map(Payday) }
+        fn with_month(&self, month: u32) -> Option<Payday> { self.0.with_month(month).map(Payday) }
+        fn with_month0(&self, month0: u32) -> Option<Payday> {
+            self.0.with_month0(month0).map(Payday)
+        }
+        fn with_day(&self, day: u32) -> Option<Payday> { self.0.with_day(day).map(Payday) }
+        fn with_day0(&self, day0: u32) -> Option<Payday> { self.0.with_day0(day0).map(Payday) }
+        fn with_ordinal(&self, ordinal: u32) -> Option<Payday> {
+            self.0.with_ordinal(ordinal).map(Payday)
+        }
+        fn with_ordinal0(&self, ordinal0: u32) -> Option<Payday> {
+            self.0.with_ordinal0(ordinal0).map(Payday)
+        }
+    }
+
+    #[test]
+    fn test_with_closest_day_generic() {
+        let payday = |m, d| Payday(NaiveDate::from_ymd_opt(2018, m, d).unwrap());
+        assert_eq!(with_closest_day(&payday(2, 10), 31), payday(2, 28));
+        assert_eq!(with_closest_day(&payday(3, 10), 31), payday(3, 31));
+        assert_eq!(try_with_closest_day(&payday(3, 10), 0), Err(ChronoUtilsError::InvalidDay));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot set the day of the month to 0")]
+    fn test_with_closest_day_zero() {
+        NaiveDate::from_ymd_opt(2018, 4, 10).unwrap().with_closest_day(0);
+    }
+
+    #[test]
+    fn test_first_and_last_day_of_month() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2016, 2, 10).first_day_of_month(), date(2016, 2, 1));
+        assert_eq!(date(2016, 2, 10).last_day_of_month(), date(2016, 2, 29));
+        assert_eq!(date(2018, 2, 10).last_day_of_month(), date(2018, 2, 28));
+        assert_eq!(date(2018, 12, 31).last_day_of_month(), date(2018, 12, 31));
+        assert_eq!(date(2018, 2, 10).start_of_next_month(), date(2018, 3, 1));
+        assert_eq!(date(2018, 12, 10).start_of_next_month(), date(2019, 1, 1));
+
+        let test_date = DateTime::parse_from_rfc3339("2018-04-15T12:30:00+09:00").unwrap();
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap();
+        assert_eq!(test_date.first_day_of_month(), at("2018-04-01T12:30:00+09:00"));
+        assert_eq!(test_date.last_day_of_month(), at("2018-04-30T12:30:00+09:00"));
+        assert_eq!(test_date.start_of_month(), at("2018-04-01T00:00:00+09:00"));
+        assert_eq!(test_date.start_of_next_month(), at("2018-05-01T00:00:00+09:00"));
+
+        let noon = date(2018, 4, 15).and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(noon.start_of_month(), date(2018, 4, 1).and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(noon.start_of_next_month(), date(2018, 5, 1).and_hms_opt(0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_start_of_month_across_dst() {
+        use test_tz::Cet;
+        // Midnight in April is CEST, while midnight in March is still CET.
+        let test_date = Cet.with_ymd_and_hms(2018, 4, 15, 12, 0, 0).unwrap();
+        let start = test_date.start_of_month();
+        assert_eq!(start.naive_local(), NaiveDate::from_ymd_opt(2018, 4, 1).unwrap()
+            .and_hms_opt(0, 0, 0).unwrap());
+        let march = Cet.with_ymd_and_hms(2018, 3, 15, 12, 0, 0).unwrap();
+        assert_eq!(march.start_of_next_month(), start);
+        assert_eq!(start.signed_duration_since(march.start_of_month()).num_hours(), 31 * 24 - 1);
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(2018, 1), Some(31));
+        assert_eq!(days_in_month(2018, 2), Some(28));
+        assert_eq!(days_in_month(2016, 2), Some(29));
+        assert_eq!(days_in_month(1900, 2), Some(28));
+        assert_eq!(days_in_month(2000, 2), Some(29));
+        assert_eq!(days_in_month(2018, 11), Some(30));
+        assert_eq!(days_in_month(2018, 0), None);
+        assert_eq!(days_in_month(2018, 13), None);
+        assert_eq!(NaiveDate::from_ymd_opt(2016, 2, 10).unwrap().days_in_month(), 29);
+        let test_date = DateTime::parse_from_rfc3339("2018-04-15T12:30:00+09:00").unwrap();
+        assert_eq!(test_date.days_in_month(), 30);
+    }
+
+    #[test]
+    fn test_is_leap_year() {
+        for year in -500..2500 {
+            assert_eq!(is_leap_year(year), NaiveDate::from_ymd_opt(year, 2, 29).is_some());
+        }
+    }
+
+    #[test]
+    fn test_shift_year_month() {
+        assert_eq!(shift_year_month(2018, 3, 0), Some((2018, 3)));
+        assert_eq!(shift_year_month(2018, 3, 10), Some((2019, 1)));
+        assert_eq!(shift_year_month(2018, 3, -3), Some((2017, 12)));
+        assert_eq!(shift_year_month(0, 1, -1), Some((-1, 12)));
+        assert_eq!(shift_year_month(2018, 3, -24_218), Some((0, 1)));
+        assert_eq!(shift_year_month(i32::MAX, 12, 1), None);
+        assert_eq!(shift_year_month(i32::MIN, 1, -1), None);
+        assert_eq!(shift_year_month(2018, 3, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_add_months_local() {
+        use test_tz::Cet;
+        let utc = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let at = |y, m, d, h, min| Cet.with_ymd_and_hms(y, m, d, h, min, 0).unwrap();
+
+        // The wall-clock time is kept across the change from CET to CEST.
+        let shifted = add_months_local(&at(2018, 3, 15, 9, 0), 1, DstPolicy::Reject).unwrap();
+        assert_eq!(shifted.with_timezone(&Utc), utc("2018-04-15T07:00:00Z"));
+
+        // 02:30 on March 25th, 2018 doesn't exist.
+        let before_gap = at(2018, 2, 25, 2, 30);
+        assert_eq!(add_months_local(&before_gap, 1, DstPolicy::Earliest), None);
+        let shifted = add_months_local(&before_gap, 1, DstPolicy::ShiftForward).unwrap();
+        assert_eq!(shifted.with_timezone(&Utc), utc("2018-03-25T01:30:00Z"));
+
+        // 02:30 on October 28th, 2018 happens twice.
+        let before_overlap = at(2018, 9, 28, 2, 30);
+        let earliest = add_months_local(&before_overlap, 1, DstPolicy::Earliest).unwrap();
+        assert_eq!(earliest.with_timezone(&Utc), utc("2018-10-28T00:30:00Z"));
+        let latest = add_months_local(&before_overlap, 1, DstPolicy::Latest).unwrap();
+        assert_eq!(latest.with_timezone(&Utc), utc("2018-10-28T01:30:00Z"));
+        assert_eq!(add_months_local(&before_overlap, 1, DstPolicy::Reject), None);
+    }
+
+    #[test]
+    fn test_try_add_months_dst() {
+        use test_tz::Cet;
+        let at = |y, m, d, h, min| Cet.with_ymd_and_hms(y, m, d, h, min, 0).unwrap();
+        assert_eq!(at(2018, 2, 25, 2, 30).try_add_months(1),
+                   Err(ChronoUtilsError::NonexistentLocalTime));
+        assert_eq!(at(2018, 9, 28, 2, 30).try_add_months(1),
+                   Err(ChronoUtilsError::AmbiguousLocalTime));
+        assert_eq!(at(2018, 2, 25, 3, 30).try_add_months(1), Ok(at(2018, 3, 25, 3, 30)));
+    }
+
+    #[test]
+    fn test_add_months_on() {
+        // January 31st 22:00 in UTC-5 is February 1st 03:00 in UTC.
+        let test_date = DateTime::parse_from_rfc3339("2018-01-31T22:00:00-05:00").unwrap();
+        let local = add_months_on(&test_date, 1, CalendarBasis::Local).unwrap();
+        assert_eq!(local.to_rfc3339(), "2018-02-28T22:00:00-05:00");
+        let utc = add_months_on(&test_date, 1, CalendarBasis::Utc).unwrap();
+        assert_eq!(utc.to_rfc3339(), "2018-02-28T22:00:00-05:00");
+
+        // The 30th in UTC-5 is already the 31st in UTC, which is clamped in April.
+        let test_date = DateTime::parse_from_rfc3339("2018-03-30T22:00:00-05:00").unwrap();
+        let local = add_months_on(&test_date, 1, CalendarBasis::Local).unwrap();
+        assert_eq!(local.to_rfc3339(), "2018-04-30T22:00:00-05:00");
+        let utc = add_months_on(&test_date, 1, CalendarBasis::Utc).unwrap();
+        assert_eq!(utc.to_rfc3339(), "2018-04-29T22:00:00-05:00");
+    }
+
+    #[test]
+    fn test_add_months_on_across_dst() {
+        use test_tz::Cet;
+        let test_date = Cet.with_ymd_and_hms(2018, 3, 15, 9, 0, 0).unwrap();
+        let local = add_months_on(&test_date, 1, CalendarBasis::Local).unwrap();
+        assert_eq!(local.naive_local().to_string(), "2018-04-15 09:00:00");
+        let utc = add_months_on(&test_date, 1, CalendarBasis::Utc).unwrap();
+        assert_eq!(utc.naive_local().to_string(), "2018-04-15 10:00:00");
+    }
+
+    #[test]
+    fn test_add_months_all() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let dates = vec![date(2018, 1, 31), date(2018, 11, 15), date(2016, 2, 29)];
+        for &n in &[0, 1, -1, 11, -13, 25] {
+            let shifted: Vec<_> = add_months_all(dates.clone(), n).collect();
+            let expected: Vec<_> = dates.iter().map(|d| d.add_months(n)).collect();
+            assert_eq!(shifted, expected);
+        }
+
+        let mut times = vec![DateTime::parse_from_rfc3339("2018-03-31T12:00:00+09:00").unwrap()];
+        add_months_in_place(&mut times, -1);
+        assert_eq!(times[0].to_rfc3339(), "2018-02-28T12:00:00+09:00");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_date() {
+        let tz = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let date = |y, m, d| tz.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().date();
+        assert_eq!(date(2018, 1, 31).add_months(1), date(2018, 2, 28));
+        assert_eq!(date(2018, 2, 28).add_months_eom(1), date(2018, 3, 31));
+        assert_eq!(date(2018, 3, 15).sub_months(14), date(2017, 1, 15));
+        assert_eq!(date(2018, 2, 15).start_of_next_month(), date(2018, 3, 1));
+        // Midnight on March 15th in UTC+9 is still March 14th in UTC.
+        let b = Utc.with_ymd_and_hms(2017, 12, 14, 0, 0, 0).unwrap().date();
+        assert_eq!(date(2018, 3, 15).months_since(&b), 3);
+        let b = Utc.with_ymd_and_hms(2017, 12, 15, 0, 0, 0).unwrap().date();
+        assert_eq!(date(2018, 3, 15).months_since(&b), 2);
+        assert_eq!(date(2018, 3, 15).months_since(&b),
+                   tz.with_ymd_and_hms(2018, 3, 15, 0, 0, 0).unwrap()
+                       .months_since(&Utc.with_ymd_and_hms(2017, 12, 15, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_add_months_to_year_month() {
+        assert_eq!(add_months_to_year_month((2018, 11), 3), Some((2019, 2)));
+        assert_eq!(add_months_to_year_month((2018, 1), -1), Some((2017, 12)));
+        assert_eq!(add_months_to_year_month((2018, 3), -27), Some((2015, 12)));
+        assert_eq!(add_months_to_year_month((2018, 13), 1), None);
+        assert_eq!(add_months_to_year_month((i32::MAX, 12), 1), None);
+    }
+
+    #[test]
+    fn test_saturating_add_months() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 1, 31).saturating_add_months(1), date(2018, 2, 28));
+        assert_eq!(date(2018, 1, 31).saturating_add_months(4_000_000), NaiveDate::MAX);
+        assert_eq!(date(2018, 1, 31).saturating_add_months(i64::MIN), NaiveDate::MIN);
+        let noon = date(2018, 3, 15).and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(noon.saturating_add_months(i64::MAX), NaiveDateTime::MAX);
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        assert_eq!(test_date.saturating_add_months(-4_000_000), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(test_date.saturating_add_months(4_000_000), DateTime::<Utc>::MAX_UTC);
+        assert_eq!(test_date.saturating_add_months(-24).to_rfc3339(), "2016-03-15T12:00:00+09:00");
+    }
+
+    #[test]
+    fn test_saturating_add_months_dst_gap() {
+        use test_tz::Cet;
+        let test_date = Cet.with_ymd_and_hms(2018, 2, 25, 2, 30, 0).unwrap();
+        let shifted = test_date.saturating_add_months(1);
+        let expected = NaiveDate::from_ymd_opt(2018, 3, 25).unwrap().and_hms_opt(3, 30, 0);
+        assert_eq!(Some(shifted.naive_local()), expected);
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 3, 20).nth_weekday_of_month(Weekday::Tue, 2),
+                   Some(date(2018, 3, 13)));
+        assert_eq!(date(2018, 1, 31).nth_weekday_of_month(Weekday::Mon, 3),
+                   Some(date(2018, 1, 15)));
+        assert_eq!(date(2018, 1, 31).nth_weekday_of_month(Weekday::Mon, 5),
+                   Some(date(2018, 1, 29)));
+        assert_eq!(date(2018, 2, 1).nth_weekday_of_month(Weekday::Mon, 5), None);
+        assert_eq!(date(2018, 2, 1).nth_weekday_of_month(Weekday::Mon, 0), None);
+        assert_eq!(date(2018, 3, 1).last_weekday_of_month(Weekday::Fri), date(2018, 3, 30));
+        assert_eq!(date(2018, 3, 1).last_weekday_of_month(Weekday::Sat), date(2018, 3, 31));
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month_keeps_time() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        let second_tuesday = test_date.nth_weekday_of_month(Weekday::Tue, 2).unwrap();
+        assert_eq!(second_tuesday.to_rfc3339(), "2018-03-13T12:00:00+09:00");
+        let last_friday = test_date.last_weekday_of_month(Weekday::Fri);
+        assert_eq!(last_friday.to_rfc3339(), "2018-03-30T12:00:00+09:00");
+    }
+
+    #[test]
+    fn test_next_and_previous_day_of_month() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 1, 15).next_day_of_month(31), date(2018, 1, 31));
+        assert_eq!(date(2018, 1, 31).next_day_of_month(31), date(2018, 2, 28));
+        assert_eq!(date(2018, 2, 28).next_day_of_month(31), date(2018, 3, 31));
+        assert_eq!(date(2018, 3, 15).next_day_of_month(15), date(2018, 4, 15));
+        assert_eq!(date(2018, 3, 15).next_day_of_month(1), date(2018, 4, 1));
+        assert_eq!(date(2018, 3, 15).previous_day_of_month(31), date(2018, 2, 28));
+        assert_eq!(date(2018, 3, 31).previous_day_of_month(31), date(2018, 2, 28));
+        assert_eq!(date(2018, 3, 15).previous_day_of_month(1), date(2018, 3, 1));
+        assert_eq!(date(2018, 1, 1).previous_day_of_month(1), date(2017, 12, 1));
+        let test_date = DateTime::parse_from_rfc3339("2018-01-31T12:00:00+09:00").unwrap();
+        assert_eq!(test_date.next_day_of_month(30).to_rfc3339(), "2018-02-28T12:00:00+09:00");
+    }
+
+    #[test]
+    fn test_first_weekday_and_occurrences() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2024, 3, 20).first_weekday_of_month(Weekday::Fri), date(2024, 3, 1));
+        assert_eq!(date(2024, 3, 20).first_weekday_of_month(Weekday::Thu), date(2024, 3, 7));
+        assert_eq!(date(2024, 3, 20).weekday_occurrences_in_month(Weekday::Fri), 5);
+        assert_eq!(date(2024, 3, 20).weekday_occurrences_in_month(Weekday::Sun), 5);
+        assert_eq!(date(2024, 3, 20).weekday_occurrences_in_month(Weekday::Mon), 4);
+        assert_eq!(date(2018, 2, 1).weekday_occurrences_in_month(Weekday::Thu), 4);
+        assert_eq!(date(2016, 2, 1).weekday_occurrences_in_month(Weekday::Mon), 5);
+        // The first Monday of the quarter starting in April 2018.
+        let quarter_start = DateTime::parse_from_rfc3339("2018-04-01T09:00:00+09:00").unwrap();
+        assert_eq!(quarter_start.first_weekday_of_month(Weekday::Mon).to_rfc3339(),
+                   "2018-04-02T09:00:00+09:00");
+    }
 }
@@ -0,0 +1,200 @@
+extern crate chrono;
+
+use chrono::Datelike;
+use month_calc::MonthCalculations;
+
+/// Which day of each month a `MonthIterator` yields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MonthAnchor {
+    /// The first day of each month (keeping the time of day).
+    FirstOfMonth,
+    /// The same day of the month as the start, clamped to the last day in shorter months.  Each
+    /// value is computed from the start, so a clamped day doesn't carry over (January 31st is
+    /// followed by February 28th and then March 31st).
+    SameDay,
+}
+
+/// Returns the value for the month `num_months` after `start`'s month, anchored by `anchor`.
+fn anchored<T: MonthCalculations>(start: &T, num_months: i64, anchor: MonthAnchor) -> Option<T> {
+    let shifted = start.add_months_checked(num_months)?;
+    Some(match anchor {
+        MonthAnchor::FirstOfMonth => shifted.first_day_of_month(),
+        MonthAnchor::SameDay => shifted,
+    })
+}
+
+/// An iterator over one value per month, as returned by `MonthCalculations::iter_months`.  It
+/// can also be iterated backwards (with `rev`) to go back through the months from the last one
+/// before the end.
+#[derive(Clone, Debug)]
+pub struct MonthIterator<T> {
+    start: T,
+    end: T,
+    anchor: MonthAnchor,
+    next_month: i64,
+    // Once iterating from the back has started, the month offset (from the start) of the last
+    // value yielded from the back, which values from the front must stay before.
+    back_month: Option<i64>,
+}
+
+impl<T: MonthCalculations> MonthIterator<T> {
+    /// Returns an iterator yielding a value for each month from the month of `start`, anchored
+    /// according to `anchor`, for as long as the values are before `end`.
+    pub fn new(start: T, end: T, anchor: MonthAnchor) -> MonthIterator<T> {
+        MonthIterator { start, end, anchor, next_month: 0, back_month: None }
+    }
+}
+
+impl<T: MonthCalculations + PartialOrd> Iterator for MonthIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.back_month.is_some_and(|back_month| self.next_month >= back_month) {
+            return None;
+        }
+        let value = anchored(&self.start, self.next_month, self.anchor)?;
+        if value < self.end {
+            self.next_month += 1;
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: MonthCalculations + PartialOrd + Datelike> DoubleEndedIterator for MonthIterator<T> {
+    fn next_back(&mut self) -> Option<T> {
+        let back_month = match self.back_month {
+            Some(back_month) => back_month,
+            None => {
+                // Only the value in the end's own month can fail to be before the end.
+                let months = (self.end.year() as i64 - self.start.year() as i64) * 12
+                    + self.end.month() as i64 - self.start.month() as i64;
+                match anchored(&self.start, months, self.anchor) {
+                    Some(value) if value < self.end => months + 1,
+                    _ => months,
+                }
+            },
+        };
+        if back_month <= self.next_month {
+            self.back_month = Some(self.next_month);
+            return None;
+        }
+        let value = anchored(&self.start, back_month - 1, self.anchor)?;
+        self.back_month = Some(back_month - 1);
+        Some(value)
+    }
+}
+
+/// An unbounded iterator over one value per month, as returned by
+/// `MonthCalculations::iter_months_from`.  It only ends once the values are out of range, so it
+/// is meant to be used with adapters such as `take` or `take_while`.
+#[derive(Clone, Debug)]
+pub struct MonthsFrom<T> {
+    start: T,
+    anchor: MonthAnchor,
+    next_month: i64,
+}
+
+impl<T: MonthCalculations> MonthsFrom<T> {
+    /// Returns an iterator yielding a value for each month from the month of `start` onwards,
+    /// anchored according to `anchor`.
+    pub fn new(start: T, anchor: MonthAnchor) -> MonthsFrom<T> {
+        MonthsFrom { start, anchor, next_month: 0 }
+    }
+}
+
+impl<T: MonthCalculations> Iterator for MonthsFrom<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = anchored(&self.start, self.next_month, self.anchor)?;
+        self.next_month += 1;
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_iter_months_same_day() {
+        let months: Vec<_> = date(2018, 1, 31)
+            .iter_months(&date(2018, 5, 31), MonthAnchor::SameDay)
+            .collect();
+        assert_eq!(months, vec![date(2018, 1, 31), date(2018, 2, 28), date(2018, 3, 31),
+                                date(2018, 4, 30)]);
+        let months: Vec<_> = date(2018, 1, 31)
+            .iter_months(&date(2018, 6, 1), MonthAnchor::SameDay)
+            .collect();
+        assert_eq!(months.last(), Some(&date(2018, 5, 31)));
+    }
+
+    #[test]
+    fn test_iter_months_first_of_month() {
+        let start = DateTime::parse_from_rfc3339("2018-11-15T12:00:00+09:00").unwrap();
+        let end = DateTime::parse_from_rfc3339("2019-02-01T12:00:00+09:00").unwrap();
+        let months: Vec<_> = start.iter_months(&end, MonthAnchor::FirstOfMonth)
+            .map(|dt| dt.to_rfc3339())
+            .collect();
+        assert_eq!(months, vec!["2018-11-01T12:00:00+09:00", "2018-12-01T12:00:00+09:00",
+                                "2019-01-01T12:00:00+09:00"]);
+    }
+
+    #[test]
+    fn test_iter_months_empty_and_near_max() {
+        let start = date(2018, 3, 15);
+        assert_eq!(start.iter_months(&start, MonthAnchor::SameDay).count(), 0);
+        assert_eq!(start.iter_months(&date(2018, 1, 1), MonthAnchor::SameDay).count(), 0);
+        let near_max = date(NaiveDate::MAX.year(), 11, 15);
+        assert_eq!(near_max.iter_months(&NaiveDate::MAX, MonthAnchor::SameDay).count(), 2);
+    }
+
+    #[test]
+    fn test_iter_months_rev() {
+        // The last six statement dates before 2018-06-20, newest first.
+        let statements: Vec<_> = date(2017, 10, 31)
+            .iter_months(&date(2018, 6, 20), MonthAnchor::SameDay)
+            .rev()
+            .take(6)
+            .collect();
+        assert_eq!(statements, vec![date(2018, 5, 31), date(2018, 4, 30), date(2018, 3, 31),
+                                    date(2018, 2, 28), date(2018, 1, 31), date(2017, 12, 31)]);
+        let months: Vec<_> = date(2018, 1, 15)
+            .iter_months(&date(2018, 4, 15), MonthAnchor::SameDay)
+            .rev()
+            .collect();
+        assert_eq!(months, vec![date(2018, 3, 15), date(2018, 2, 15), date(2018, 1, 15)]);
+        let start = date(2018, 3, 15);
+        assert_eq!(start.iter_months(&date(2018, 1, 1), MonthAnchor::SameDay).next_back(), None);
+    }
+
+    #[test]
+    fn test_iter_months_both_ends() {
+        let mut months = date(2018, 1, 1).iter_months(&date(2018, 5, 1), MonthAnchor::FirstOfMonth);
+        assert_eq!(months.next(), Some(date(2018, 1, 1)));
+        assert_eq!(months.next_back(), Some(date(2018, 4, 1)));
+        assert_eq!(months.next_back(), Some(date(2018, 3, 1)));
+        assert_eq!(months.next(), Some(date(2018, 2, 1)));
+        assert_eq!(months.next(), None);
+        assert_eq!(months.next_back(), None);
+    }
+
+    #[test]
+    fn test_iter_months_from() {
+        let months: Vec<_> = date(2018, 11, 30)
+            .iter_months_from(MonthAnchor::SameDay)
+            .take(4)
+            .collect();
+        assert_eq!(months, vec![date(2018, 11, 30), date(2018, 12, 30), date(2019, 1, 30),
+                                date(2019, 2, 28)]);
+        let near_max = date(NaiveDate::MAX.year(), 11, 15);
+        assert_eq!(near_max.iter_months_from(MonthAnchor::FirstOfMonth).count(), 2);
+    }
+}
@@ -0,0 +1,210 @@
+extern crate chrono;
+
+use business_days::{roll, RollConvention, Workweek};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use holidays::HolidayCalendar;
+use month_calc::OverflowPolicy;
+use period::Period;
+use std::fmt;
+
+/// Common invoice payment terms, which determine when an invoice is due.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaymentTerms {
+    /// Due on the invoice date ("DUE ON RECEIPT").
+    DueOnReceipt,
+    /// Due the given number of days after the invoice date ("NET 30").
+    Net(u32),
+    /// Due the given number of days after the end of the invoice's month ("EOM+15"; plain "EOM"
+    /// is `EndOfMonth(0)`).
+    EndOfMonth(u32),
+    /// Due on the given day (1 to 31) of the month after the invoice's month ("10th proximo").
+    /// Days past the end of that month are clamped to its last day.
+    Proximo(u32),
+}
+
+impl PaymentTerms {
+    /// Returns the date an invoice issued on `invoice_date` is due, moved to a working day
+    /// according to `convention`.  Returns `None` if the due date is out of range, or for
+    /// `Proximo` with a day outside 1 to 31.
+    pub fn due_date<C>(&self, invoice_date: NaiveDate, convention: RollConvention,
+                       calendar: &C, workweek: &Workweek) -> Option<NaiveDate>
+        where C: HolidayCalendar + ?Sized {
+        let due = match *self {
+            PaymentTerms::DueOnReceipt => invoice_date,
+            PaymentTerms::Net(days) => invoice_date.checked_add_days(Days::new(days as u64))?,
+            PaymentTerms::EndOfMonth(days) => {
+                let last = first_of_next_month(invoice_date)?.pred_opt()?;
+                last.checked_add_days(Days::new(days as u64))?
+            }
+            PaymentTerms::Proximo(day) if (1..=31).contains(&day) => {
+                let first = first_of_next_month(invoice_date)?;
+                let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                first.with_day(day.min(last.day()))?
+            }
+            PaymentTerms::Proximo(_) => return None,
+        };
+        roll(due, convention, calendar, workweek)
+    }
+}
+
+impl fmt::Display for PaymentTerms {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PaymentTerms::DueOnReceipt => write!(f, "DUE ON RECEIPT"),
+            PaymentTerms::Net(days) => write!(f, "NET {}", days),
+            PaymentTerms::EndOfMonth(0) => write!(f, "EOM"),
+            PaymentTerms::EndOfMonth(days) => write!(f, "EOM+{}", days),
+            PaymentTerms::Proximo(day) => write!(f, "{}{} PROXIMO", day, ordinal_suffix(day)),
+        }
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    date.with_day(1)?.checked_add_months(Months::new(1))
+}
+
+fn ordinal_suffix(n: u32) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Parse a payment terms string, ignoring case and spacing.  Accepts "DUE ON RECEIPT",
+/// "NET 30" (or "NET30", "N30"), "EOM", "EOM+15" (or "EOM 15") and "10th proximo" (or "10 PROX").
+/// Returns `None` if the string isn't recognized.
+pub fn parse_payment_terms(s: &str) -> Option<PaymentTerms> {
+    let normalized: String = s.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if normalized == "DUEONRECEIPT" {
+        return Some(PaymentTerms::DueOnReceipt);
+    }
+    if normalized == "EOM" {
+        return Some(PaymentTerms::EndOfMonth(0));
+    }
+    if let Some(days) = normalized.strip_prefix("EOM") {
+        return days.trim_start_matches('+').parse().ok().map(PaymentTerms::EndOfMonth);
+    }
+    if let Some(days) = normalized.strip_prefix("NET").or_else(|| normalized.strip_prefix('N')) {
+        return days.parse().ok().map(PaymentTerms::Net);
+    }
+    let day = normalized.strip_suffix("PROXIMO").or_else(|| normalized.strip_suffix("PROX"))?;
+    let day = ["ST", "ND", "RD", "TH"].iter()
+        .find_map(|suffix| day.strip_suffix(suffix))
+        .unwrap_or(day);
+    match day.parse() {
+        Ok(day) if (1..=31).contains(&day) => Some(PaymentTerms::Proximo(day)),
+        _ => None,
+    }
+}
+
+/// Returns the reminder and escalation dates for an invoice due on `due_date`, one for each of
+/// `steps`.  Each step is measured from the due date (not from the previous step), clamping to
+/// the end of the month where needed, and dates falling on non-working days are moved to the
+/// following working day so that no reminder goes out early.  Returns `None` if any date is out
+/// of range.
+pub fn dunning_schedule<C>(due_date: NaiveDate, steps: &[Period], calendar: &C,
+                           workweek: &Workweek) -> Option<Vec<NaiveDate>>
+    where C: HolidayCalendar + ?Sized {
+    steps.iter()
+        .map(|step| {
+            let date = step.add_to_date(due_date, OverflowPolicy::ClampToLastDay)?;
+            roll(date, RollConvention::Following, calendar, workweek)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holidays::{Holiday, HolidayCategory, HolidayList};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn due(terms: PaymentTerms, invoice_date: NaiveDate) -> Option<NaiveDate> {
+        terms.due_date(invoice_date, RollConvention::Unadjusted, &HolidayList::new(),
+                       &Workweek::monday_to_friday())
+    }
+
+    #[test]
+    fn test_parse_payment_terms() {
+        assert_eq!(parse_payment_terms("NET 30"), Some(PaymentTerms::Net(30)));
+        assert_eq!(parse_payment_terms("net30"), Some(PaymentTerms::Net(30)));
+        assert_eq!(parse_payment_terms("N45"), Some(PaymentTerms::Net(45)));
+        assert_eq!(parse_payment_terms("EOM"), Some(PaymentTerms::EndOfMonth(0)));
+        assert_eq!(parse_payment_terms("EOM+15"), Some(PaymentTerms::EndOfMonth(15)));
+        assert_eq!(parse_payment_terms("eom 15"), Some(PaymentTerms::EndOfMonth(15)));
+        assert_eq!(parse_payment_terms("10th proximo"), Some(PaymentTerms::Proximo(10)));
+        assert_eq!(parse_payment_terms("1 PROX"), Some(PaymentTerms::Proximo(1)));
+        assert_eq!(parse_payment_terms("Due on receipt"), Some(PaymentTerms::DueOnReceipt));
+        assert_eq!(parse_payment_terms("32nd proximo"), None);
+        assert_eq!(parse_payment_terms("NET"), None);
+        assert_eq!(parse_payment_terms("2/10"), None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for terms in &[PaymentTerms::DueOnReceipt, PaymentTerms::Net(30),
+                       PaymentTerms::EndOfMonth(0), PaymentTerms::EndOfMonth(15),
+                       PaymentTerms::Proximo(1), PaymentTerms::Proximo(22),
+                       PaymentTerms::Proximo(11)] {
+            assert_eq!(parse_payment_terms(&terms.to_string()), Some(*terms));
+        }
+        assert_eq!(PaymentTerms::Proximo(22).to_string(), "22nd PROXIMO");
+        // Days outside 1 to 31 display, but don't parse back.
+        assert_eq!(parse_payment_terms(&PaymentTerms::Proximo(0).to_string()), None);
+        assert_eq!(parse_payment_terms(&PaymentTerms::Proximo(32).to_string()), None);
+    }
+
+    #[test]
+    fn test_due_date() {
+        let invoice_date = date(2018, 1, 20);
+        assert_eq!(due(PaymentTerms::DueOnReceipt, invoice_date), Some(invoice_date));
+        assert_eq!(due(PaymentTerms::Net(30), invoice_date), Some(date(2018, 2, 19)));
+        assert_eq!(due(PaymentTerms::EndOfMonth(0), invoice_date), Some(date(2018, 1, 31)));
+        assert_eq!(due(PaymentTerms::EndOfMonth(15), invoice_date), Some(date(2018, 2, 15)));
+        assert_eq!(due(PaymentTerms::Proximo(10), invoice_date), Some(date(2018, 2, 10)));
+        // There is no February 30th, so the last day of February is used.
+        assert_eq!(due(PaymentTerms::Proximo(30), invoice_date), Some(date(2018, 2, 28)));
+        assert_eq!(due(PaymentTerms::Proximo(10), date(2018, 12, 31)), Some(date(2019, 1, 10)));
+        assert_eq!(due(PaymentTerms::Proximo(0), invoice_date), None);
+        assert_eq!(due(PaymentTerms::Proximo(32), invoice_date), None);
+        // Terms that don't need the next month still work in the last representable month.
+        assert_eq!(due(PaymentTerms::DueOnReceipt, NaiveDate::MAX), Some(NaiveDate::MAX));
+    }
+
+    #[test]
+    fn test_due_date_rolls_to_working_day() {
+        // February 10th, 2018 is a Saturday.
+        let due = PaymentTerms::Proximo(10).due_date(date(2018, 1, 20), RollConvention::Following,
+                                                     &HolidayList::new(), &Workweek::default());
+        assert_eq!(due, Some(date(2018, 2, 12)));
+    }
+
+    #[test]
+    fn test_dunning_schedule() {
+        let mut calendar = HolidayList::new();
+        calendar.add(date(2018, 2, 19), Holiday::new("Presidents' Day", HolidayCategory::Public));
+        let steps = [Period::days(0), Period::days(7), Period::weeks(2), Period::months(1)];
+        // Wednesday January 31st, 2018.
+        let schedule = dunning_schedule(date(2018, 1, 31), &steps, &calendar, &Workweek::default());
+        assert_eq!(schedule, Some(vec![
+            date(2018, 1, 31),
+            date(2018, 2, 7),
+            date(2018, 2, 14),
+            // February 28th is a Wednesday.
+            date(2018, 2, 28),
+        ]));
+        // A step landing on the holiday moves to the Tuesday.
+        let schedule = dunning_schedule(date(2018, 2, 12), &[Period::weeks(1)], &calendar,
+                                        &Workweek::default());
+        assert_eq!(schedule, Some(vec![date(2018, 2, 20)]));
+    }
+}
@@ -0,0 +1,119 @@
+extern crate chrono;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use month_calc::{resolve_day, shift_year_month, OverflowPolicy};
+
+/// An exact calendar period made up of years, months and days, such as "3 months" or
+/// "1 year and 2 weeks".  Unlike a `chrono::Duration`, the length of a Period depends on the
+/// date it is applied to.  (For display-only estimates of Durations, see `ApproxPeriod`.)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Period {
+    pub years: i32,
+    pub months: i32,
+    pub days: i32,
+}
+
+impl Period {
+    pub fn years(years: i32) -> Period {
+        Period { years, ..Period::default() }
+    }
+
+    pub fn months(months: i32) -> Period {
+        Period { months, ..Period::default() }
+    }
+
+    pub fn weeks(weeks: i32) -> Period {
+        Period { days: weeks * 7, ..Period::default() }
+    }
+
+    pub fn days(days: i32) -> Period {
+        Period { days, ..Period::default() }
+    }
+
+    /// Apply this period to `date`: the years and months are added first, keeping the day of the
+    /// month, and then the days.  If the day of the month doesn't exist after adding the years and
+    /// months (e.g. January 31st + 1 month), `policy` decides what to do instead (with
+    /// `OverflowPolicy::Reject`, `None` is returned).  `None` is also returned if the result is
+    /// out of range.
+    pub fn add_to_date(&self, date: NaiveDate, policy: OverflowPolicy) -> Option<NaiveDate> {
+        let num_months = self.years as i64 * 12 + self.months as i64;
+        let (year, month) = shift_year_month(date.year(), date.month(), num_months)?;
+
+        let shifted = resolve_day(year, month, date.day(), policy).ok()?;
+        shifted.checked_add_signed(Duration::days(self.days as i64))
+    }
+}
+
+/// How the start date is counted when working out the end of a period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InclusionRule {
+    /// The start date is the first day of the period (day one), so a 3 month period starting
+    /// January 15th includes April 14th but not April 15th.
+    IncludeStart,
+    /// The start date is day zero and the period begins the day after, so a 3 month period
+    /// starting January 15th includes April 15th.
+    ExcludeStart,
+}
+
+/// Work out where a period such as a probation or notice period ends.  Returns the last day
+/// included in the period and the first day after it, or `None` if the period can't be applied
+/// (see `Period::add_to_date`).
+pub fn period_end(start: NaiveDate, period: Period, rule: InclusionRule,
+                  policy: OverflowPolicy) -> Option<(NaiveDate, NaiveDate)> {
+    let end = period.add_to_date(start, policy)?;
+    match rule {
+        InclusionRule::IncludeStart => Some((end.pred_opt()?, end)),
+        InclusionRule::ExcludeStart => Some((end, end.succ_opt()?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_add_to_date() {
+        let clamp = OverflowPolicy::ClampToLastDay;
+        let start = date(2018, 1, 15);
+        assert_eq!(Period::months(3).add_to_date(start, clamp), Some(date(2018, 4, 15)));
+        assert_eq!(Period::years(1).add_to_date(start, clamp), Some(date(2019, 1, 15)));
+        assert_eq!(Period::weeks(2).add_to_date(start, clamp), Some(date(2018, 1, 29)));
+        assert_eq!(Period::months(-2).add_to_date(start, clamp), Some(date(2017, 11, 15)));
+        let period = Period { years: 1, months: 1, days: 1 };
+        assert_eq!(period.add_to_date(start, clamp), Some(date(2019, 2, 16)));
+    }
+
+    #[test]
+    fn test_add_to_date_overflow() {
+        let period = Period::months(1);
+        assert_eq!(period.add_to_date(date(2018, 1, 31), OverflowPolicy::ClampToLastDay),
+                   Some(date(2018, 2, 28)));
+        assert_eq!(period.add_to_date(date(2016, 1, 31), OverflowPolicy::ClampToLastDay),
+                   Some(date(2016, 2, 29)));
+        assert_eq!(period.add_to_date(date(2018, 1, 31), OverflowPolicy::Reject), None);
+        assert_eq!(Period::years(1).add_to_date(date(2016, 2, 29), OverflowPolicy::Reject), None);
+    }
+
+    #[test]
+    fn test_period_end() {
+        let clamp = OverflowPolicy::ClampToLastDay;
+        let start = date(2018, 1, 15);
+        assert_eq!(period_end(start, Period::months(3), InclusionRule::IncludeStart, clamp),
+                   Some((date(2018, 4, 14), date(2018, 4, 15))));
+        assert_eq!(period_end(start, Period::months(3), InclusionRule::ExcludeStart, clamp),
+                   Some((date(2018, 4, 15), date(2018, 4, 16))));
+    }
+
+    #[test]
+    fn test_period_end_month_end() {
+        let start = date(2018, 11, 30);
+        let rule = InclusionRule::IncludeStart;
+        assert_eq!(period_end(start, Period::months(3), rule, OverflowPolicy::ClampToLastDay),
+                   Some((date(2019, 2, 27), date(2019, 2, 28))));
+        assert_eq!(period_end(start, Period::months(3), rule, OverflowPolicy::Reject), None);
+    }
+}
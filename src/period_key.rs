@@ -0,0 +1,238 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Weekday};
+use civil::{resolve_local, DstPolicy};
+use month_calc::days_in_month;
+use std::convert::TryFrom;
+
+/// A calendar unit used to bucket instants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+/// Selects the day of the month produced by `from_months_since_epoch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DayPolicy {
+    /// The first day of the month.
+    First,
+    /// The last day of the month.
+    Last,
+    /// The given day, capped to the last day of the month the same way
+    /// `MonthCalculations::with_closest_day` does.
+    Closest(u32),
+}
+
+/// This trait defines integer month and week counts relative to the Unix epoch, so bucketing
+/// dates by month or week becomes a pure integer operation.  Counts are based on the calendar date
+/// only, so for a DateTime they are taken in its own timezone.
+pub trait EpochCalculations {
+    /// Returns the number of months between January 1970 and this date's month (e.g. 578 for
+    /// March 2018, -1 for December 1969).
+    fn months_since_epoch(&self) -> i64;
+
+    /// Returns the number of ISO weeks between the week containing January 1st, 1970 (which
+    /// starts on Monday, December 29th, 1969) and this date's week.
+    fn weeks_since_epoch(&self) -> i64;
+}
+
+impl<T> EpochCalculations for T where T: Datelike {
+    fn months_since_epoch(&self) -> i64 {
+        (self.year() as i64 - 1970) * 12 + self.month0() as i64
+    }
+
+    fn weeks_since_epoch(&self) -> i64 {
+        // The epoch is a Thursday, three days after the Monday that starts its ISO week.
+        (self.num_days_from_ce() as i64 - epoch_date().num_days_from_ce() as i64 + 3).div_euclid(7)
+    }
+}
+
+/// The inverse of `EpochCalculations::months_since_epoch`: returns the date in the given month
+/// selected by `day`, or `None` if the month is out of chrono's range or `day` is
+/// `DayPolicy::Closest(0)`.
+pub fn from_months_since_epoch(months: i64, day: DayPolicy) -> Option<NaiveDate> {
+    let year = i32::try_from(months.div_euclid(12).checked_add(1970)?).ok()?;
+    let month = months.rem_euclid(12) as u32 + 1;
+    let last = days_in_month(year, month)?;
+    let day = match day {
+        DayPolicy::First => 1,
+        DayPolicy::Last => last,
+        DayPolicy::Closest(0) => return None,
+        DayPolicy::Closest(d) => d.min(last),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// The inverse of `EpochCalculations::weeks_since_epoch`: returns the given weekday of the given
+/// ISO week, or `None` if it is out of chrono's range.
+pub fn from_weeks_since_epoch(weeks: i64, weekday: Weekday) -> Option<NaiveDate> {
+    let days = weeks.checked_mul(7)?
+        .checked_sub(3)?
+        .checked_add(weekday.num_days_from_monday() as i64)?;
+    epoch_date().checked_add_signed(Duration::try_days(days)?)
+}
+
+fn epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+/// Returns an integer key for the `unit`-sized bucket containing `dt`, as seen in timezone `tz`.
+/// Keys count buckets since the one containing the Unix epoch (January 1st, 1970), so they
+/// increase monotonically with time and are negative before 1970: for example, with
+/// `Unit::Month`, March 2018 is key 578 and December 1969 is key -1.  Weeks are ISO weeks, so
+/// key 0 is the week starting Monday, December 29th, 1969.
+///
+/// Because the key depends on the local date, the same instant can map to different keys in
+/// different timezones.
+pub fn period_key<Tz: TimeZone, Tz2: TimeZone>(dt: &DateTime<Tz>, unit: Unit, tz: &Tz2) -> i64 {
+    let local = dt.with_timezone(tz).date_naive();
+    match unit {
+        Unit::Day => local.signed_duration_since(epoch_date()).num_days(),
+        Unit::Week => local.weeks_since_epoch(),
+        Unit::Month => local.months_since_epoch(),
+        Unit::Year => local.year() as i64 - 1970,
+    }
+}
+
+/// The inverse of `period_key`: returns the start (local midnight in `tz`) of the bucket with the
+/// given key, or `None` if the key is out of chrono's representable range.  DST transitions are
+/// handled as in `MonthCalculations::start_of_month`: if midnight doesn't exist the start is
+/// moved forward by the length of the gap, and if it is ambiguous the earlier instant is used.
+pub fn from_period_key<Tz: TimeZone>(key: i64, unit: Unit, tz: &Tz) -> Option<DateTime<Tz>> {
+    let date = match unit {
+        Unit::Day => epoch_date().checked_add_signed(Duration::try_days(key)?)?,
+        Unit::Week => from_weeks_since_epoch(key, Weekday::Mon)?,
+        Unit::Month => from_months_since_epoch(key, DayPolicy::First)?,
+        Unit::Year => NaiveDate::from_ymd_opt(i32::try_from(key.checked_add(1970)?).ok()?, 1, 1)?,
+    };
+    resolve_local(tz, &date.and_time(NaiveTime::MIN), DstPolicy::ShiftForward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    #[test]
+    fn test_period_key_epoch() {
+        let test_date = DateTime::parse_from_rfc3339("1970-01-01T00:00:00Z").unwrap();
+        for unit in &[Unit::Day, Unit::Week, Unit::Month, Unit::Year] {
+            assert_eq!(period_key(&test_date, *unit, &Utc), 0);
+        }
+    }
+
+    #[test]
+    fn test_period_key_units() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(period_key(&test_date, Unit::Day, &Utc), 17605);
+        assert_eq!(period_key(&test_date, Unit::Week, &Utc), 2515);
+        assert_eq!(period_key(&test_date, Unit::Month, &Utc), 578);
+        assert_eq!(period_key(&test_date, Unit::Year, &Utc), 48);
+    }
+
+    #[test]
+    fn test_period_key_before_epoch() {
+        let test_date = DateTime::parse_from_rfc3339("1969-12-31T23:00:00Z").unwrap();
+        assert_eq!(period_key(&test_date, Unit::Day, &Utc), -1);
+        assert_eq!(period_key(&test_date, Unit::Week, &Utc), 0);
+        assert_eq!(period_key(&test_date, Unit::Month, &Utc), -1);
+        assert_eq!(period_key(&test_date, Unit::Year, &Utc), -1);
+
+        let test_date = DateTime::parse_from_rfc3339("1969-12-28T12:00:00Z").unwrap();
+        assert_eq!(period_key(&test_date, Unit::Week, &Utc), -1);
+    }
+
+    #[test]
+    fn test_period_key_uses_timezone() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-31T20:00:00Z").unwrap();
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        assert_eq!(period_key(&test_date, Unit::Month, &Utc), 578);
+        assert_eq!(period_key(&test_date, Unit::Month, &tokyo), 579);
+    }
+
+    #[test]
+    fn test_from_period_key() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let start = from_period_key(578, Unit::Month, &tokyo).unwrap();
+        assert_eq!(start, DateTime::parse_from_rfc3339("2018-03-01T00:00:00+09:00").unwrap());
+
+        let start = from_period_key(2515, Unit::Week, &Utc).unwrap();
+        assert_eq!(start, DateTime::parse_from_rfc3339("2018-03-12T00:00:00Z").unwrap());
+
+        let start = from_period_key(-1, Unit::Month, &Utc).unwrap();
+        assert_eq!(start, DateTime::parse_from_rfc3339("1969-12-01T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn test_from_period_key_midnight_gap() {
+        use test_tz::MidnightGap;
+        // November 4th, 2018 starts at 01:00, an hour after midnight would have been.
+        let key = period_key(&MidnightGap.with_ymd_and_hms(2018, 11, 4, 12, 0, 0).unwrap(),
+                             Unit::Day, &MidnightGap);
+        let start = from_period_key(key, Unit::Day, &MidnightGap).unwrap();
+        assert_eq!(start.to_rfc3339(), "2018-11-04T01:00:00-02:00");
+        assert_eq!(period_key(&start, Unit::Day, &MidnightGap), key);
+    }
+
+    #[test]
+    fn test_from_period_key_round_trip() {
+        let test_date = DateTime::parse_from_rfc3339("2001-07-04T18:45:00-04:00").unwrap();
+        for unit in &[Unit::Day, Unit::Week, Unit::Month, Unit::Year] {
+            let key = period_key(&test_date, *unit, &Utc);
+            let start = from_period_key(key, *unit, &Utc).unwrap();
+            assert!(start <= test_date);
+            assert_eq!(period_key(&start, *unit, &Utc), key);
+        }
+    }
+
+    #[test]
+    fn test_months_since_epoch() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.months_since_epoch(), 578);
+        assert_eq!(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap().months_since_epoch(), -1);
+        assert_eq!(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().months_since_epoch(), 0);
+    }
+
+    #[test]
+    fn test_weeks_since_epoch() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.weeks_since_epoch(), 2515);
+        assert_eq!(NaiveDate::from_ymd_opt(1969, 12, 29).unwrap().weeks_since_epoch(), 0);
+        assert_eq!(NaiveDate::from_ymd_opt(1969, 12, 28).unwrap().weeks_since_epoch(), -1);
+    }
+
+    #[test]
+    fn test_from_months_since_epoch() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(from_months_since_epoch(578, DayPolicy::First), date(2018, 3, 1));
+        assert_eq!(from_months_since_epoch(578, DayPolicy::Last), date(2018, 3, 31));
+        assert_eq!(from_months_since_epoch(577, DayPolicy::Last), date(2018, 2, 28));
+        assert_eq!(from_months_since_epoch(553, DayPolicy::Last), date(2016, 2, 29));
+        assert_eq!(from_months_since_epoch(577, DayPolicy::Closest(30)), date(2018, 2, 28));
+        assert_eq!(from_months_since_epoch(577, DayPolicy::Closest(15)), date(2018, 2, 15));
+        assert_eq!(from_months_since_epoch(-1, DayPolicy::Closest(45)), date(1969, 12, 31));
+        assert_eq!(from_months_since_epoch(577, DayPolicy::Closest(0)), None);
+        assert_eq!(from_months_since_epoch(i64::MAX, DayPolicy::First), None);
+    }
+
+    #[test]
+    fn test_from_weeks_since_epoch() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(from_weeks_since_epoch(2515, Weekday::Mon), date(2018, 3, 12));
+        assert_eq!(from_weeks_since_epoch(2515, Weekday::Sun), date(2018, 3, 18));
+        assert_eq!(from_weeks_since_epoch(0, Weekday::Thu), date(1970, 1, 1));
+        assert_eq!(from_weeks_since_epoch(i64::MIN, Weekday::Mon), None);
+    }
+
+    #[test]
+    fn test_from_period_key_out_of_range() {
+        assert!(from_period_key(i64::MAX, Unit::Day, &Utc).is_none());
+        assert!(from_period_key(i64::MIN, Unit::Week, &Utc).is_none());
+        assert!(from_period_key(i64::MAX, Unit::Month, &Utc).is_none());
+        assert!(from_period_key(i64::MAX, Unit::Year, &Utc).is_none());
+    }
+}
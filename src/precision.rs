@@ -0,0 +1,107 @@
+extern crate chrono;
+
+use chrono::{DateTime, SubsecRound, TimeZone, Timelike};
+
+/// The number of sub-second digits kept by a `PrecisionPolicy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Precision {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl Precision {
+    /// Returns the number of fractional digits for this precision.
+    pub fn digits(&self) -> u16 {
+        match *self {
+            Precision::Seconds => 0,
+            Precision::Millis => 3,
+            Precision::Micros => 6,
+            Precision::Nanos => 9,
+        }
+    }
+}
+
+/// How extra sub-second digits are dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Drop the extra digits, rounding towards the start of the second.
+    Truncate,
+    /// Round to the nearest value, with halves rounded up.
+    Round,
+}
+
+/// How sub-seconds are handled when formatting and parsing, so that timestamps round-trip
+/// identically between systems with different precisions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PrecisionPolicy {
+    pub precision: Precision,
+    pub rounding: Rounding,
+    /// When formatting, whether to always write every digit of the precision (`.500`), rather
+    /// than stripping trailing zeros (`.5`, or no fraction at all for whole seconds).
+    pub pad: bool,
+}
+
+impl PrecisionPolicy {
+    pub fn new(precision: Precision, rounding: Rounding, pad: bool) -> PrecisionPolicy {
+        PrecisionPolicy { precision, rounding, pad }
+    }
+
+    /// Returns `dt` reduced to this policy's precision.
+    pub fn apply<Tz: TimeZone>(&self, dt: &DateTime<Tz>) -> DateTime<Tz> {
+        let digits = self.precision.digits();
+        match self.rounding {
+            Rounding::Truncate => dt.clone().trunc_subsecs(digits),
+            Rounding::Round => dt.clone().round_subsecs(digits),
+        }
+    }
+
+    /// Returns the fraction of a second of `dt` (after applying this policy) as it should be
+    /// written, including the leading `.`, or an empty string if no fraction is written.
+    pub fn format_fraction<Tz: TimeZone>(&self, dt: &DateTime<Tz>) -> String {
+        let digits = self.precision.digits() as usize;
+        if digits == 0 {
+            return String::new();
+        }
+        // A leap second is represented with nanoseconds of a second or more.
+        let nanos = self.apply(dt).nanosecond() % 1_000_000_000;
+        let fraction = format!("{:09}", nanos);
+        let fraction = &fraction[..digits];
+        let fraction = if self.pad { fraction } else { fraction.trim_end_matches('0') };
+        if fraction.is_empty() { String::new() } else { format!(".{}", fraction) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let dt = at("2018-03-15T12:00:00.123456789Z");
+        let millis = PrecisionPolicy::new(Precision::Millis, Rounding::Truncate, true);
+        assert_eq!(millis.apply(&dt), at("2018-03-15T12:00:00.123Z"));
+        let micros = PrecisionPolicy::new(Precision::Micros, Rounding::Round, true);
+        assert_eq!(micros.apply(&dt), at("2018-03-15T12:00:00.123457Z"));
+        let seconds = PrecisionPolicy::new(Precision::Seconds, Rounding::Round, true);
+        assert_eq!(seconds.apply(&at("2018-03-15T12:59:59.5Z")), at("2018-03-15T13:00:00Z"));
+    }
+
+    #[test]
+    fn test_format_fraction() {
+        let dt = at("2018-03-15T12:00:00.120Z");
+        let padded = PrecisionPolicy::new(Precision::Micros, Rounding::Truncate, true);
+        assert_eq!(padded.format_fraction(&dt), ".120000");
+        let stripped = PrecisionPolicy { pad: false, ..padded };
+        assert_eq!(stripped.format_fraction(&dt), ".12");
+        assert_eq!(stripped.format_fraction(&at("2018-03-15T12:00:00Z")), "");
+        let seconds = PrecisionPolicy::new(Precision::Seconds, Rounding::Truncate, true);
+        assert_eq!(seconds.format_fraction(&dt), "");
+    }
+}
@@ -0,0 +1,80 @@
+extern crate chrono;
+#[cfg(feature = "rand")]
+extern crate rand;
+
+use chrono::{DateTime, TimeZone};
+use windows::{window_bounds, Window};
+
+#[cfg(feature = "rand")]
+use chrono::Duration;
+#[cfg(feature = "rand")]
+use self::rand::Rng;
+
+/// Coarsen `dt` to the start of the window containing it (e.g. the start of its hour with
+/// `Window::Fixed(Duration::hours(1))`, or of its local day with `Window::Calendar(Unit::Day)`),
+/// so that every timestamp in the same window becomes indistinguishable.  Calendar windows are
+/// taken in `dt`'s own timezone.
+///
+/// Returns `None` if the window start is outside chrono's representable range.  Panics under
+/// the same conditions as `window_bounds`.
+pub fn anonymize<Tz: TimeZone>(dt: &DateTime<Tz>, granularity: Window) -> Option<DateTime<Tz>> {
+    window_bounds(dt, granularity, &dt.timezone()).map(|bounds| bounds.start)
+}
+
+/// Perturb `dt` by a uniformly random offset between `-window` and `+window` (inclusive, with
+/// nanosecond resolution).  Using a seeded RNG makes the perturbation reproducible.  Returns
+/// `None` if the result would be out of range.
+#[cfg(feature = "rand")]
+pub fn jitter<Tz, R>(dt: &DateTime<Tz>, window: Duration, rng: &mut R) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, R: Rng + ?Sized {
+    let window = window.abs().num_nanoseconds()?;
+    let offset = rng.gen_range(-window..=window);
+    dt.clone().checked_add_signed(Duration::nanoseconds(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset};
+    use period_key::Unit;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_anonymize_fixed() {
+        let dt = at("2018-03-15T12:34:56.789+09:00");
+        assert_eq!(anonymize(&dt, Window::Fixed(Duration::hours(1))),
+                   Some(at("2018-03-15T12:00:00+09:00")));
+        assert_eq!(anonymize(&dt, Window::Fixed(Duration::minutes(15))),
+                   Some(at("2018-03-15T12:30:00+09:00")));
+    }
+
+    #[test]
+    fn test_anonymize_calendar() {
+        let dt = at("2018-03-15T01:30:00+09:00");
+        assert_eq!(anonymize(&dt, Window::Calendar(Unit::Day)),
+                   Some(at("2018-03-15T00:00:00+09:00")));
+        assert_eq!(anonymize(&dt, Window::Calendar(Unit::Month)),
+                   Some(at("2018-03-01T00:00:00+09:00")));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_jitter() {
+        use self::rand::rngs::StdRng;
+        use self::rand::SeedableRng;
+
+        let dt = at("2018-03-15T12:00:00Z");
+        let window = Duration::minutes(5);
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let jittered = jitter(&dt, window, &mut rng).unwrap();
+            assert!(dt - window <= jittered && jittered <= dt + window);
+        }
+        assert_eq!(jitter(&dt, window, &mut StdRng::seed_from_u64(1)),
+                   jitter(&dt, window, &mut StdRng::seed_from_u64(1)));
+        assert_eq!(jitter(&dt, Duration::zero(), &mut rng), Some(dt));
+    }
+}
@@ -0,0 +1,137 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use civil::{resolve_local, DstPolicy};
+use time_of_day::TimeOfDayRange;
+
+/// Do-not-disturb periods for each day of the week, in a given timezone.  Each period is given
+/// by the weekday it starts on and its start and end times; as with `TimeOfDayRange`, a period
+/// whose end is not after its start runs past midnight into the next day (e.g. 22:00 to 07:00).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuietHours<Tz: TimeZone> {
+    periods: [Option<TimeOfDayRange>; 7],
+    tz: Tz,
+}
+
+impl<Tz: TimeZone> QuietHours<Tz> {
+    /// Quiet hours with no quiet periods.
+    pub fn new(tz: Tz) -> QuietHours<Tz> {
+        QuietHours { periods: [None; 7], tz }
+    }
+
+    /// Quiet hours with the same period starting every day.
+    pub fn daily(start: NaiveTime, end: NaiveTime, tz: Tz) -> QuietHours<Tz> {
+        QuietHours { periods: [Some(TimeOfDayRange::new(start, end)); 7], tz }
+    }
+
+    /// Set the quiet period starting on `weekday`, replacing any existing one.
+    pub fn set(&mut self, weekday: Weekday, start: NaiveTime,
+               end: NaiveTime) -> &mut QuietHours<Tz> {
+        let range = TimeOfDayRange::new(start, end);
+        self.periods[weekday.num_days_from_monday() as usize] = Some(range);
+        self
+    }
+
+    /// Remove the quiet period starting on `weekday`.
+    pub fn clear(&mut self, weekday: Weekday) -> &mut QuietHours<Tz> {
+        self.periods[weekday.num_days_from_monday() as usize] = None;
+        self
+    }
+
+    /// Returns the local time at which the quiet period containing `local` ends, if any.
+    fn quiet_until(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = local.date();
+        [date.pred_opt(), Some(date)].iter().flatten().find_map(|start_date| {
+            let range = self.periods[start_date.weekday().num_days_from_monday() as usize]?;
+            let from = start_date.and_time(range.start);
+            let to = if range.crosses_midnight() {
+                start_date.succ_opt()?.and_time(range.end)
+            } else {
+                start_date.and_time(range.end)
+            };
+            if local >= from && local < to { Some(to) } else { None }
+        })
+    }
+
+    /// Returns true if `dt` falls in a quiet period.  Start times are included and end times
+    /// are not.
+    pub fn is_quiet<Tz2: TimeZone>(&self, dt: &DateTime<Tz2>) -> bool {
+        self.quiet_until(dt.with_timezone(&self.tz).naive_local()).is_some()
+    }
+
+    /// Returns `dt` itself if it isn't in a quiet period, and otherwise the end of the quiet
+    /// period (following on through any back-to-back periods), in the quiet hours' timezone.  An
+    /// end time which falls in a DST gap is moved forward by the length of the gap.  Returns
+    /// `None` if quiet periods never end or the result is out of range.
+    pub fn defer_until_allowed<Tz2: TimeZone>(&self, dt: &DateTime<Tz2>) -> Option<DateTime<Tz>> {
+        let dt = dt.with_timezone(&self.tz);
+        let mut local = dt.naive_local();
+        if self.quiet_until(local).is_none() {
+            return Some(dt);
+        }
+        for _ in 0..8 {
+            match self.quiet_until(local) {
+                Some(end) => local = end,
+                None => return resolve_local(&self.tz, &local, DstPolicy::ShiftForward),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn quiet_hours() -> QuietHours<FixedOffset> {
+        let mut quiet_hours = QuietHours::daily(time(22, 0), time(7, 0),
+                                                FixedOffset::east_opt(9 * 3600).unwrap());
+        // Sleep in at the weekend.
+        quiet_hours.set(Weekday::Fri, time(23, 0), time(10, 0))
+            .set(Weekday::Sat, time(23, 0), time(10, 0));
+        quiet_hours
+    }
+
+    #[test]
+    fn test_is_quiet() {
+        let quiet_hours = quiet_hours();
+        // Thursday March 15th, 2018.
+        assert!(quiet_hours.is_quiet(&at("2018-03-15T22:00:00+09:00")));
+        assert!(quiet_hours.is_quiet(&at("2018-03-15T03:00:00+09:00")));
+        assert!(!quiet_hours.is_quiet(&at("2018-03-15T07:00:00+09:00")));
+        assert!(!quiet_hours.is_quiet(&at("2018-03-15T12:00:00Z")));
+        assert!(quiet_hours.is_quiet(&at("2018-03-15T13:00:00Z")));
+        assert!(!quiet_hours.is_quiet(&at("2018-03-16T22:30:00+09:00")));
+        assert!(quiet_hours.is_quiet(&at("2018-03-17T09:00:00+09:00")));
+    }
+
+    #[test]
+    fn test_defer_until_allowed() {
+        let quiet_hours = quiet_hours();
+        let allowed = at("2018-03-15T12:00:00+09:00");
+        assert_eq!(quiet_hours.defer_until_allowed(&allowed), Some(allowed));
+        assert_eq!(quiet_hours.defer_until_allowed(&at("2018-03-15T23:00:00+09:00")),
+                   Some(at("2018-03-16T07:00:00+09:00")));
+        assert_eq!(quiet_hours.defer_until_allowed(&at("2018-03-17T00:30:00+09:00")),
+                   Some(at("2018-03-17T10:00:00+09:00")));
+    }
+
+    #[test]
+    fn test_defer_until_allowed_back_to_back() {
+        let mut quiet_hours = quiet_hours();
+        quiet_hours.set(Weekday::Mon, time(7, 0), time(9, 0));
+        assert_eq!(quiet_hours.defer_until_allowed(&at("2018-03-19T06:00:00+09:00")),
+                   Some(at("2018-03-19T09:00:00+09:00")));
+        let always = QuietHours::daily(time(0, 0), time(0, 0), FixedOffset::east_opt(0).unwrap());
+        assert_eq!(always.defer_until_allowed(&at("2018-03-19T06:00:00+09:00")), None);
+    }
+}
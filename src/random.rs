@@ -0,0 +1,272 @@
+extern crate chrono;
+extern crate rand;
+
+use business_days::{is_working_day, Workweek};
+use chrono::{DateTime, Datelike, NaiveTime, TimeZone, Timelike};
+use civil::{resolve_local, DstPolicy};
+use holidays::HolidayCalendar;
+use self::rand::Rng;
+use std::ops::Range;
+use ticks::{from_nanos, to_nanos};
+
+/// Returns a uniformly distributed random DateTime within the half-open `interval`, with
+/// nanosecond resolution, or `None` if the interval is empty.  Passing a seeded RNG (e.g.
+/// `StdRng::seed_from_u64`) makes the result reproducible, which is useful for generating
+/// test data.
+pub fn random_datetime_in<Tz, R>(interval: &Range<DateTime<Tz>>,
+                                 rng: &mut R) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, R: Rng + ?Sized {
+    if interval.start >= interval.end {
+        return None;
+    }
+    let span = to_nanos(interval.end.clone().signed_duration_since(interval.start.clone()));
+    Some(interval.start.clone() + from_nanos(rng.gen_range(0..span))?)
+}
+
+/// Returns a uniformly distributed random DateTime within the half-open `interval` which falls
+/// on a working day (in the sense of `is_working_day`) in the local time of the interval's
+/// timezone, or `None` if no part of the interval does.  Days are measured from local midnight
+/// to local midnight, so a day shortened by a DST change is proportionally less likely.
+pub fn random_business_datetime_in<Tz, C, R>(interval: &Range<DateTime<Tz>>, calendar: &C,
+                                             workweek: &Workweek,
+                                             rng: &mut R) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, C: HolidayCalendar + ?Sized, R: Rng + ?Sized {
+    let tz = interval.start.timezone();
+    // The parts of the interval falling on working days, with their lengths in nanoseconds.
+    let mut segments = Vec::new();
+    let mut total = 0;
+    let mut start = interval.start.clone();
+    let mut date = start.naive_local().date();
+    while start < interval.end {
+        let next = date.succ_opt();
+        let end = next
+            .and_then(|next| {
+                resolve_local(&tz, &next.and_time(NaiveTime::MIN), DstPolicy::ShiftForward)
+            })
+            .map_or(interval.end.clone(), |end| end.min(interval.end.clone()));
+        let length = to_nanos(end.clone().signed_duration_since(start.clone()));
+        if length > 0 && is_working_day(date, calendar, workweek) {
+            segments.push((start, length));
+            total += length;
+        }
+        match next {
+            Some(next) => date = next,
+            None => break,
+        }
+        start = end;
+    }
+    if total == 0 {
+        return None;
+    }
+    let mut offset = rng.gen_range(0..total);
+    for (start, length) in segments {
+        if offset < length {
+            return Some(start + from_nanos(offset)?);
+        }
+        offset -= length;
+    }
+    None
+}
+
+/// Relative weights for each hour of the day and each day of the week, used by
+/// `random_weighted_datetime_in` to produce realistic diurnal and weekly traffic patterns.
+/// The weight of an instant is its hour weight multiplied by its weekday weight; only the
+/// relative sizes of the weights matter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiurnalWeights {
+    /// Weights for each local hour, from 00:00 to 23:00.
+    pub hours: [f64; 24],
+    /// Weights for each weekday, from Monday to Sunday.
+    pub weekdays: [f64; 7],
+}
+
+impl DiurnalWeights {
+    /// Weights concentrated on typical business hours: Monday to Friday, 09:00 to 18:00, with
+    /// a small amount of background activity (a tenth of the peak) at all other times.
+    pub fn business_hours() -> DiurnalWeights {
+        let mut hours = [0.1; 24];
+        for hour in hours.iter_mut().take(18).skip(9) {
+            *hour = 1.0;
+        }
+        DiurnalWeights { hours, weekdays: [1.0, 1.0, 1.0, 1.0, 1.0, 0.1, 0.1] }
+    }
+
+    fn weight_of<Tz: TimeZone>(&self, dt: &DateTime<Tz>) -> f64 {
+        self.hours[dt.hour() as usize] * self.weekdays[dt.weekday().num_days_from_monday() as usize]
+    }
+
+    fn max_weight(&self) -> f64 {
+        let max = |weights: &[f64]| weights.iter().cloned().fold(0.0, f64::max);
+        max(&self.hours) * max(&self.weekdays)
+    }
+}
+
+/// Returns a random DateTime within the half-open `interval`, distributed according to `weights`
+/// in the local time of the interval's timezone.  For example, with
+/// `DiurnalWeights::business_hours()`, a weekday at 10:00 is ten times as likely as a weekday at
+/// 22:00 and a hundred times as likely as a Saturday at 22:00.
+///
+/// Returns `None` if the interval is empty or no instant with a positive weight was found (e.g.
+/// if every weight is zero, or the interval only covers zero-weight hours).
+pub fn random_weighted_datetime_in<Tz, R>(interval: &Range<DateTime<Tz>>, weights: &DiurnalWeights,
+                                          rng: &mut R) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, R: Rng + ?Sized {
+    // Rejection sampling: draw uniformly, and keep the candidate with a probability proportional
+    // to its weight.  The attempt limit only matters for intervals with (almost) no weight.
+    const MAX_ATTEMPTS: usize = 10_000;
+
+    let max_weight = weights.max_weight();
+    if max_weight <= 0.0 {
+        return None;
+    }
+    for _ in 0..MAX_ATTEMPTS {
+        let candidate = random_datetime_in(interval, rng)?;
+        if rng.gen::<f64>() * max_weight < weights.weight_of(&candidate) {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use self::rand::rngs::StdRng;
+    use self::rand::SeedableRng;
+    use chrono::{Duration, FixedOffset, NaiveDate, Utc, Weekday};
+    use holidays::{Holiday, HolidayCategory, HolidayList};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_random_datetime_in_range() {
+        let interval = at("2018-03-15T00:00:00Z")..at("2018-03-16T00:00:00Z");
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let dt = random_datetime_in(&interval, &mut rng).unwrap();
+            assert!(interval.start <= dt && dt < interval.end);
+        }
+    }
+
+    #[test]
+    fn test_random_datetime_in_deterministic() {
+        let interval = at("1900-01-01T00:00:00Z")..at("2400-01-01T00:00:00Z");
+        let first = random_datetime_in(&interval, &mut StdRng::seed_from_u64(7));
+        let second = random_datetime_in(&interval, &mut StdRng::seed_from_u64(7));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_random_datetime_in_empty() {
+        let interval = at("2018-03-15T00:00:00Z")..at("2018-03-15T00:00:00Z");
+        assert_eq!(random_datetime_in(&interval, &mut StdRng::seed_from_u64(42)), None);
+    }
+
+    #[test]
+    fn test_random_weighted_datetime_in_business_hours() {
+        let interval = at("2018-03-12T00:00:00Z")..at("2018-03-19T00:00:00Z");
+        let weights = DiurnalWeights::business_hours();
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut in_hours = 0;
+        for _ in 0..1000 {
+            let dt = random_weighted_datetime_in(&interval, &weights, &mut rng).unwrap();
+            assert!(interval.start <= dt && dt < interval.end);
+            let weekday = dt.weekday() != Weekday::Sat && dt.weekday() != Weekday::Sun;
+            if weekday && dt.hour() >= 9 && dt.hour() < 18 {
+                in_hours += 1;
+            }
+        }
+        // 45 of the week's 168 hours are business hours, and they carry about 82% of the weight.
+        assert!(in_hours > 700, "only {} samples were in business hours", in_hours);
+    }
+
+    #[test]
+    fn test_random_weighted_datetime_in_local_time() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let start = DateTime::parse_from_rfc3339("2018-03-12T00:00:00+09:00").unwrap();
+        let interval = start..start + Duration::days(1);
+        let mut hours = [0.0; 24];
+        hours[10] = 1.0;
+        let weights = DiurnalWeights { hours, weekdays: [1.0; 7] };
+        let dt = random_weighted_datetime_in(&interval, &weights, &mut StdRng::seed_from_u64(1));
+        assert_eq!(dt.unwrap().with_timezone(&tokyo).hour(), 10);
+    }
+
+    #[test]
+    fn test_random_weighted_datetime_in_zero_weights() {
+        let interval = at("2018-03-12T00:00:00Z")..at("2018-03-19T00:00:00Z");
+        let weights = DiurnalWeights { hours: [0.0; 24], weekdays: [1.0; 7] };
+        let dt = random_weighted_datetime_in(&interval, &weights, &mut StdRng::seed_from_u64(1));
+        assert_eq!(dt, None);
+    }
+
+    #[test]
+    fn test_random_business_datetime_in() {
+        // Friday March 16th, 2018 at noon to Tuesday March 20th at noon, with Monday a holiday.
+        let interval = at("2018-03-16T12:00:00Z")..at("2018-03-20T12:00:00Z");
+        let mut calendar = HolidayList::new();
+        calendar.add(NaiveDate::from_ymd_opt(2018, 3, 19).unwrap(),
+                     Holiday::new("Closure", HolidayCategory::Public));
+        let workweek = Workweek::default();
+        let mut rng = StdRng::seed_from_u64(42);
+        let (mut friday, mut tuesday) = (0, 0);
+        for _ in 0..1000 {
+            let dt = random_business_datetime_in(&interval, &calendar, &workweek, &mut rng)
+                .unwrap();
+            assert!(interval.start <= dt && dt < interval.end);
+            match dt.weekday() {
+                Weekday::Fri => friday += 1,
+                Weekday::Tue => tuesday += 1,
+                weekday => panic!("{} is not a working day", weekday),
+            }
+        }
+        // Twelve hours of each day are in the interval.
+        assert!(friday > 400 && tuesday > 400, "{} Fridays, {} Tuesdays", friday, tuesday);
+    }
+
+    #[test]
+    fn test_random_business_datetime_in_local_time() {
+        // Saturday 00:00 to Monday 00:00 in Tokyo is Friday 15:00 to Sunday 15:00 UTC, which
+        // is not a working day in Tokyo at any point.
+        let start = DateTime::parse_from_rfc3339("2018-03-17T00:00:00+09:00").unwrap();
+        let interval = start..start + Duration::days(2);
+        let dt = random_business_datetime_in(&interval, &HolidayList::new(),
+                                             &Workweek::default(),
+                                             &mut StdRng::seed_from_u64(1));
+        assert_eq!(dt, None);
+        let interval = start..start + Duration::days(2) + Duration::hours(1);
+        let dt = random_business_datetime_in(&interval, &HolidayList::new(),
+                                             &Workweek::default(),
+                                             &mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(dt.weekday(), Weekday::Mon);
+        assert_eq!(dt.hour(), 0);
+    }
+
+    #[test]
+    fn test_random_business_datetime_in_dst_gap() {
+        use test_tz::MidnightGap;
+
+        // Sunday November 4th, 2018 starts at 01:00 in MidnightGap, so only Saturday is
+        // skipped.
+        let workweek = Workweek::new(&[Weekday::Sun]);
+        let start = MidnightGap.with_ymd_and_hms(2018, 11, 3, 12, 0, 0).unwrap();
+        let interval = start..start + Duration::days(1);
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let dt = random_business_datetime_in(&interval, &HolidayList::new(), &workweek,
+                                                 &mut rng).unwrap();
+            assert_eq!(dt.weekday(), Weekday::Sun);
+            assert!(dt.hour() >= 1);
+        }
+    }
+
+    #[test]
+    fn test_random_business_datetime_in_empty() {
+        let interval = at("2018-03-16T12:00:00Z")..at("2018-03-16T12:00:00Z");
+        let dt = random_business_datetime_in(&interval, &HolidayList::new(), &Workweek::default(),
+                                             &mut StdRng::seed_from_u64(42));
+        assert_eq!(dt, None);
+    }
+}
@@ -0,0 +1,278 @@
+extern crate chrono;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::ops::RangeInclusive;
+
+/// The number of weeks in each of the three periods of a quarter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetailPattern {
+    FourFourFive,
+    FourFiveFour,
+    FiveFourFour,
+}
+
+impl RetailPattern {
+    fn weeks(&self) -> [u32; 3] {
+        match *self {
+            RetailPattern::FourFourFive => [4, 4, 5],
+            RetailPattern::FourFiveFour => [4, 5, 4],
+            RetailPattern::FiveFourFour => [5, 4, 4],
+        }
+    }
+}
+
+/// How the last day of a retail year is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum YearEndMethod {
+    /// The year ends on the last `end_weekday` of `end_month`.
+    LastOfMonth,
+    /// The year ends on the `end_weekday` nearest to the last day of `end_month`, which may fall
+    /// in the first days of the following month (e.g. the NRF calendar ends on the Saturday
+    /// nearest January 31st).
+    NearestToMonthEnd,
+}
+
+/// How retail years are numbered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum YearNaming {
+    /// A year is named after the calendar year of the end month it ends in (or nearest to), so a
+    /// year ending on January 3rd under `NearestToMonthEnd` with a December end month is named
+    /// after the previous calendar year.
+    EndYear,
+    /// A year is named after the calendar year before the one its end month falls in, which for
+    /// calendars ending early in the year is the calendar year it starts in.  The NRF calendar is
+    /// numbered this way, so fiscal 2018 runs from February 2018 to early February 2019.
+    StartYear,
+}
+
+/// A date's position in a retail calendar, as returned by `RetailCalendar::locate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RetailDate {
+    /// The retail year, numbered according to the calendar's `YearNaming`.
+    pub year: i32,
+    /// The quarter, from 1 to 4.
+    pub quarter: u32,
+    /// The period ("retail month"), from 1 to 12.
+    pub period: u32,
+    /// The week of the retail year, from 1 to 52 (or 53).
+    pub week: u32,
+}
+
+/// A 52/53-week retail (4-4-5, 4-5-4 or 5-4-4) calendar.  Each year is made up of whole weeks,
+/// ending on a fixed weekday near the end of a fixed month, and is divided into four quarters of
+/// 13 weeks, each split into three periods according to the pattern.  When a year has 53 weeks,
+/// the extra week is added to the last period.
+///
+/// Every method taking a `year` takes it as numbered by `naming`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RetailCalendar {
+    pub pattern: RetailPattern,
+    pub end_weekday: Weekday,
+    pub end_month: u32,
+    pub method: YearEndMethod,
+    pub naming: YearNaming,
+}
+
+impl RetailCalendar {
+    /// Returns a calendar with years named after the calendar year they end in
+    /// (`YearNaming::EndYear`).
+    pub fn new(pattern: RetailPattern, end_weekday: Weekday, end_month: u32,
+               method: YearEndMethod) -> RetailCalendar {
+        RetailCalendar { pattern, end_weekday, end_month, method, naming: YearNaming::EndYear }
+    }
+
+    /// The calendar used by the US National Retail Federation: 4-5-4, with years ending on the
+    /// Saturday nearest January 31st, and numbered as NRF does (`YearNaming::StartYear`), so
+    /// `year_bounds(2018)` is fiscal 2018, from February 4th, 2018 to February 2nd, 2019.
+    pub fn nrf() -> RetailCalendar {
+        RetailCalendar {
+            naming: YearNaming::StartYear,
+            ..RetailCalendar::new(RetailPattern::FourFiveFour, Weekday::Sat, 1,
+                                  YearEndMethod::NearestToMonthEnd)
+        }
+    }
+
+    /// The difference between the calendar year of a retail year's end month and its name.
+    fn naming_offset(&self) -> i32 {
+        match self.naming {
+            YearNaming::EndYear => 0,
+            YearNaming::StartYear => 1,
+        }
+    }
+
+    fn year_end(&self, year: i32) -> Option<NaiveDate> {
+        let month_end = NaiveDate::from_ymd_opt(year, self.end_month, 1)?
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?;
+        // Days back from the month end to the previous (or same) end weekday.
+        let back = (month_end.weekday().num_days_from_monday() + 7
+            - self.end_weekday.num_days_from_monday()) % 7;
+        let last = month_end - Duration::days(back as i64);
+        match self.method {
+            YearEndMethod::LastOfMonth => Some(last),
+            YearEndMethod::NearestToMonthEnd if back > 3 => Some(last + Duration::days(7)),
+            YearEndMethod::NearestToMonthEnd => Some(last),
+        }
+    }
+
+    /// Returns the first and last days of the given retail year (numbered by `naming`).
+    pub fn year_bounds(&self, year: i32) -> Option<RangeInclusive<NaiveDate>> {
+        let end_year = year.checked_add(self.naming_offset())?;
+        Some(self.year_end(end_year - 1)?.succ_opt()?..=self.year_end(end_year)?)
+    }
+
+    /// Returns the number of weeks (52 or 53) in the given retail year.
+    pub fn weeks_in_year(&self, year: i32) -> Option<u32> {
+        let bounds = self.year_bounds(year)?;
+        Some(((*bounds.end() - *bounds.start()).num_days() as u32 + 1) / 7)
+    }
+
+    /// Returns the retail year, quarter, period and week containing `date`.
+    pub fn locate(&self, date: NaiveDate) -> Option<RetailDate> {
+        // A year can end a few days into the next calendar year, or start in the previous one.
+        let mut year = date.year();
+        if date > self.year_end(year)? {
+            year += 1;
+        } else if date <= self.year_end(year - 1)? {
+            year -= 1;
+        }
+        let year = year - self.naming_offset();
+        let start = *self.year_bounds(year)?.start();
+        let week = (date - start).num_days() as u32 / 7 + 1;
+
+        let weeks = self.pattern.weeks();
+        let week_in_quarter = (week - 1).min(51) % 13;
+        let period_in_quarter = if week_in_quarter < weeks[0] {
+            0
+        } else if week_in_quarter < weeks[0] + weeks[1] {
+            1
+        } else {
+            2
+        };
+        let quarter = (week - 1).min(51) / 13 + 1;
+        Some(RetailDate { year, quarter, period: (quarter - 1) * 3 + period_in_quarter + 1, week })
+    }
+
+    /// Returns the first and last days of the given period (1 to 12) of a retail year.
+    pub fn period_bounds(&self, year: i32, period: u32) -> Option<RangeInclusive<NaiveDate>> {
+        if period == 0 || period > 12 {
+            return None;
+        }
+        let year_bounds = self.year_bounds(year)?;
+        let weeks = self.pattern.weeks();
+        let quarter_index = (period - 1) / 3;
+        let period_index = ((period - 1) % 3) as usize;
+        let weeks_before = quarter_index * 13 + weeks[..period_index].iter().sum::<u32>();
+
+        let start = *year_bounds.start() + Duration::weeks(weeks_before as i64);
+        let end = if period == 12 {
+            *year_bounds.end()
+        } else {
+            start + Duration::weeks(weeks[period_index] as i64) - Duration::days(1)
+        };
+        Some(start..=end)
+    }
+
+    /// Returns the first and last days of the given quarter (1 to 4) of a retail year.
+    pub fn quarter_bounds(&self, year: i32, quarter: u32) -> Option<RangeInclusive<NaiveDate>> {
+        if quarter == 0 || quarter > 4 {
+            return None;
+        }
+        let first = self.period_bounds(year, quarter * 3 - 2)?;
+        let last = self.period_bounds(year, quarter * 3)?;
+        Some(*first.start()..=*last.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_nrf_year_bounds() {
+        let calendar = RetailCalendar::nrf();
+        // NRF fiscal 2018 ran from February 4th, 2018 to February 2nd, 2019.
+        assert_eq!(calendar.year_bounds(2018), Some(date(2018, 2, 4)..=date(2019, 2, 2)));
+        assert_eq!(calendar.weeks_in_year(2018), Some(52));
+        // NRF fiscal 2017 was a 53-week year.
+        assert_eq!(calendar.year_bounds(2017), Some(date(2017, 1, 29)..=date(2018, 2, 3)));
+        assert_eq!(calendar.weeks_in_year(2017), Some(53));
+        // Named after the year it ends in, fiscal 2018 is 2019.
+        let end_year = RetailCalendar { naming: YearNaming::EndYear, ..calendar };
+        assert_eq!(end_year.year_bounds(2019), calendar.year_bounds(2018));
+    }
+
+    #[test]
+    fn test_last_of_month() {
+        let calendar = RetailCalendar::new(RetailPattern::FourFourFive, Weekday::Sun, 12,
+                                           YearEndMethod::LastOfMonth);
+        assert_eq!(calendar.year_bounds(2018), Some(date(2018, 1, 1)..=date(2018, 12, 30)));
+        assert_eq!(calendar.weeks_in_year(2018), Some(52));
+        assert_eq!(calendar.year_bounds(2023), Some(date(2022, 12, 26)..=date(2023, 12, 31)));
+        assert_eq!(calendar.weeks_in_year(2023), Some(53));
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let calendar = RetailCalendar::nrf();
+        assert_eq!(calendar.period_bounds(2018, 1), Some(date(2018, 2, 4)..=date(2018, 3, 3)));
+        assert_eq!(calendar.period_bounds(2018, 2), Some(date(2018, 3, 4)..=date(2018, 4, 7)));
+        assert_eq!(calendar.period_bounds(2018, 3), Some(date(2018, 4, 8)..=date(2018, 5, 5)));
+        assert_eq!(calendar.period_bounds(2018, 12), Some(date(2019, 1, 6)..=date(2019, 2, 2)));
+        // The extra week of a 53-week year goes to the last period.
+        assert_eq!(calendar.period_bounds(2017, 12), Some(date(2017, 12, 31)..=date(2018, 2, 3)));
+        assert_eq!(calendar.period_bounds(2018, 13), None);
+    }
+
+    #[test]
+    fn test_quarter_bounds() {
+        let calendar = RetailCalendar::nrf();
+        assert_eq!(calendar.quarter_bounds(2018, 1), Some(date(2018, 2, 4)..=date(2018, 5, 5)));
+        assert_eq!(calendar.quarter_bounds(2018, 4), Some(date(2018, 11, 4)..=date(2019, 2, 2)));
+        assert_eq!(calendar.quarter_bounds(2018, 0), None);
+    }
+
+    #[test]
+    fn test_locate() {
+        let calendar = RetailCalendar::nrf();
+        let expected = RetailDate { year: 2018, quarter: 1, period: 2, week: 6 };
+        assert_eq!(calendar.locate(date(2018, 3, 15)), Some(expected));
+        let expected = RetailDate { year: 2018, quarter: 1, period: 1, week: 1 };
+        assert_eq!(calendar.locate(date(2018, 2, 4)), Some(expected));
+        let expected = RetailDate { year: 2017, quarter: 4, period: 12, week: 53 };
+        assert_eq!(calendar.locate(date(2018, 2, 3)), Some(expected));
+        let expected = RetailDate { year: 2018, quarter: 4, period: 12, week: 52 };
+        assert_eq!(calendar.locate(date(2019, 2, 2)), Some(expected));
+    }
+
+    #[test]
+    fn test_locate_december_year_end() {
+        // The Saturday nearest December 31st 2025 is January 3rd 2026.
+        let calendar = RetailCalendar::new(RetailPattern::FourFourFive, Weekday::Sat, 12,
+                                           YearEndMethod::NearestToMonthEnd);
+        assert_eq!(calendar.year_bounds(2025), Some(date(2024, 12, 29)..=date(2026, 1, 3)));
+        let expected = RetailDate { year: 2025, quarter: 4, period: 12, week: 53 };
+        assert_eq!(calendar.locate(date(2026, 1, 2)), Some(expected));
+        assert_eq!(calendar.locate(date(2026, 1, 3)), Some(expected));
+        let expected = RetailDate { year: 2026, quarter: 1, period: 1, week: 1 };
+        assert_eq!(calendar.locate(date(2026, 1, 4)), Some(expected));
+        // 2024 ended on December 28th, so December 29th is already in 2025.
+        let expected = RetailDate { year: 2025, quarter: 1, period: 1, week: 1 };
+        assert_eq!(calendar.locate(date(2024, 12, 29)), Some(expected));
+    }
+
+    #[test]
+    fn test_locate_matches_period_bounds() {
+        let calendar = RetailCalendar::new(RetailPattern::FiveFourFour, Weekday::Sat, 8,
+                                           YearEndMethod::NearestToMonthEnd);
+        for period in 1..13 {
+            let bounds = calendar.period_bounds(2020, period).unwrap();
+            assert_eq!(calendar.locate(*bounds.start()).unwrap().period, period);
+            assert_eq!(calendar.locate(*bounds.end()).unwrap().period, period);
+        }
+    }
+}
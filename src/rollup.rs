@@ -0,0 +1,180 @@
+extern crate chrono;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use civil::{resolve_local, DstPolicy};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Returns the instant at which the business day `date` starts, with days starting at `cutoff`
+/// local time.  A cutoff in a DST gap is moved forward by the length of the gap.
+fn day_start<Tz: TimeZone>(date: NaiveDate, cutoff: NaiveTime, tz: &Tz) -> Option<DateTime<Tz>> {
+    resolve_local(tz, &date.and_time(cutoff), DstPolicy::ShiftForward)
+}
+
+/// Returns the logical date of the shift (or business day) containing `dt`, with days starting at
+/// `day_start_cutoff` local time in `tz`: times before the cutoff belong to the previous day, so
+/// with a 06:00 cutoff, 01:00 on a Saturday is part of Friday's night shift.  As in
+/// `rollup_boundaries`, a cutoff in a DST gap is moved forward by the length of the gap.
+pub fn shift_date<Tz: TimeZone, Tz2: TimeZone>(dt: &DateTime<Tz>, day_start_cutoff: NaiveTime,
+                                               tz: &Tz2) -> NaiveDate {
+    let dt = dt.with_timezone(tz);
+    let date = dt.date_naive();
+    match day_start(date, day_start_cutoff, tz) {
+        Some(start) if dt < start => date.pred_opt().unwrap_or(date),
+        _ => date,
+    }
+}
+
+/// Divide `interval` into business days which start at `cutoff` local time in `tz` rather than
+/// at midnight (e.g. a 04:00 cutoff counts 02:00 on Saturday as part of Friday night).  Each part
+/// is returned with the date of its business day, clipped to the interval, in order.  Cutoffs
+/// which fall in a DST gap are moved forward by the length of the gap, so business days may be
+/// shorter or longer than 24 hours across DST transitions.
+pub fn rollup_boundaries<Tz: TimeZone, Tz2: TimeZone>(interval: &Range<DateTime<Tz>>, tz: &Tz2,
+                                                      cutoff: NaiveTime)
+                                                      -> Vec<(NaiveDate, Range<DateTime<Tz2>>)> {
+    let start = interval.start.with_timezone(tz);
+    let end = interval.end.with_timezone(tz);
+    let mut parts = Vec::new();
+    let mut date = shift_date(&start, cutoff, tz);
+    let mut from = start;
+    while from < end {
+        let next_start = date.succ_opt().and_then(|next| day_start(next, cutoff, tz));
+        let to = match next_start {
+            Some(next_start) if next_start < end => next_start,
+            _ => end.clone(),
+        };
+        parts.push((date, from..to.clone()));
+        from = to;
+        date = match date.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    parts
+}
+
+/// Group `items` by the shift date (see `shift_date`) of the instant returned by `key` for each
+/// item.  The items don't need to be sorted; each group keeps the items in their original order.
+pub fn group_by_shift_date<I, F, Tz, Tz2>(items: I, mut key: F, day_start_cutoff: NaiveTime,
+                                          tz: &Tz2) -> BTreeMap<NaiveDate, Vec<I::Item>>
+    where I: IntoIterator, F: FnMut(&I::Item) -> DateTime<Tz>, Tz: TimeZone, Tz2: TimeZone {
+    let mut groups = BTreeMap::new();
+    for item in items {
+        let date = shift_date(&key(&item), day_start_cutoff, tz);
+        groups.entry(date).or_insert_with(Vec::new).push(item);
+    }
+    groups
+}
+
+/// Fold `f` over the local calendar days of `tz` which `interval` covers, as a building block for
+/// per-day accumulations such as degree days.  `f` is called in order with the accumulator, the
+/// date, and the part of the interval on that date.  Days run from local midnight to midnight,
+/// so across DST transitions they are 23 or 25 hours long (see `rollup_boundaries`, which this
+/// uses with a midnight cutoff).
+pub fn accumulate_over_days<Tz, Tz2, A, F>(interval: &Range<DateTime<Tz>>, tz: &Tz2, init: A,
+                                           mut f: F) -> A
+    where Tz: TimeZone, Tz2: TimeZone, F: FnMut(A, NaiveDate, Range<DateTime<Tz2>>) -> A {
+    rollup_boundaries(interval, tz, NaiveTime::MIN)
+        .into_iter()
+        .fold(init, |acc, (date, part)| f(acc, date, part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, FixedOffset, Utc};
+    use test_tz::Cet;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_rollup_boundaries() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let cutoff = NaiveTime::from_hms_opt(4, 0, 0).unwrap();
+        let interval = at("2018-03-15T12:00:00+09:00")..at("2018-03-17T02:00:00+09:00");
+        assert_eq!(rollup_boundaries(&interval, &tz, cutoff), vec![
+            (date(2018, 3, 15), at("2018-03-15T12:00:00+09:00")..at("2018-03-16T04:00:00+09:00")),
+            (date(2018, 3, 16), at("2018-03-16T04:00:00+09:00")..at("2018-03-17T02:00:00+09:00")),
+        ]);
+        // Before the cutoff, the interval starts in the previous business day.
+        let interval = at("2018-03-15T01:00:00+09:00")..at("2018-03-15T05:00:00+09:00");
+        assert_eq!(rollup_boundaries(&interval, &tz, cutoff), vec![
+            (date(2018, 3, 14), at("2018-03-15T01:00:00+09:00")..at("2018-03-15T04:00:00+09:00")),
+            (date(2018, 3, 15), at("2018-03-15T04:00:00+09:00")..at("2018-03-15T05:00:00+09:00")),
+        ]);
+        assert!(rollup_boundaries(&(interval.end..interval.start), &tz, cutoff).is_empty());
+    }
+
+    #[test]
+    fn test_shift_date() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let cutoff = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+        assert_eq!(shift_date(&at("2018-03-17T01:00:00+09:00"), cutoff, &tz), date(2018, 3, 16));
+        assert_eq!(shift_date(&at("2018-03-17T06:00:00+09:00"), cutoff, &tz), date(2018, 3, 17));
+        // 20:00 UTC is 05:00 the next morning in the shift's timezone.
+        assert_eq!(shift_date(&at("2018-03-16T20:00:00Z"), cutoff, &tz), date(2018, 3, 16));
+    }
+
+    #[test]
+    fn test_group_by_shift_date() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let cutoff = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+        let events = vec![("late", at("2018-03-17T01:00:00+09:00")),
+                          ("evening", at("2018-03-16T22:00:00+09:00")),
+                          ("morning", at("2018-03-17T07:00:00+09:00"))];
+        let groups = group_by_shift_date(events, |event| event.1, cutoff, &tz);
+        let names: Vec<_> = groups.iter()
+            .map(|(date, events)| (*date, events.iter().map(|e| e.0).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(names, vec![(date(2018, 3, 16), vec!["late", "evening"]),
+                               (date(2018, 3, 17), vec!["morning"])]);
+    }
+
+    #[test]
+    fn test_rollup_boundaries_dst() {
+        // Clocks in Cet jump from 02:00 to 03:00 on March 25th, 2018, so the 02:30 cutoff moves
+        // to 03:30 and the business day of the 24th is only 23 hours long.
+        let cutoff = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        let start = at("2018-03-24T12:00:00Z").with_timezone(&Utc);
+        let end = at("2018-03-26T12:00:00Z").with_timezone(&Utc);
+        let parts = rollup_boundaries(&(start..end), &Cet, cutoff);
+        let bounds: Vec<_> = parts.iter()
+            .map(|(date, part)| (*date, part.start.to_rfc3339(), part.end.to_rfc3339()))
+            .collect();
+        assert_eq!(shift_date(&at("2018-03-25T01:15:00Z"), cutoff, &Cet), date(2018, 3, 24));
+        assert_eq!(bounds, vec![
+            (date(2018, 3, 24), "2018-03-24T13:00:00+01:00".to_string(),
+             "2018-03-25T03:30:00+02:00".to_string()),
+            (date(2018, 3, 25), "2018-03-25T03:30:00+02:00".to_string(),
+             "2018-03-26T02:30:00+02:00".to_string()),
+            (date(2018, 3, 26), "2018-03-26T02:30:00+02:00".to_string(),
+             "2018-03-26T14:00:00+02:00".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_accumulate_over_days() {
+        let interval = at("2018-03-24T12:00:00+01:00")..at("2018-03-27T00:00:00+02:00");
+        let hours = accumulate_over_days(&interval, &Cet, Vec::new(), |mut hours, date, part| {
+            hours.push((date, part.end.signed_duration_since(part.start).num_hours()));
+            hours
+        });
+        // March 25th is only 23 hours long, as clocks go forward.
+        assert_eq!(hours, vec![(date(2018, 3, 24), 12), (date(2018, 3, 25), 23),
+                               (date(2018, 3, 26), 24)]);
+        // Growing degree days over a 10 degree base, from each day's mean temperature.
+        let mean_temperature = |date: NaiveDate| -> f64 { 8.0 + date.day() as f64 * 0.5 };
+        let interval = at("2018-05-01T00:00:00Z")..at("2018-05-11T00:00:00Z");
+        let degree_days = accumulate_over_days(&interval, &Utc, 0.0, |total, date, _| {
+            total + (mean_temperature(date) - 10.0).max(0.0)
+        });
+        assert_eq!(degree_days, 0.5 + 1.0 + 1.5 + 2.0 + 2.5 + 3.0);
+    }
+}
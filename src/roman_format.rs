@@ -0,0 +1,92 @@
+extern crate chrono;
+
+use chrono::{Datelike, NaiveDate};
+
+const ROMAN_MONTHS: [&str; 12] = [
+    "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII",
+];
+
+/// Returns the Roman numeral for a month number (1-12), or `None` if the month is out of range.
+pub fn month_to_roman(month: u32) -> Option<&'static str> {
+    if month == 0 { None } else { ROMAN_MONTHS.get(month as usize - 1).cloned() }
+}
+
+/// Returns the month number (1-12) for a Roman numeral month.  Lower-case numerals are accepted.
+pub fn month_from_roman(roman: &str) -> Option<u32> {
+    ROMAN_MONTHS.iter()
+        .position(|r| r.eq_ignore_ascii_case(roman))
+        .map(|i| i as u32 + 1)
+}
+
+/// This trait defines formatting of dates with the month written as a Roman numeral, as is
+/// common in some European date styles (e.g. "15.III.2018").
+pub trait RomanMonthFormatting {
+    /// Format self as day, Roman numeral month and year, joined by `sep` (e.g. "15.III.2018").
+    fn to_roman_date(&self, sep: char) -> String;
+}
+
+impl<T> RomanMonthFormatting for T where T: Datelike {
+    fn to_roman_date(&self, sep: char) -> String {
+        let month = ROMAN_MONTHS[self.month0() as usize];
+        format!("{}{}{}{}{}", self.day(), sep, month, sep, self.year())
+    }
+}
+
+/// Parse a date written as day, Roman numeral month and year, joined by `sep`.  This is the
+/// inverse of `RomanMonthFormatting::to_roman_date`.  Surrounding whitespace around each part is
+/// ignored, and `None` is returned if the string is malformed or names a day that doesn't exist.
+pub fn parse_roman_date(s: &str, sep: char) -> Option<NaiveDate> {
+    let mut parts = s.split(sep).map(str::trim);
+    let day = parts.next()?.parse().ok()?;
+    let month = month_from_roman(parts.next()?)?;
+    let year = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_month_to_roman() {
+        assert_eq!(month_to_roman(1), Some("I"));
+        assert_eq!(month_to_roman(4), Some("IV"));
+        assert_eq!(month_to_roman(9), Some("IX"));
+        assert_eq!(month_to_roman(12), Some("XII"));
+        assert_eq!(month_to_roman(0), None);
+        assert_eq!(month_to_roman(13), None);
+    }
+
+    #[test]
+    fn test_month_from_roman() {
+        assert_eq!(month_from_roman("VIII"), Some(8));
+        assert_eq!(month_from_roman("xi"), Some(11));
+        assert_eq!(month_from_roman("IIII"), None);
+        assert_eq!(month_from_roman(""), None);
+    }
+
+    #[test]
+    fn test_to_roman_date() {
+        let test_date = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(test_date.to_roman_date('.'), "15.III.2018");
+        assert_eq!(test_date.date_naive().to_roman_date(' '), "15 III 2018");
+    }
+
+    #[test]
+    fn test_parse_roman_date() {
+        assert_eq!(parse_roman_date("15.III.2018", '.'), NaiveDate::from_ymd_opt(2018, 3, 15));
+        assert_eq!(parse_roman_date("1 / xii / 1999", '/'), NaiveDate::from_ymd_opt(1999, 12, 1));
+    }
+
+    #[test]
+    fn test_parse_roman_date_invalid() {
+        assert_eq!(parse_roman_date("30.II.2018", '.'), None);
+        assert_eq!(parse_roman_date("15.3.2018", '.'), None);
+        assert_eq!(parse_roman_date("15.III", '.'), None);
+        assert_eq!(parse_roman_date("15.III.2018.1", '.'), None);
+    }
+}
@@ -0,0 +1,362 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use civil::{resolve_local, DstPolicy};
+use error::ChronoUtilsError;
+use month_calc::{days_in_month, shift_year_month};
+use std::iter;
+
+/// A recurring sequence of instants, such as "every Monday at 09:00 in Tokyo" or "the 31st of
+/// each month at noon".  Instants are given in UTC so that schedules in different timezones can
+/// be navigated the same way; convert the results with `with_timezone` as needed.  The trait is
+/// object safe, so a scheduler can take a `Box<dyn Schedule>` and be given any kind of schedule.
+pub trait Schedule {
+    /// Returns the first occurrence strictly after `dt`, or `None` if there is none in range.
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>>;
+
+    /// Returns the last occurrence strictly before `dt`, or `None` if there is none in range.
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>>;
+
+    /// Returns an iterator over the occurrences at or after `start`, in order.  The iterator ends
+    /// when there are no more occurrences in range.
+    fn iter_from<'a>(&'a self,
+                     start: &DateTime<Utc>) -> Box<dyn Iterator<Item = DateTime<Utc>> + 'a> {
+        let mut next = start.checked_sub_signed(Duration::nanoseconds(1))
+            .and_then(|just_before| self.next_after(&just_before));
+        Box::new(iter::from_fn(move || {
+            let occurrence = next?;
+            next = self.next_after(&occurrence);
+            Some(occurrence)
+        }))
+    }
+}
+
+impl<S: Schedule + ?Sized> Schedule for &S {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (**self).next_after(dt)
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (**self).prev_before(dt)
+    }
+}
+
+impl<S: Schedule + ?Sized> Schedule for Box<S> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (**self).next_after(dt)
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (**self).prev_before(dt)
+    }
+}
+
+/// Which way `snap_to` looks for an occurrence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The latest occurrence at or before the given time.
+    Before,
+    /// The earliest occurrence at or after the given time.
+    After,
+}
+
+/// Returns the occurrence of `schedule` nearest to `dt` in the given direction, in `dt`'s
+/// timezone.  Unlike `next_after` and `prev_before`, `dt` itself is returned if it is an
+/// occurrence.  Returns `None` if there is no such occurrence in range.
+pub fn snap_to<Tz, S>(dt: &DateTime<Tz>, schedule: &S,
+                      direction: Direction) -> Option<DateTime<Tz>>
+    where Tz: TimeZone, S: Schedule + ?Sized {
+    let utc = dt.with_timezone(&Utc);
+    let nanosecond = Duration::nanoseconds(1);
+    let snapped = match direction {
+        Direction::Before => schedule.prev_before(&utc.checked_add_signed(nanosecond)?),
+        Direction::After => schedule.next_after(&utc.checked_sub_signed(nanosecond)?),
+    };
+    snapped.map(|snapped| snapped.with_timezone(&dt.timezone()))
+}
+
+/// What a job runner does about occurrences of its schedule which were missed while it wasn't
+/// running, as decided by `missed_occurrences`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CatchUpPolicy {
+    /// Run every missed occurrence.
+    All,
+    /// Run only the most recent missed occurrence.
+    LatestOnly,
+    /// Don't run any missed occurrences, and wait for the next one.
+    Skip,
+}
+
+/// Returns the occurrences of `schedule` to run after downtime, when the last run was at
+/// `last_run` and the time is now `now`.  Missed occurrences are those after `last_run` and at or
+/// before `now`; `policy` decides which of them are returned, oldest first.
+pub fn missed_occurrences<S, Tz, Tz2>(schedule: &S, last_run: &DateTime<Tz>, now: &DateTime<Tz2>,
+                                      policy: CatchUpPolicy) -> Vec<DateTime<Utc>>
+    where S: Schedule + ?Sized, Tz: TimeZone, Tz2: TimeZone {
+    let now = now.with_timezone(&Utc);
+    match policy {
+        CatchUpPolicy::All => {
+            let mut missed = Vec::new();
+            let mut from = last_run.with_timezone(&Utc);
+            while let Some(next) = schedule.next_after(&from).filter(|next| *next <= now) {
+                missed.push(next);
+                from = next;
+            }
+            missed
+        },
+        CatchUpPolicy::LatestOnly => {
+            snap_to(&now, schedule, Direction::Before)
+                .filter(|latest| *latest > last_run.with_timezone(&Utc))
+                .into_iter()
+                .collect()
+        },
+        CatchUpPolicy::Skip => Vec::new(),
+    }
+}
+
+/// Returns the next `n` occurrences of `schedule` after `after`, each converted to every one of
+/// `zones` (in the same order), for showing upcoming occurrences to people in several timezones.
+/// Fewer than `n` occurrences are returned if the schedule runs out.
+pub fn occurrences_localized<S, Tz, Tz2>(schedule: &S, after: &DateTime<Tz2>, n: usize,
+                                         zones: &[Tz]) -> Vec<Vec<DateTime<Tz>>>
+    where S: Schedule + ?Sized, Tz: TimeZone, Tz2: TimeZone {
+    let mut from = after.with_timezone(&Utc);
+    let mut occurrences = Vec::with_capacity(n);
+    while occurrences.len() < n {
+        match schedule.next_after(&from) {
+            Some(next) => {
+                occurrences.push(zones.iter().map(|tz| next.with_timezone(tz)).collect());
+                from = next;
+            },
+            None => break,
+        }
+    }
+    occurrences
+}
+
+/// Returns the instant of `time` on `date` in `tz`.  Times in a DST gap are moved forward by the
+/// length of the gap, and ambiguous times use the earlier instant, so there is exactly one
+/// occurrence per day.
+fn occurrence_on<Tz: TimeZone>(date: NaiveDate, time: NaiveTime,
+                               tz: &Tz) -> Option<DateTime<Utc>> {
+    resolve_local(tz, &date.and_time(time), DstPolicy::ShiftForward)
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// A schedule occurring once a week, on `weekday` at `time` in `tz`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeeklySchedule<Tz: TimeZone> {
+    weekday: Weekday,
+    time: NaiveTime,
+    tz: Tz,
+}
+
+impl<Tz: TimeZone> WeeklySchedule<Tz> {
+    pub fn new(weekday: Weekday, time: NaiveTime, tz: Tz) -> WeeklySchedule<Tz> {
+        WeeklySchedule { weekday, time, tz }
+    }
+}
+
+impl<Tz: TimeZone> Schedule for WeeklySchedule<Tz> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Starting a day early catches an occurrence whose local date differs from dt's.
+        let local_date = dt.with_timezone(&self.tz).date_naive();
+        (-1..8)
+            .filter_map(|days| local_date.checked_add_signed(Duration::days(days)))
+            .filter(|date| date.weekday() == self.weekday)
+            .filter_map(|date| occurrence_on(date, self.time, &self.tz))
+            .find(|occurrence| occurrence > dt)
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_date = dt.with_timezone(&self.tz).date_naive();
+        (-1..8)
+            .filter_map(|days| local_date.checked_sub_signed(Duration::days(days)))
+            .filter(|date| date.weekday() == self.weekday)
+            .filter_map(|date| occurrence_on(date, self.time, &self.tz))
+            .find(|occurrence| occurrence < dt)
+    }
+}
+
+/// A schedule occurring once a month, on a given day of the month at `time` in `tz`.  Days past
+/// the end of a shorter month are clamped to its last day, as with `add_months`, so a schedule on
+/// the 31st occurs on February 28th (or 29th) and April 30th.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonthlySchedule<Tz: TimeZone> {
+    day: u32,
+    time: NaiveTime,
+    tz: Tz,
+}
+
+impl<Tz: TimeZone> MonthlySchedule<Tz> {
+    /// Returns a schedule on `day` of each month, or `ChronoUtilsError::InvalidDay` if `day` is 0
+    /// or over 31.
+    pub fn new(day: u32, time: NaiveTime,
+               tz: Tz) -> Result<MonthlySchedule<Tz>, ChronoUtilsError> {
+        if day == 0 || day > 31 {
+            return Err(ChronoUtilsError::InvalidDay);
+        }
+        Ok(MonthlySchedule { day, time, tz })
+    }
+
+    /// Returns the occurrence in the month `num_months` from `date`'s month.
+    fn occurrence_in(&self, date: NaiveDate, num_months: i64) -> Option<DateTime<Utc>> {
+        let (year, month) = shift_year_month(date.year(), date.month(), num_months)?;
+        let day = self.day.min(days_in_month(year, month)?);
+        occurrence_on(NaiveDate::from_ymd_opt(year, month, day)?, self.time, &self.tz)
+    }
+}
+
+impl<Tz: TimeZone> Schedule for MonthlySchedule<Tz> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_date = dt.with_timezone(&self.tz).date_naive();
+        (-1..3)
+            .filter_map(|months| self.occurrence_in(local_date, months))
+            .find(|occurrence| occurrence > dt)
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_date = dt.with_timezone(&self.tz).date_naive();
+        (-2..2).rev()
+            .filter_map(|months| self.occurrence_in(local_date, months))
+            .find(|occurrence| occurrence < dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use test_tz::Cet;
+    use ticks::ticks;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_schedule() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let monday = WeeklySchedule::new(Weekday::Mon, time(9, 0), tokyo);
+        // Sunday 2018-03-18 23:00 UTC is already Monday 08:00 in Tokyo.
+        let now = at("2018-03-18T23:00:00Z");
+        assert_eq!(monday.next_after(&now), Some(at("2018-03-19T09:00:00+09:00")));
+        assert_eq!(monday.prev_before(&now), Some(at("2018-03-12T09:00:00+09:00")));
+        let occurrence = at("2018-03-19T09:00:00+09:00");
+        assert_eq!(monday.next_after(&occurrence), Some(at("2018-03-26T09:00:00+09:00")));
+        assert_eq!(monday.prev_before(&occurrence), Some(at("2018-03-12T09:00:00+09:00")));
+    }
+
+    #[test]
+    fn test_weekly_schedule_dst_gap() {
+        let sunday = WeeklySchedule::new(Weekday::Sun, time(2, 30), Cet);
+        let now = at("2018-03-20T12:00:00Z");
+        // 02:30 doesn't exist on 2018-03-25, so that week's occurrence is at 03:30 CEST.
+        assert_eq!(sunday.next_after(&now), Some(at("2018-03-25T03:30:00+02:00")));
+        assert_eq!(sunday.prev_before(&now), Some(at("2018-03-18T02:30:00+01:00")));
+    }
+
+    #[test]
+    fn test_monthly_schedule() {
+        let month_end = MonthlySchedule::new(31, time(12, 0), Utc).unwrap();
+        let now = at("2018-01-31T12:00:00Z");
+        assert_eq!(month_end.next_after(&now), Some(at("2018-02-28T12:00:00Z")));
+        assert_eq!(month_end.prev_before(&now), Some(at("2017-12-31T12:00:00Z")));
+        assert_eq!(month_end.next_after(&at("2018-02-28T13:00:00Z")),
+                   Some(at("2018-03-31T12:00:00Z")));
+        assert_eq!(MonthlySchedule::new(0, time(12, 0), Utc), Err(ChronoUtilsError::InvalidDay));
+        assert_eq!(MonthlySchedule::new(32, time(12, 0), Utc), Err(ChronoUtilsError::InvalidDay));
+    }
+
+    #[test]
+    fn test_snap_to() {
+        let monday = WeeklySchedule::new(Weekday::Mon, time(9, 0), Utc);
+        let payday = MonthlySchedule::new(25, time(0, 0), Utc).unwrap();
+        let now = DateTime::parse_from_rfc3339("2018-03-21T10:00:00+09:00").unwrap();
+        let snapped = snap_to(&now, &monday, Direction::After).unwrap();
+        assert_eq!(snapped.to_rfc3339(), "2018-03-26T18:00:00+09:00");
+        let snapped = snap_to(&now, &monday, Direction::Before).unwrap();
+        assert_eq!(snapped.to_rfc3339(), "2018-03-19T18:00:00+09:00");
+        let snap_utc = |direction| {
+            snap_to(&now, &payday, direction).map(|dt| dt.with_timezone(&Utc))
+        };
+        assert_eq!(snap_utc(Direction::After), Some(at("2018-03-25T00:00:00Z")));
+        assert_eq!(snap_utc(Direction::Before), Some(at("2018-02-25T00:00:00Z")));
+        // An occurrence snaps to itself in either direction.
+        assert_eq!(snapped.with_timezone(&Utc), at("2018-03-19T09:00:00Z"));
+        assert_eq!(snap_to(&snapped, &monday, Direction::After), Some(snapped));
+        assert_eq!(snap_to(&snapped, &monday, Direction::Before), Some(snapped));
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let monday = WeeklySchedule::new(Weekday::Mon, time(9, 0), Utc);
+        let mondays: Vec<_> = monday.iter_from(&at("2018-03-19T09:00:00Z")).take(3).collect();
+        assert_eq!(mondays, vec![at("2018-03-19T09:00:00Z"), at("2018-03-26T09:00:00Z"),
+                                 at("2018-04-02T09:00:00Z")]);
+        let mondays: Vec<_> = monday.iter_from(&at("2018-03-19T09:00:01Z")).take(1).collect();
+        assert_eq!(mondays, vec![at("2018-03-26T09:00:00Z")]);
+    }
+
+    #[test]
+    fn test_boxed_schedules() {
+        let schedules: Vec<Box<dyn Schedule>> = vec![
+            Box::new(WeeklySchedule::new(Weekday::Mon, time(9, 0), Cet)),
+            Box::new(MonthlySchedule::new(1, time(0, 0), Utc).unwrap()),
+            Box::new(ticks(at("2018-03-15T00:00:00Z"), Duration::hours(6))),
+        ];
+        let now = at("2018-03-21T10:00:00Z");
+        let next: Vec<_> = schedules.iter().map(|schedule| schedule.next_after(&now)).collect();
+        assert_eq!(next, vec![Some(at("2018-03-26T07:00:00Z")), Some(at("2018-04-01T00:00:00Z")),
+                              Some(at("2018-03-21T12:00:00Z"))]);
+        let prev: Vec<_> = schedules.iter().map(|schedule| schedule.prev_before(&now)).collect();
+        assert_eq!(prev, vec![Some(at("2018-03-19T08:00:00Z")), Some(at("2018-03-01T00:00:00Z")),
+                              Some(at("2018-03-21T06:00:00Z"))]);
+        let boxed = &schedules[2];
+        assert_eq!(boxed.iter_from(&now).nth(1), Some(at("2018-03-21T18:00:00Z")));
+        assert_eq!(snap_to(&now, boxed, Direction::Before), Some(at("2018-03-21T06:00:00Z")));
+    }
+
+    #[test]
+    fn test_missed_occurrences() {
+        let hourly = ticks(at("2018-03-15T00:00:00Z"), Duration::hours(1));
+        let last_run = at("2018-03-15T09:00:00Z");
+        let now = DateTime::parse_from_rfc3339("2018-03-15T21:30:00+09:00").unwrap();
+        assert_eq!(missed_occurrences(&hourly, &last_run, &now, CatchUpPolicy::All),
+                   vec![at("2018-03-15T10:00:00Z"), at("2018-03-15T11:00:00Z"),
+                        at("2018-03-15T12:00:00Z")]);
+        assert_eq!(missed_occurrences(&hourly, &last_run, &now, CatchUpPolicy::LatestOnly),
+                   vec![at("2018-03-15T12:00:00Z")]);
+        assert!(missed_occurrences(&hourly, &last_run, &now, CatchUpPolicy::Skip).is_empty());
+        // An occurrence exactly at `now` counts as missed, and one at `last_run` doesn't.
+        let now = at("2018-03-15T10:00:00Z");
+        assert_eq!(missed_occurrences(&hourly, &last_run, &now, CatchUpPolicy::All), vec![now]);
+        let nothing_missed = at("2018-03-15T09:59:59Z");
+        for policy in [CatchUpPolicy::All, CatchUpPolicy::LatestOnly] {
+            assert!(missed_occurrences(&hourly, &last_run, &nothing_missed, policy).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_occurrences_localized() {
+        let standup = WeeklySchedule::new(Weekday::Mon, time(9, 30), Cet);
+        let zones = [FixedOffset::west_opt(5 * 3600).unwrap(),
+                     FixedOffset::east_opt(9 * 3600).unwrap()];
+        let after = at("2018-03-15T00:00:00Z");
+        let localized: Vec<Vec<String>> = occurrences_localized(&standup, &after, 2, &zones)
+            .iter()
+            .map(|zoned| zoned.iter().map(|dt| dt.to_rfc3339()).collect())
+            .collect();
+        assert_eq!(localized, vec![
+            vec!["2018-03-19T03:30:00-05:00", "2018-03-19T17:30:00+09:00"],
+            vec!["2018-03-26T02:30:00-05:00", "2018-03-26T16:30:00+09:00"],
+        ]);
+        let once = MonthlySchedule::new(1, time(0, 0), Utc).unwrap();
+        assert_eq!(occurrences_localized(&once, &after, 0, &zones).len(), 0);
+        assert!(occurrences_localized(&once, &after, 3, &[] as &[Utc]).iter().all(Vec::is_empty));
+    }
+}
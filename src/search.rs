@@ -0,0 +1,73 @@
+extern crate chrono;
+
+use chrono::{DateTime, TimeZone};
+use std::ops::Range;
+
+/// Returns the index of the first DateTime in `slice` that is at or after the instant `dt` (or
+/// `slice.len()` if there is none).  `slice` must be sorted by instant.  Comparisons are by
+/// instant, so `slice` and `dt` may use different timezones, and a `DateTime<FixedOffset>` slice
+/// may mix offsets.
+pub fn partition_point_by_instant<Tz, Tz2>(slice: &[DateTime<Tz>], dt: &DateTime<Tz2>) -> usize
+    where Tz: TimeZone, Tz2: TimeZone {
+    slice.partition_point(|x| x < dt)
+}
+
+/// Returns the range of indices of the DateTimes in `slice` which fall within the half-open
+/// interval `interval` (i.e. at or after `interval.start` and before `interval.end`).  `slice`
+/// must be sorted by instant.  An empty or reversed interval results in an empty range.
+pub fn range_indices<Tz, Tz2>(slice: &[DateTime<Tz>],
+                              interval: &Range<DateTime<Tz2>>) -> Range<usize>
+    where Tz: TimeZone, Tz2: TimeZone {
+    let start = partition_point_by_instant(slice, &interval.start);
+    let end = partition_point_by_instant(slice, &interval.end);
+    start..end.max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn series() -> Vec<DateTime<FixedOffset>> {
+        vec![
+            DateTime::parse_from_rfc3339("2018-03-15T09:00:00+09:00").unwrap(),
+            DateTime::parse_from_rfc3339("2018-03-15T01:00:00Z").unwrap(),
+            DateTime::parse_from_rfc3339("2018-03-15T02:00:00Z").unwrap(),
+            DateTime::parse_from_rfc3339("2018-03-14T22:00:00-05:00").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_partition_point_by_instant() {
+        let series = series();
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        assert_eq!(partition_point_by_instant(&series, &at("2018-03-14T00:00:00Z")), 0);
+        assert_eq!(partition_point_by_instant(&series, &at("2018-03-15T00:00:00Z")), 0);
+        assert_eq!(partition_point_by_instant(&series, &at("2018-03-15T00:00:01Z")), 1);
+        assert_eq!(partition_point_by_instant(&series, &at("2018-03-15T02:30:00Z")), 3);
+        assert_eq!(partition_point_by_instant(&series, &at("2018-03-16T00:00:00Z")), 4);
+    }
+
+    #[test]
+    fn test_range_indices() {
+        let series = series();
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let interval = at("2018-03-15T01:00:00Z")..at("2018-03-15T03:00:00Z");
+        assert_eq!(range_indices(&series, &interval), 1..3);
+
+        let interval = at("2018-03-15T00:00:00Z")..at("2018-03-16T00:00:00Z");
+        assert_eq!(range_indices(&series, &interval), 0..4);
+    }
+
+    #[test]
+    fn test_range_indices_empty() {
+        let series = series();
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let interval = at("2018-03-15T01:10:00Z")..at("2018-03-15T01:20:00Z");
+        assert!(range_indices(&series, &interval).is_empty());
+
+        let interval = at("2018-03-16T00:00:00Z")..at("2018-03-15T00:00:00Z");
+        assert!(range_indices(&series, &interval).is_empty());
+        assert!(range_indices(&[] as &[DateTime<Utc>], &interval).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use civil::{resolve_local, DstPolicy};
+use month_calc::shift_year_month;
+use std::ops::Range;
+
+/// The four seasons of the year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+/// The hemisphere a season is reckoned for.  The seasons in the southern hemisphere are the
+/// opposite of those in the northern hemisphere (December is summer).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    Northern,
+    Southern,
+}
+
+/// How the seasons are divided up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SeasonConvention {
+    /// Whole months, as used for weather statistics: in the northern hemisphere, spring is March
+    /// to May, summer June to August, autumn September to November and winter December to
+    /// February.  Seasons start at midnight (local time) on the first of the month.
+    Meteorological,
+    /// Seasons start at the equinoxes and solstices: in the northern hemisphere, spring starts at
+    /// the March equinox, summer at the June solstice, autumn at the September equinox and
+    /// winter at the December solstice.
+    Astronomical,
+}
+
+const SEASONS: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+/// Returns the season of the index of the quarter starting in March (0), June (1), September (2)
+/// or December (3) in `hemisphere`.
+fn season_of(quarter: usize, hemisphere: Hemisphere) -> Season {
+    match hemisphere {
+        Hemisphere::Northern => SEASONS[quarter % 4],
+        Hemisphere::Southern => SEASONS[(quarter + 2) % 4],
+    }
+}
+
+/// Returns the season `dt` is in, and the half-open interval the season covers, in `dt`'s
+/// timezone.  Astronomical seasons are calculated with the algorithm for equinoxes and solstices
+/// from Meeus' Astronomical Algorithms, which is accurate to within a couple of minutes for the
+/// years 1000 to 3000; `None` is returned outside those years, and if the season's bounds are out
+/// of range.
+pub fn season<Tz: TimeZone>(dt: &DateTime<Tz>, hemisphere: Hemisphere,
+                            convention: SeasonConvention) -> Option<(Season, Range<DateTime<Tz>>)> {
+    match convention {
+        SeasonConvention::Meteorological => {
+            let date = dt.date_naive();
+            // Count quarters from March, so that December to February is one quarter.
+            let (year, month) = shift_year_month(date.year(), date.month(), -2)?;
+            let quarter = (month - 1) / 3;
+            let (start_year, start_month) = shift_year_month(year, quarter * 3 + 1, 2)?;
+            let (end_year, end_month) = shift_year_month(start_year, start_month, 3)?;
+            let start = start_of_month(start_year, start_month, &dt.timezone())?;
+            let end = start_of_month(end_year, end_month, &dt.timezone())?;
+            Some((season_of(quarter as usize, hemisphere), start..end))
+        },
+        SeasonConvention::Astronomical => {
+            let year = dt.with_timezone(&Utc).year();
+            // The December solstice of the previous year, and then every equinox and solstice
+            // until the March equinox of the following year.
+            let mut bounds = vec![(3, solstice_or_equinox(year - 1, 3)?)];
+            for quarter in 0..4 {
+                bounds.push((quarter, solstice_or_equinox(year, quarter)?));
+            }
+            bounds.push((0, solstice_or_equinox(year + 1, 0)?));
+            bounds.windows(2)
+                .find(|pair| pair[0].1 <= *dt && *dt < pair[1].1)
+                .map(|pair| {
+                    let tz = dt.timezone();
+                    (season_of(pair[0].0, hemisphere),
+                     pair[0].1.with_timezone(&tz)..pair[1].1.with_timezone(&tz))
+                })
+        },
+    }
+}
+
+fn start_of_month<Tz: TimeZone>(year: i32, month: u32, tz: &Tz) -> Option<DateTime<Tz>> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    resolve_local(tz, &first.and_time(NaiveTime::MIN), DstPolicy::ShiftForward)
+}
+
+// Periodic terms (A, B, C) for the equinoxes and solstices, from Meeus' table 27.C.
+const PERIODIC_TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136), (203.0, 337.23, 32964.467), (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112), (156.0, 73.14, 45036.886), (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934), (74.0, 296.72, 3034.906), (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147), (52.0, 297.17, 150.678), (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562), (44.0, 325.15, 31555.956), (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328), (17.0, 288.79, 4562.452), (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921), (12.0, 95.39, 14577.848), (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259), (9.0, 227.73, 1222.114), (8.0, 15.45, 16859.074),
+];
+
+// Polynomial coefficients for the mean March equinox, June solstice, September equinox and
+// December solstice, from Meeus' table 27.B (for the years 1000 to 3000).
+const MEAN_TERMS: [[f64; 5]; 4] = [
+    [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+    [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+    [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+    [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+];
+
+/// The Julian day of the Unix epoch.
+pub(crate) const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+/// Returns the instant of the March equinox (`quarter` 0), June solstice (1), September equinox
+/// (2) or December solstice (3) of `year`, or `None` if the year is outside 1000 to 3000.
+fn solstice_or_equinox(year: i32, quarter: usize) -> Option<DateTime<Utc>> {
+    if !(1000..=3000).contains(&year) {
+        return None;
+    }
+    let y = (year - 2000) as f64 / 1000.0;
+    let mean = MEAN_TERMS[quarter].iter().rev().fold(0.0, |sum, coefficient| sum * y + coefficient);
+    let t = (mean - 2451545.0) / 36525.0;
+    let w = (35999.373 * t - 2.47).to_radians();
+    let delta_lambda = 1.0 + 0.0334 * w.cos() + 0.0007 * (2.0 * w).cos();
+    let s: f64 = PERIODIC_TERMS.iter()
+        .map(|(a, b, c)| a * (b + c * t).to_radians().cos())
+        .sum();
+    let jde = mean + 0.00001 * s / delta_lambda;
+    let millis = ((jde - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    DateTime::<Utc>::UNIX_EPOCH.checked_add_signed(Duration::milliseconds(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_meteorological_seasons() {
+        let christmas = at("2018-12-25T12:00:00+09:00");
+        let (north, interval) = season(&christmas, Hemisphere::Northern,
+                                       SeasonConvention::Meteorological).unwrap();
+        assert_eq!(north, Season::Winter);
+        assert_eq!(interval, at("2018-12-01T00:00:00+09:00")..at("2019-03-01T00:00:00+09:00"));
+        let (south, _) = season(&christmas, Hemisphere::Southern,
+                                SeasonConvention::Meteorological).unwrap();
+        assert_eq!(south, Season::Summer);
+        let (north, interval) = season(&at("2018-02-28T23:59:59+09:00"), Hemisphere::Northern,
+                                       SeasonConvention::Meteorological).unwrap();
+        assert_eq!(north, Season::Winter);
+        assert_eq!(interval.start, at("2017-12-01T00:00:00+09:00"));
+        let (north, _) = season(&at("2018-09-01T00:00:00+09:00"), Hemisphere::Northern,
+                                SeasonConvention::Meteorological).unwrap();
+        assert_eq!(north, Season::Autumn);
+    }
+
+    #[test]
+    fn test_astronomical_seasons() {
+        // The 2018 March equinox was at 16:15 UTC on March 20th, and the June solstice at 10:07
+        // UTC on June 21st.
+        let (north, interval) = season(&at("2018-04-01T00:00:00Z"), Hemisphere::Northern,
+                                       SeasonConvention::Astronomical).unwrap();
+        assert_eq!(north, Season::Spring);
+        let error = |actual: DateTime<FixedOffset>, expected: &str| {
+            actual.signed_duration_since(at(expected)).num_seconds().abs()
+        };
+        assert!(error(interval.start, "2018-03-20T16:15:00Z") < 120);
+        assert!(error(interval.end, "2018-06-21T10:07:00Z") < 120);
+        let (south, _) = season(&at("2018-04-01T00:00:00Z"), Hemisphere::Southern,
+                                SeasonConvention::Astronomical).unwrap();
+        assert_eq!(south, Season::Autumn);
+        // The 2017 December solstice was at 16:28 UTC on December 21st.
+        let (north, interval) = season(&at("2018-01-15T00:00:00Z"), Hemisphere::Northern,
+                                       SeasonConvention::Astronomical).unwrap();
+        assert_eq!(north, Season::Winter);
+        assert!(error(interval.start, "2017-12-21T16:28:00Z") < 120);
+        assert_eq!(season(&at("0999-06-01T00:00:00Z"), Hemisphere::Northern,
+                          SeasonConvention::Astronomical), None);
+    }
+}
@@ -0,0 +1,175 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::cmp::Ordering;
+
+/// Compare two instants which may come from machines whose clocks disagree by up to `tolerance`
+/// (the sign of `tolerance` is ignored).  Returns `Ordering::Equal` if the instants are within
+/// `tolerance` of each other, since their true order can't be known, and otherwise the order of
+/// `a` relative to `b`.
+pub fn ordering_with_skew<Tz, Tz2>(a: &DateTime<Tz>, b: &DateTime<Tz2>,
+                                   tolerance: Duration) -> Ordering
+    where Tz: TimeZone, Tz2: TimeZone {
+    let difference = a.clone().signed_duration_since(b.clone());
+    let tolerance = tolerance.abs();
+    if difference < -tolerance {
+        Ordering::Less
+    } else if difference > tolerance {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Returns true if `a` and `b` are within `tolerance` of each other (inclusive).
+pub fn approx_eq<Tz, Tz2>(a: &DateTime<Tz>, b: &DateTime<Tz2>, tolerance: Duration) -> bool
+    where Tz: TimeZone, Tz2: TimeZone {
+    ordering_with_skew(a, b, tolerance) == Ordering::Equal
+}
+
+/// Returns true if `a` is before `b` even allowing for up to `skew` of clock disagreement, i.e.
+/// `a` is more than `skew` earlier than `b`.
+pub fn is_before_with_skew<Tz, Tz2>(a: &DateTime<Tz>, b: &DateTime<Tz2>, skew: Duration) -> bool
+    where Tz: TimeZone, Tz2: TimeZone {
+    ordering_with_skew(a, b, skew) == Ordering::Less
+}
+
+/// The offset and drift of a remote clock relative to the local one, as estimated by
+/// `estimate_skew`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkewEstimate {
+    /// The local time at which `offset` was estimated (the latest local time sampled).
+    pub reference: DateTime<Utc>,
+    /// How far the remote clock was ahead of the local clock at `reference` (negative if it was
+    /// behind).
+    pub offset: Duration,
+    /// How much the offset grows per unit of local time, e.g. `0.00001` if the remote clock gains
+    /// 10 microseconds every second.
+    pub drift: f64,
+}
+
+impl SkewEstimate {
+    /// Returns the estimated offset of the remote clock at the local time `local`.
+    pub fn offset_at<Tz: TimeZone>(&self, local: &DateTime<Tz>) -> Duration {
+        let elapsed = seconds(local.clone().signed_duration_since(self.reference));
+        self.offset + from_seconds(elapsed * self.drift)
+    }
+
+    /// Converts a local time to the time the remote clock would show at that moment.
+    pub fn to_remote<Tz: TimeZone>(&self, local: &DateTime<Tz>) -> DateTime<Utc> {
+        local.with_timezone(&Utc) + self.offset_at(local)
+    }
+
+    /// Converts a timestamp reported by the remote clock to local time.
+    pub fn to_local<Tz: TimeZone>(&self, remote: &DateTime<Tz>) -> DateTime<Utc> {
+        // remote = local + offset + drift * (local - reference), solved for local.
+        let since_reference = seconds(remote.clone().signed_duration_since(self.reference))
+            - seconds(self.offset);
+        self.reference + from_seconds(since_reference / (1.0 + self.drift))
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
+
+fn from_seconds(seconds: f64) -> Duration {
+    Duration::nanoseconds((seconds.fract() * 1e9).round() as i64)
+        + Duration::seconds(seconds.trunc() as i64)
+}
+
+/// Estimate the offset and drift of a remote clock from samples of `(local, remote)` times taken
+/// at the same moments (e.g. the midpoint of a request's local send and receive times, and the
+/// server's reported time), using a least-squares linear fit of the offset over local time.  With
+/// a single sample, or samples all taken at the same local time, the drift is taken to be zero
+/// and the offset is averaged.  Returns `None` if there are no samples.
+pub fn estimate_skew<Tz, Tz2>(samples: &[(DateTime<Tz>, DateTime<Tz2>)]) -> Option<SkewEstimate>
+    where Tz: TimeZone, Tz2: TimeZone {
+    let reference = samples.iter().map(|(local, _)| local.with_timezone(&Utc)).max()?;
+    let points: Vec<(f64, f64)> = samples.iter().map(|(local, remote)| {
+        (seconds(local.clone().signed_duration_since(reference)),
+         seconds(remote.clone().signed_duration_since(local.clone())))
+    }).collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let drift = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    Some(SkewEstimate { reference, offset: from_seconds(mean_y - drift * mean_x), drift })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<chrono::FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_ordering_with_skew() {
+        let a = at("2018-03-15T12:00:00Z");
+        let cmp = |b| ordering_with_skew(&a, &at(b), Duration::milliseconds(500));
+        assert_eq!(cmp("2018-03-15T12:00:00.400Z"), Ordering::Equal);
+        assert_eq!(cmp("2018-03-15T11:59:59.500Z"), Ordering::Equal);
+        assert_eq!(cmp("2018-03-15T12:00:00.501Z"), Ordering::Less);
+        assert_eq!(cmp("2018-03-15T11:59:59Z"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_ordering_with_skew_across_offsets() {
+        let a = at("2018-03-15T21:00:00.100+09:00");
+        let b = at("2018-03-15T12:00:00Z");
+        assert_eq!(ordering_with_skew(&a, &b, Duration::milliseconds(200)), Ordering::Equal);
+        assert_eq!(ordering_with_skew(&a, &b, Duration::milliseconds(-200)), Ordering::Equal);
+        assert_eq!(ordering_with_skew(&a, &b, Duration::zero()), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = at("2018-03-15T12:00:00Z");
+        assert!(approx_eq(&a, &at("2018-03-15T12:00:01Z"), Duration::seconds(1)));
+        assert!(!approx_eq(&a, &at("2018-03-15T12:00:01.001Z"), Duration::seconds(1)));
+        assert!(approx_eq(&a, &a, Duration::zero()));
+    }
+
+    #[test]
+    fn test_is_before_with_skew() {
+        let a = at("2018-03-15T12:00:00Z");
+        assert!(is_before_with_skew(&a, &at("2018-03-15T12:00:02Z"), Duration::seconds(1)));
+        assert!(!is_before_with_skew(&a, &at("2018-03-15T12:00:01Z"), Duration::seconds(1)));
+        assert!(!is_before_with_skew(&at("2018-03-15T12:00:02Z"), &a, Duration::seconds(1)));
+    }
+
+    #[test]
+    fn test_estimate_skew() {
+        // The remote clock starts 2 seconds ahead and gains 1 millisecond per second.
+        let start = at("2018-03-15T12:00:00Z");
+        let samples: Vec<_> = (0..5).map(|i| {
+            let local = start + Duration::seconds(i * 100);
+            (local, local + Duration::seconds(2) + Duration::milliseconds(i * 100))
+        }).collect();
+        let estimate = estimate_skew(&samples).unwrap();
+        assert_eq!(estimate.reference, at("2018-03-15T12:06:40Z"));
+        assert!((estimate.drift - 0.001).abs() < 1e-9);
+        assert!((estimate.offset - Duration::milliseconds(2400)).abs() < Duration::microseconds(1));
+
+        let local = at("2018-03-15T12:10:00Z");
+        let remote = at("2018-03-15T12:10:02.600Z");
+        let error = |a: DateTime<Utc>, b| a.signed_duration_since(b).abs();
+        assert!(error(estimate.to_remote(&local), remote) < Duration::microseconds(1));
+        assert!(error(estimate.to_local(&remote), local) < Duration::microseconds(1));
+    }
+
+    #[test]
+    fn test_estimate_skew_without_drift() {
+        let local = at("2018-03-15T12:00:00Z");
+        let estimate = estimate_skew(&[(local, at("2018-03-15T21:00:00.250+09:00"))]).unwrap();
+        assert_eq!(estimate.offset, Duration::milliseconds(250));
+        assert_eq!(estimate.drift, 0.0);
+        assert_eq!(estimate.to_local(&at("2018-03-15T12:01:00.250Z")), at("2018-03-15T12:01:00Z"));
+        assert_eq!(estimate_skew::<Utc, Utc>(&[]), None);
+    }
+}
@@ -0,0 +1,249 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Timelike};
+use std::f64::consts::PI;
+use period_key::Unit;
+use std::ops::Range;
+use ticks::{from_nanos, to_nanos};
+use windows::{windows_by, Window};
+
+/// Occurrence statistics for a stream of timestamps, as returned by `occurrence_stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OccurrenceStats<Tz: TimeZone> {
+    /// The number of occurrences in each bucket, in order.  Buckets without any occurrences are
+    /// not included.
+    pub buckets: Vec<(Range<DateTime<Tz>>, usize)>,
+    /// The shortest gap between two consecutive occurrences (`None` if there are fewer than two).
+    pub min_gap: Option<Duration>,
+    /// The longest gap between two consecutive occurrences (`None` if there are fewer than two).
+    pub max_gap: Option<Duration>,
+    /// The mean gap between consecutive occurrences (`None` if there are fewer than two).
+    pub mean_gap: Option<Duration>,
+}
+
+/// Count the occurrences in a sorted stream of timestamps per calendar `unit` (in the local time
+/// of `tz`), and compute the minimum, maximum and mean gaps between consecutive occurrences.
+pub fn occurrence_stats<I, Tz>(iter: I, unit: Unit, tz: Tz) -> OccurrenceStats<Tz>
+    where I: IntoIterator<Item = DateTime<Tz>>, Tz: TimeZone {
+    let mut buckets = Vec::new();
+    let mut previous: Option<DateTime<Tz>> = None;
+    let mut min_gap: Option<Duration> = None;
+    let mut max_gap: Option<Duration> = None;
+    let mut gap_total = Duration::zero();
+    let mut gap_count = 0;
+
+    for (bounds, items) in windows_by(iter, Window::Calendar(unit), tz) {
+        buckets.push((bounds, items.len()));
+        for item in items {
+            if let Some(prev) = previous {
+                let gap = item.clone().signed_duration_since(prev);
+                min_gap = Some(min_gap.map_or(gap, |g| g.min(gap)));
+                max_gap = Some(max_gap.map_or(gap, |g| g.max(gap)));
+                gap_total += gap;
+                gap_count += 1;
+            }
+            previous = Some(item);
+        }
+    }
+
+    OccurrenceStats {
+        buckets,
+        min_gap,
+        max_gap,
+        mean_gap: if gap_count > 0 { Some(gap_total / gap_count) } else { None },
+    }
+}
+
+/// Returns the weighted mean ("center of mass") of the instants in `pairs`, each given with its
+/// weight, in the timezone of the first instant.  Offsets from the first instant are computed in
+/// whole nanoseconds without overflow, and then weighted as floating point numbers, so the
+/// result is exact to the nanosecond for instants up to about 100 days apart and to within a
+/// microsecond for several centuries.  Returns `None` if `pairs` is empty, any weight is
+/// negative or not finite, or the weights add up to zero.
+pub fn weighted_mean_instant<Tz: TimeZone>(pairs: &[(DateTime<Tz>, f64)]) -> Option<DateTime<Tz>> {
+    let reference = &pairs.first()?.0;
+    let mut total_weight = 0.0;
+    let mut weighted_offset = 0.0;
+    for (dt, weight) in pairs {
+        if !weight.is_finite() || *weight < 0.0 {
+            return None;
+        }
+        let offset = to_nanos(dt.clone().signed_duration_since(reference.clone()));
+        total_weight += weight;
+        weighted_offset += offset as f64 * weight;
+    }
+    if total_weight == 0.0 {
+        return None;
+    }
+    let mean_offset = from_nanos((weighted_offset / total_weight).round() as i128)?;
+    reference.clone().checked_add_signed(mean_offset)
+}
+
+/// Returns the median of `instants`, in the timezone of the first one.  For an even number of
+/// instants this is the midpoint of the middle two, computed in nanoseconds without overflow.
+/// Returns `None` if `instants` is empty.
+pub fn median_instant<Tz: TimeZone>(instants: &[DateTime<Tz>]) -> Option<DateTime<Tz>> {
+    let tz = instants.first()?.timezone();
+    let mut sorted = instants.to_vec();
+    sorted.sort();
+    let upper = sorted[sorted.len() / 2].clone();
+    let median = if sorted.len() % 2 == 1 {
+        upper
+    } else {
+        let lower = sorted[sorted.len() / 2 - 1].clone();
+        let half_gap = to_nanos(upper.signed_duration_since(lower.clone())) / 2;
+        lower.checked_add_signed(from_nanos(half_gap)?)?
+    };
+    Some(median.with_timezone(&tz))
+}
+
+const NANOS_PER_DAY: f64 = 86_400_000_000_000.0;
+
+/// Returns the sums of the sines and cosines of `times` as angles on a 24 hour clock face, and
+/// the number of times.
+fn clock_vector<I: IntoIterator<Item = NaiveTime>>(times: I) -> (f64, f64, usize) {
+    times.into_iter().fold((0.0, 0.0, 0), |(sin, cos, count), time| {
+        let nanos = time.num_seconds_from_midnight() as f64 * 1e9 + time.nanosecond() as f64;
+        let angle = nanos / NANOS_PER_DAY * 2.0 * PI;
+        (sin + angle.sin(), cos + angle.cos(), count + 1)
+    })
+}
+
+/// Returns the mean resultant length of `times` (from 0 if they are spread evenly around the
+/// clock to 1 if they are all the same) and their mean angle, or `None` if there are none.
+fn clock_mean<I: IntoIterator<Item = NaiveTime>>(times: I) -> Option<(f64, f64)> {
+    let (sin, cos, count) = clock_vector(times);
+    if count == 0 {
+        return None;
+    }
+    Some((sin.hypot(cos) / count as f64, sin.atan2(cos)))
+}
+
+/// Returns the mean time of day of `times`, treating times as angles on a 24 hour clock face
+/// (circular statistics) so that times either side of midnight average correctly: the mean of
+/// 23:50 and 00:10 is 00:00, not 12:00.  The result is rounded to the nearest millisecond.
+/// Returns `None` if there are no times, or they are spread so evenly around the clock that they
+/// have no mean (such as 06:00 and 18:00).
+pub fn mean_time_of_day<I: IntoIterator<Item = NaiveTime>>(times: I) -> Option<NaiveTime> {
+    let (length, angle) = clock_mean(times)?;
+    if length < 1e-9 {
+        return None;
+    }
+    let millis = (angle.rem_euclid(2.0 * PI) / (2.0 * PI) * 86_400_000.0).round() as u32;
+    let millis = millis % 86_400_000;
+    NaiveTime::from_num_seconds_from_midnight_opt(millis / 1000, millis % 1000 * 1_000_000)
+}
+
+/// Returns the circular standard deviation of the times of day in `times` (`sqrt(-2 ln R)`,
+/// where `R` is the mean resultant length), as a duration on the 24 hour clock.  Times which are
+/// close together give about the same result as the ordinary standard deviation, even across
+/// midnight, and it grows without bound as the times spread evenly around the clock.  Returns
+/// `None` if there are no times, or they have no mean (see `mean_time_of_day`).
+pub fn std_dev_time_of_day<I: IntoIterator<Item = NaiveTime>>(times: I) -> Option<Duration> {
+    let (length, _) = clock_mean(times)?;
+    if length < 1e-9 {
+        return None;
+    }
+    let radians = (-2.0 * length.min(1.0).ln()).sqrt();
+    let millis = (radians / (2.0 * PI) * 86_400_000.0).round() as i64;
+    Some(Duration::milliseconds(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_occurrence_stats() {
+        let stamps = vec![
+            at("2018-03-15T10:00:00Z"),
+            at("2018-03-15T11:00:00Z"),
+            at("2018-03-15T14:00:00Z"),
+            at("2018-03-17T14:00:00Z"),
+        ];
+        let stats = occurrence_stats(stamps, Unit::Day, Utc);
+        assert_eq!(stats.buckets, vec![
+            (at("2018-03-15T00:00:00Z")..at("2018-03-16T00:00:00Z"), 3),
+            (at("2018-03-17T00:00:00Z")..at("2018-03-18T00:00:00Z"), 1),
+        ]);
+        assert_eq!(stats.min_gap, Some(Duration::hours(1)));
+        assert_eq!(stats.max_gap, Some(Duration::hours(48)));
+        assert_eq!(stats.mean_gap, Some(Duration::hours(52) / 3));
+    }
+
+    #[test]
+    fn test_occurrence_stats_single() {
+        let stats = occurrence_stats(vec![at("2018-03-15T10:00:00Z")], Unit::Month, Utc);
+        assert_eq!(stats.buckets.len(), 1);
+        assert_eq!(stats.buckets[0].1, 1);
+        assert_eq!(stats.min_gap, None);
+        assert_eq!(stats.max_gap, None);
+        assert_eq!(stats.mean_gap, None);
+    }
+
+    #[test]
+    fn test_occurrence_stats_empty() {
+        let stats = occurrence_stats(Vec::new(), Unit::Week, Utc);
+        assert!(stats.buckets.is_empty());
+        assert_eq!(stats.mean_gap, None);
+    }
+
+    #[test]
+    fn test_weighted_mean_instant() {
+        let pairs = vec![(at("2018-03-15T10:00:00Z"), 1.0), (at("2018-03-15T14:00:00Z"), 3.0)];
+        assert_eq!(weighted_mean_instant(&pairs), Some(at("2018-03-15T13:00:00Z")));
+        // Instants near the end of chrono's range don't overflow.
+        let max = DateTime::<Utc>::MAX_UTC;
+        let near_max = vec![(max - Duration::days(2), 1.0), (max, 1.0)];
+        assert_eq!(weighted_mean_instant(&near_max), Some(max - Duration::days(1)));
+        assert_eq!(weighted_mean_instant::<Utc>(&[]), None);
+        assert_eq!(weighted_mean_instant(&[(at("2018-03-15T10:00:00Z"), 0.0)]), None);
+        assert_eq!(weighted_mean_instant(&[(at("2018-03-15T10:00:00Z"), -1.0)]), None);
+        assert_eq!(weighted_mean_instant(&[(at("2018-03-15T10:00:00Z"), f64::NAN)]), None);
+    }
+
+    #[test]
+    fn test_median_instant() {
+        let stamps = vec![at("2018-03-15T14:00:00Z"), at("2018-03-15T10:00:00Z"),
+                          at("2018-03-17T14:00:00Z")];
+        assert_eq!(median_instant(&stamps), Some(at("2018-03-15T14:00:00Z")));
+        let even = vec![at("2018-03-15T14:00:00Z"), at("2018-03-15T10:00:00Z"),
+                        at("2018-03-15T11:00:00.000000001Z"), at("2018-03-17T14:00:00Z")];
+        assert_eq!(median_instant(&even), Some(at("2018-03-15T12:30:00Z")));
+        let extremes = vec![DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC];
+        assert!(median_instant(&extremes).is_some());
+        assert_eq!(median_instant::<Utc>(&[]), None);
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_mean_time_of_day() {
+        assert_eq!(mean_time_of_day(vec![time(23, 50), time(0, 10)]), Some(time(0, 0)));
+        // The circular mean is close to, but not exactly, the arithmetic mean of the offsets from
+        // midnight (-60, -30 and +90 minutes).
+        assert_eq!(mean_time_of_day(vec![time(23, 0), time(23, 30), time(1, 30)]),
+                   Some(NaiveTime::from_hms_milli_opt(23, 59, 28, 206).unwrap()));
+        assert_eq!(mean_time_of_day(vec![time(9, 0), time(10, 0)]), Some(time(9, 30)));
+        assert_eq!(mean_time_of_day(vec![time(6, 0), time(18, 0)]), None);
+        assert_eq!(mean_time_of_day(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_std_dev_time_of_day() {
+        assert_eq!(std_dev_time_of_day(vec![time(9, 0); 3]), Some(Duration::zero()));
+        // Close together, the result is about the ordinary standard deviation (10 minutes).
+        let spread = std_dev_time_of_day(vec![time(23, 50), time(0, 10)]).unwrap();
+        assert_eq!(spread.num_seconds(), 600);
+        let wide = std_dev_time_of_day(vec![time(0, 0), time(8, 0), time(15, 0)]).unwrap();
+        assert!(wide > Duration::hours(6));
+        assert_eq!(std_dev_time_of_day(vec![time(6, 0), time(18, 0)]), None);
+    }
+}
@@ -0,0 +1,84 @@
+extern crate chrono;
+
+use chrono::{DateTime, TimeZone, Utc};
+use month_calc::completed_months;
+
+/// A labeled tenure band, covering completed service of at least `min_months` months (and less
+/// than the `min_months` of the next band).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TenureBand<'a> {
+    pub min_months: u32,
+    pub label: &'a str,
+}
+
+/// Returns the label of the band that the service time from `start` to `now` falls into, or
+/// `None` if `now` is before `start` or before the first band.  `bands` must be sorted by
+/// `min_months`.
+///
+/// Only completed months count, with the same anniversary handling as
+/// `YearCalculations::years_since`: both instants are compared as UTC dates, and a year (or
+/// month) of service is completed on the anniversary date itself.  A start on the 31st completes
+/// a month on the last day of shorter months.
+pub fn tenure_band<'a, Tz, Tz2>(start: &DateTime<Tz>, now: &DateTime<Tz2>,
+                                bands: &[TenureBand<'a>]) -> Option<&'a str>
+    where Tz: TimeZone, Tz2: TimeZone {
+    let start = start.with_timezone(&Utc).date_naive();
+    let now = now.with_timezone(&Utc).date_naive();
+    if now < start {
+        return None;
+    }
+    let months = completed_months(&now, &start);
+    bands.iter()
+        .take_while(|band| band.min_months <= months as u32)
+        .last()
+        .map(|band| band.label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANDS: [TenureBand<'static>; 3] = [
+        TenureBand { min_months: 0, label: "<1y" },
+        TenureBand { min_months: 12, label: "1-3y" },
+        TenureBand { min_months: 36, label: "3y+" },
+    ];
+
+    fn band(start: &str, now: &str) -> Option<&'static str> {
+        let start = DateTime::parse_from_rfc3339(start).unwrap();
+        let now = DateTime::parse_from_rfc3339(now).unwrap();
+        tenure_band(&start, &now, &BANDS)
+    }
+
+    #[test]
+    fn test_tenure_band() {
+        assert_eq!(band("2018-03-15T09:00:00Z", "2018-03-15T09:00:00Z"), Some("<1y"));
+        assert_eq!(band("2018-03-15T09:00:00Z", "2019-03-14T23:00:00Z"), Some("<1y"));
+        assert_eq!(band("2018-03-15T09:00:00Z", "2020-11-01T09:00:00Z"), Some("1-3y"));
+        assert_eq!(band("2018-03-15T09:00:00Z", "2025-01-01T09:00:00Z"), Some("3y+"));
+    }
+
+    #[test]
+    fn test_tenure_band_anniversary() {
+        // The anniversary counts regardless of the time of day, like years_since.
+        assert_eq!(band("2018-03-15T09:00:00Z", "2019-03-15T00:00:00Z"), Some("1-3y"));
+        assert_eq!(band("2018-03-15T09:00:00Z", "2021-03-15T00:00:00Z"), Some("3y+"));
+        assert_eq!(band("2018-03-15T09:00:00Z", "2021-03-14T23:59:59Z"), Some("1-3y"));
+    }
+
+    #[test]
+    fn test_tenure_band_month_end_start() {
+        let bands = [TenureBand { min_months: 0, label: "new" },
+                     TenureBand { min_months: 1, label: "1m+" }];
+        let start = DateTime::parse_from_rfc3339("2018-01-31T09:00:00Z").unwrap();
+        let now = DateTime::parse_from_rfc3339("2018-02-28T09:00:00Z").unwrap();
+        assert_eq!(tenure_band(&start, &now, &bands), Some("1m+"));
+    }
+
+    #[test]
+    fn test_tenure_band_before_start() {
+        assert_eq!(band("2018-03-15T09:00:00Z", "2018-03-14T09:00:00Z"), None);
+        let start = DateTime::parse_from_rfc3339("2018-03-15T09:00:00Z").unwrap();
+        assert_eq!(tenure_band(&start, &start, &BANDS[1..]), None);
+    }
+}
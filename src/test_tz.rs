@@ -0,0 +1,102 @@
+extern crate chrono;
+
+use chrono::{Datelike, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone,
+             Weekday};
+
+/// A minimal Central European Time zone (UTC+1, or UTC+2 from the last Sunday of March to the last
+/// Sunday of October, switching at 01:00 UTC), for tests which need DST transitions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cet;
+
+fn last_sunday(year: i32, month: u32) -> NaiveDate {
+    let last = NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap().pred_opt().unwrap();
+    let back = (last.weekday().num_days_from_monday() + 7
+        - Weekday::Sun.num_days_from_monday()) % 7;
+    last - Duration::days(back as i64)
+}
+
+fn is_summer(utc: &NaiveDateTime) -> bool {
+    let start = last_sunday(utc.year(), 3).and_hms_opt(1, 0, 0).unwrap();
+    let end = last_sunday(utc.year(), 10).and_hms_opt(1, 0, 0).unwrap();
+    *utc >= start && *utc < end
+}
+
+fn winter() -> FixedOffset {
+    FixedOffset::east_opt(3600).unwrap()
+}
+
+fn summer() -> FixedOffset {
+    FixedOffset::east_opt(7200).unwrap()
+}
+
+impl TimeZone for Cet {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Cet {
+        Cet
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        let as_winter = !is_summer(&(*local - Duration::hours(1)));
+        let as_summer = is_summer(&(*local - Duration::hours(2)));
+        match (as_summer, as_winter) {
+            (true, true) => LocalResult::Ambiguous(summer(), winter()),
+            (true, false) => LocalResult::Single(summer()),
+            (false, true) => LocalResult::Single(winter()),
+            (false, false) => LocalResult::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        if is_summer(utc) { summer() } else { winter() }
+    }
+}
+
+/// A zone at UTC-3 which moves its clocks forward to UTC-2 at midnight on November 4th, 2018 (as
+/// São Paulo did), so that the day starts at 01:00, for tests of DST gaps at midnight.  It never
+/// switches back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MidnightGap;
+
+fn midnight_gap_start() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2018, 11, 4).unwrap().and_hms_opt(3, 0, 0).unwrap()
+}
+
+impl TimeZone for MidnightGap {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> MidnightGap {
+        MidnightGap
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        if *local + Duration::hours(3) < midnight_gap_start() {
+            LocalResult::Single(FixedOffset::west_opt(3 * 3600).unwrap())
+        } else if *local + Duration::hours(2) >= midnight_gap_start() {
+            LocalResult::Single(FixedOffset::west_opt(2 * 3600).unwrap())
+        } else {
+            LocalResult::None
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        let hours = if *utc < midnight_gap_start() { 3 } else { 2 };
+        FixedOffset::west_opt(hours * 3600).unwrap()
+    }
+}
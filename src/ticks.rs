@@ -0,0 +1,130 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use schedule::Schedule;
+use std::convert::TryFrom;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+pub(crate) fn to_nanos(duration: Duration) -> i128 {
+    duration.num_seconds() as i128 * NANOS_PER_SEC + duration.subsec_nanos() as i128
+}
+
+pub(crate) fn from_nanos(nanos: i128) -> Option<Duration> {
+    let secs = Duration::try_seconds(i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?)?;
+    Some(secs + Duration::nanoseconds(nanos.rem_euclid(NANOS_PER_SEC) as i64))
+}
+
+/// An iterator over evenly spaced instants: `start`, `start + every`, `start + 2 * every`, and so
+/// on.  Each tick is computed directly from `start` rather than by adding `every` to the previous
+/// tick, so there is no accumulated drift no matter how long the iterator runs.  Created by
+/// `ticks`.
+#[derive(Clone, Debug)]
+pub struct Ticks<Tz: TimeZone> {
+    start: DateTime<Tz>,
+    every: Duration,
+    next: i64,
+}
+
+impl<Tz: TimeZone> Ticks<Tz> {
+    /// Returns the `k`th tick (`start` being tick 0), or `None` if it is out of range.
+    pub fn nth_tick(&self, k: i64) -> Option<DateTime<Tz>> {
+        let offset = from_nanos(to_nanos(self.every).checked_mul(k as i128)?)?;
+        self.start.clone().checked_add_signed(offset)
+    }
+
+    /// Returns the first tick strictly after `now` (or `start` if `now` is before it), regardless
+    /// of how far this iterator has advanced.  Returns `None` if that tick is out of range.
+    pub fn next_tick_after<Tz2: TimeZone>(&self, now: &DateTime<Tz2>) -> Option<DateTime<Tz>> {
+        let elapsed = to_nanos(now.clone().signed_duration_since(self.start.clone()));
+        if elapsed < 0 {
+            return Some(self.start.clone());
+        }
+        let k = elapsed.div_euclid(to_nanos(self.every)) + 1;
+        self.nth_tick(i64::try_from(k).ok()?)
+    }
+}
+
+impl<Tz: TimeZone> Iterator for Ticks<Tz> {
+    type Item = DateTime<Tz>;
+
+    fn next(&mut self) -> Option<DateTime<Tz>> {
+        let tick = self.nth_tick(self.next)?;
+        self.next += 1;
+        Some(tick)
+    }
+}
+
+impl<Tz: TimeZone> Schedule for Ticks<Tz> {
+    fn next_after(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_tick_after(dt).map(|tick| tick.with_timezone(&Utc))
+    }
+
+    fn prev_before(&self, dt: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let elapsed = to_nanos(dt.signed_duration_since(self.start.clone()));
+        if elapsed <= 0 {
+            return None;
+        }
+        let k = (elapsed - 1).div_euclid(to_nanos(self.every));
+        self.nth_tick(i64::try_from(k).ok()?).map(|tick| tick.with_timezone(&Utc))
+    }
+}
+
+/// Returns an iterator over the instants `start + k * every` for k = 0, 1, 2, ...  The iterator
+/// ends if a tick would be out of chrono's representable range.
+///
+/// # Panics
+///
+/// Panics if `every` is not positive.
+pub fn ticks<Tz: TimeZone>(start: DateTime<Tz>, every: Duration) -> Ticks<Tz> {
+    assert!(every > Duration::zero(), "Tick interval must be positive");
+    Ticks { start, every, next: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_ticks() {
+        let ticks: Vec<_> = ticks(at("2018-03-15T12:00:00Z"), Duration::minutes(20))
+            .take(4)
+            .collect();
+        assert_eq!(ticks, vec![
+            at("2018-03-15T12:00:00Z"),
+            at("2018-03-15T12:20:00Z"),
+            at("2018-03-15T12:40:00Z"),
+            at("2018-03-15T13:00:00Z"),
+        ]);
+    }
+
+    #[test]
+    fn test_nth_tick() {
+        let ticks = ticks(at("2018-03-15T12:00:00Z"), Duration::milliseconds(1500));
+        assert_eq!(ticks.nth_tick(1_000_000), Some(at("2018-04-01T20:40:00Z")));
+        assert_eq!(ticks.nth_tick(-2), Some(at("2018-03-15T11:59:57Z")));
+        assert_eq!(ticks.nth_tick(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_next_tick_after() {
+        let ticks = ticks(at("2018-03-15T12:00:00Z"), Duration::minutes(15));
+        let next = |now| ticks.next_tick_after(&at(now)).unwrap();
+        assert_eq!(next("2018-03-15T12:07:00Z"), at("2018-03-15T12:15:00Z"));
+        assert_eq!(next("2018-03-15T12:15:00Z"), at("2018-03-15T12:30:00Z"));
+        // A late wake-up still lands back on the original grid.
+        assert_eq!(next("2018-03-16T12:01:13Z"), at("2018-03-16T12:15:00Z"));
+        assert_eq!(next("2018-03-14T00:00:00Z"), at("2018-03-15T12:00:00Z"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ticks_zero_interval() {
+        ticks(at("2018-03-15T12:00:00Z"), Duration::zero());
+    }
+}
@@ -0,0 +1,105 @@
+extern crate chrono;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use civil::{resolve_local, DstPolicy};
+use std::ops::Range;
+
+/// A daily range of times, such as 09:00 to 17:00 or 22:00 to 06:00.  The start is included and
+/// the end is not.  If the end is not after the start, the range runs past midnight into the next
+/// day; equal start and end times cover the whole day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeOfDayRange {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeOfDayRange {
+    pub fn new(start: NaiveTime, end: NaiveTime) -> TimeOfDayRange {
+        TimeOfDayRange { start, end }
+    }
+
+    /// Returns true if the range runs past midnight.
+    pub fn crosses_midnight(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Returns true if `time` is in the range.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.crosses_midnight() {
+            time >= self.start || time < self.end
+        } else {
+            time >= self.start && time < self.end
+        }
+    }
+
+    /// Returns the parts of `date` (in `tz`) covered by the range, in order: one interval for a
+    /// range within the day, or up to two for a range which crosses midnight (the end of the
+    /// previous night's range, and the start of tonight's).  Times which fall in a DST gap are
+    /// moved forward by the length of the gap.  Returns `None` if a time is out of range.
+    pub fn on_date<Tz: TimeZone>(&self, date: NaiveDate,
+                                 tz: &Tz) -> Option<Vec<Range<DateTime<Tz>>>> {
+        let midnight = NaiveTime::MIN;
+        let bounds = if self.crosses_midnight() {
+            vec![(date, midnight, date, self.end), (date, self.start, date.succ_opt()?, midnight)]
+        } else {
+            vec![(date, self.start, date, self.end)]
+        };
+        let mut intervals = Vec::new();
+        for (start_date, start, end_date, end) in bounds {
+            let from = resolve_local(tz, &start_date.and_time(start), DstPolicy::ShiftForward)?;
+            let to = resolve_local(tz, &end_date.and_time(end), DstPolicy::ShiftForward)?;
+            if from < to {
+                intervals.push(from..to);
+            }
+        }
+        Some(intervals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_contains() {
+        let day = TimeOfDayRange::new(time(9, 0), time(17, 0));
+        assert!(!day.crosses_midnight());
+        assert!(day.contains(time(9, 0)));
+        assert!(!day.contains(time(17, 0)));
+        assert!(!day.contains(time(23, 0)));
+
+        let night = TimeOfDayRange::new(time(22, 0), time(6, 0));
+        assert!(night.crosses_midnight());
+        assert!(night.contains(time(22, 0)));
+        assert!(night.contains(time(0, 0)));
+        assert!(night.contains(time(5, 59)));
+        assert!(!night.contains(time(6, 0)));
+        assert!(!night.contains(time(12, 0)));
+
+        assert!(TimeOfDayRange::new(time(4, 0), time(4, 0)).contains(time(3, 0)));
+    }
+
+    #[test]
+    fn test_on_date() {
+        let date = NaiveDate::from_ymd_opt(2018, 3, 15).unwrap();
+        let day = TimeOfDayRange::new(time(9, 0), time(17, 0));
+        assert_eq!(day.on_date(date, &Utc),
+                   Some(vec![at("2018-03-15T09:00:00Z")..at("2018-03-15T17:00:00Z")]));
+        let night = TimeOfDayRange::new(time(22, 0), time(6, 0));
+        assert_eq!(night.on_date(date, &Utc),
+                   Some(vec![at("2018-03-15T00:00:00Z")..at("2018-03-15T06:00:00Z"),
+                             at("2018-03-15T22:00:00Z")..at("2018-03-16T00:00:00Z")]));
+        let evening = TimeOfDayRange::new(time(18, 0), time(0, 0));
+        assert_eq!(evening.on_date(date, &Utc),
+                   Some(vec![at("2018-03-15T18:00:00Z")..at("2018-03-16T00:00:00Z")]));
+    }
+}
@@ -0,0 +1,253 @@
+extern crate chrono;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeZone, Weekday};
+use std::ops::RangeInclusive;
+
+/// Returns the DateTime `n` weeks after `anchor` (before it, if `n` is negative), keeping the
+/// local time of day even across DST transitions.  Returns `None` if the result is out of range
+/// or the local time doesn't exist on the resulting date.
+pub fn weeks_from<Tz: TimeZone>(anchor: &DateTime<Tz>, n: i64) -> Option<DateTime<Tz>> {
+    let days = Days::new(n.unsigned_abs().checked_mul(7)?);
+    if n >= 0 {
+        anchor.clone().checked_add_days(days)
+    } else {
+        anchor.clone().checked_sub_days(days)
+    }
+}
+
+/// Returns the time elapsed from `anchor` to `now` as whole weeks plus remaining days (e.g.
+/// "12 weeks and 3 days"), counting calendar days in the timezone of `anchor`.  Returns `None` if
+/// `now` is before `anchor`.
+pub fn gestational_age<Tz: TimeZone, Tz2: TimeZone>(anchor: &DateTime<Tz>,
+                                                    now: &DateTime<Tz2>) -> Option<(u32, u32)> {
+    let days = now.with_timezone(&anchor.timezone()).date_naive()
+        .signed_duration_since(anchor.date_naive())
+        .num_days();
+    if days < 0 {
+        return None;
+    }
+    Some(((days / 7) as u32, (days % 7) as u32))
+}
+
+/// A labeled week of a week-year, as returned by `budget_weeks`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BudgetWeek {
+    /// The week's label, e.g. `2018-W01`.
+    pub label: String,
+    /// The week number, from 1 to 52 (or 53).
+    pub week: u32,
+    /// The seven days of the week.
+    pub dates: RangeInclusive<NaiveDate>,
+}
+
+/// Returns the first day of week 1 of `week_year`, for weeks starting on `week_start`.  As in
+/// ISO 8601, week 1 is the first week with at least four days in the year, which is the week
+/// containing January 4th.
+fn first_week_start(week_year: i32, week_start: Weekday) -> Option<NaiveDate> {
+    let jan_4 = NaiveDate::from_ymd_opt(week_year, 1, 4)?;
+    let back = (jan_4.weekday().num_days_from_monday() + 7
+        - week_start.num_days_from_monday()) % 7;
+    jan_4.checked_sub_days(Days::new(back as u64))
+}
+
+/// Returns every week (W01 to W52 or W53) of `week_year`, for weeks starting on `week_start`,
+/// following the ISO 8601 rule that week 1 is the week containing January 4th.  With
+/// `Weekday::Mon` this yields exactly the ISO weeks.  Returns `None` if the week-year is out of
+/// range.
+pub fn budget_weeks(week_year: i32, week_start: Weekday) -> Option<Vec<BudgetWeek>> {
+    let start = first_week_start(week_year, week_start)?;
+    let next_start = first_week_start(week_year + 1, week_start)?;
+    let count = (next_start - start).num_days() / 7;
+    (0..count).map(|i| {
+        let first = start.checked_add_days(Days::new(i as u64 * 7))?;
+        let week = i as u32 + 1;
+        Some(BudgetWeek {
+            label: format!("{}-W{:02}", week_year, week),
+            week,
+            dates: first..=first.checked_add_days(Days::new(6))?,
+        })
+    }).collect()
+}
+
+/// Returns the `nth` (from 1) `weekday` of `year`, e.g. the 2nd Sunday of the year, or counting
+/// from the end of the year if `nth` is negative, so -1 is the last.  Returns `None` if `nth` is
+/// 0, the year doesn't have that many of the weekday, or the year is out of range.
+pub fn nth_weekday_of_year(year: i32, weekday: Weekday, nth: i32) -> Option<NaiveDate> {
+    let date = if nth > 0 {
+        let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let offset = days_since(weekday, first.weekday()) as u64;
+        first.checked_add_days(Days::new(offset + (nth as u64 - 1) * 7))?
+    } else if nth < 0 {
+        let last = NaiveDate::from_ymd_opt(year, 12, 31)?;
+        let offset = days_since(last.weekday(), weekday) as u64;
+        last.checked_sub_days(Days::new(offset + (nth.unsigned_abs() as u64 - 1) * 7))?
+    } else {
+        return None;
+    };
+    if date.year() == year { Some(date) } else { None }
+}
+
+/// How dates are numbered into weeks of the month by `week_of_month`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeekOfMonthConvention {
+    /// Weeks start on the given weekday, and week 1 is the (possibly partial) week containing the
+    /// 1st of the month.  Every date belongs to a week of its own month.
+    ContainsFirst(Weekday),
+    /// Weeks start on the given weekday, and week 1 is the first full week of the month.  Days
+    /// before it belong to the last week of the previous month, and a week running past the end of
+    /// the month belongs to the month it starts in.
+    FirstFullWeek(Weekday),
+    /// As in ISO 8601 week numbering: weeks start on Monday, and each week belongs to the month
+    /// containing its Thursday (so week 1 is the first week with at least four days in the
+    /// month).  Dates at the start or end of a month can belong to a week of the previous or
+    /// next month.
+    Iso,
+}
+
+/// A week of a month, as returned by `week_of_month`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonthWeek {
+    pub year: i32,
+    pub month: u32,
+    /// The week number, from 1.
+    pub week: u32,
+}
+
+/// Returns the week of the month which `date` belongs to under `convention`.  The month is not
+/// always the month of `date` (see `WeekOfMonthConvention`).  Returns `None` if the week's dates
+/// are out of range.
+pub fn week_of_month(date: NaiveDate, convention: WeekOfMonthConvention) -> Option<MonthWeek> {
+    let (week_start, anchor_day) = match convention {
+        WeekOfMonthConvention::ContainsFirst(week_start) => {
+            let first = date.with_day(1)?;
+            let offset = days_since(first.weekday(), week_start);
+            let week = (date.day0() + offset) / 7 + 1;
+            return Some(MonthWeek { year: date.year(), month: date.month(), week });
+        },
+        WeekOfMonthConvention::FirstFullWeek(week_start) => (week_start, 0),
+        WeekOfMonthConvention::Iso => (Weekday::Mon, 3),
+    };
+    // Each week belongs to the month containing its day number `anchor_day` (counting from 0),
+    // and the first such day in a month is in its first seven days.
+    let start = date.checked_sub_days(Days::new(days_since(date.weekday(), week_start) as u64))?;
+    let anchor = start.checked_add_days(Days::new(anchor_day))?;
+    let week = anchor.day0() / 7 + 1;
+    Some(MonthWeek { year: anchor.year(), month: anchor.month(), week })
+}
+
+/// Returns the number of days from the most recent `week_start` to a day which is a `weekday`.
+fn days_since(weekday: Weekday, week_start: Weekday) -> u32 {
+    (weekday.num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_weeks_from() {
+        let anchor = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        let expected = DateTime::parse_from_rfc3339("2018-12-20T12:00:00+09:00").unwrap();
+        assert_eq!(weeks_from(&anchor, 40), Some(expected));
+        let expected = DateTime::parse_from_rfc3339("2018-03-01T12:00:00+09:00").unwrap();
+        assert_eq!(weeks_from(&anchor, -2), Some(expected));
+        assert_eq!(weeks_from(&anchor, 0), Some(anchor));
+        assert_eq!(weeks_from(&anchor, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_gestational_age() {
+        let anchor = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        let now = DateTime::parse_from_rfc3339("2018-06-10T08:00:00+09:00").unwrap();
+        assert_eq!(gestational_age(&anchor, &now), Some((12, 3)));
+        assert_eq!(gestational_age(&anchor, &anchor), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_gestational_age_uses_anchor_timezone() {
+        // 2018-03-21T20:00:00Z is already March 22nd in the anchor's timezone.
+        let anchor = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        let now = DateTime::parse_from_rfc3339("2018-03-21T20:00:00Z").unwrap();
+        assert_eq!(gestational_age(&anchor, &now), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_gestational_age_before_anchor() {
+        let anchor = DateTime::parse_from_rfc3339("2018-03-15T12:00:00+09:00").unwrap();
+        let now = DateTime::parse_from_rfc3339("2018-03-14T12:00:00+09:00").unwrap();
+        assert_eq!(gestational_age(&anchor, &now), None);
+    }
+
+    #[test]
+    fn test_budget_weeks_iso() {
+        let weeks = budget_weeks(2020, Weekday::Mon).unwrap();
+        assert_eq!(weeks.len(), 53);
+        assert_eq!(weeks[0].label, "2020-W01");
+        assert_eq!(weeks[0].dates, date(2019, 12, 30)..=date(2020, 1, 5));
+        assert_eq!(weeks[52].label, "2020-W53");
+        assert_eq!(weeks[52].dates, date(2020, 12, 28)..=date(2021, 1, 3));
+        for week in &weeks {
+            assert_eq!(week.dates.start().iso_week().week(), week.week);
+        }
+        assert_eq!(budget_weeks(2018, Weekday::Mon).unwrap().len(), 52);
+    }
+
+    #[test]
+    fn test_budget_weeks_sunday_start() {
+        let weeks = budget_weeks(2018, Weekday::Sun).unwrap();
+        assert_eq!(weeks.len(), 52);
+        assert_eq!(weeks[0].dates, date(2017, 12, 31)..=date(2018, 1, 6));
+        assert_eq!(weeks[51].label, "2018-W52");
+        assert_eq!(weeks[51].dates, date(2018, 12, 23)..=date(2018, 12, 29));
+    }
+
+    #[test]
+    fn test_week_of_month_contains_first() {
+        // March 1st, 2018 was a Thursday.
+        let convention = WeekOfMonthConvention::ContainsFirst(Weekday::Mon);
+        let week = |d| week_of_month(date(2018, 3, d), convention).unwrap().week;
+        assert_eq!((week(1), week(4), week(5), week(31)), (1, 1, 2, 5));
+        let convention = WeekOfMonthConvention::ContainsFirst(Weekday::Sun);
+        let week = |d| week_of_month(date(2018, 3, d), convention).unwrap().week;
+        assert_eq!((week(3), week(4), week(31)), (1, 2, 5));
+    }
+
+    #[test]
+    fn test_week_of_month_first_full_week() {
+        let convention = WeekOfMonthConvention::FirstFullWeek(Weekday::Mon);
+        let week = |m, d| week_of_month(date(2018, m, d), convention).unwrap();
+        assert_eq!(week(3, 5), MonthWeek { year: 2018, month: 3, week: 1 });
+        assert_eq!(week(3, 4), MonthWeek { year: 2018, month: 2, week: 4 });
+        assert_eq!(week(4, 1), MonthWeek { year: 2018, month: 3, week: 4 });
+        assert_eq!(week(4, 2), MonthWeek { year: 2018, month: 4, week: 1 });
+        assert_eq!(week(1, 1), MonthWeek { year: 2018, month: 1, week: 1 });
+        assert_eq!(week(12, 31), MonthWeek { year: 2018, month: 12, week: 5 });
+    }
+
+    #[test]
+    fn test_week_of_month_iso() {
+        let week = |y, m, d| week_of_month(date(y, m, d), WeekOfMonthConvention::Iso).unwrap();
+        // March 1st, 2018 was a Thursday, so its week is week 1 of March.
+        assert_eq!(week(2018, 2, 26), MonthWeek { year: 2018, month: 3, week: 1 });
+        assert_eq!(week(2018, 3, 5), MonthWeek { year: 2018, month: 3, week: 2 });
+        // April 1st, 2018 was a Sunday, so it belongs to the last week of March.
+        assert_eq!(week(2018, 4, 1), MonthWeek { year: 2018, month: 3, week: 5 });
+        assert_eq!(week(2018, 12, 31), MonthWeek { year: 2019, month: 1, week: 1 });
+    }
+
+    #[test]
+    fn test_nth_weekday_of_year() {
+        // January 1st, 2018 was a Monday and December 31st, 2018 a Monday.
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Sun, 2), Some(date(2018, 1, 14)));
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Mon, 1), Some(date(2018, 1, 1)));
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Mon, 53), Some(date(2018, 12, 31)));
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Tue, 53), None);
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Fri, -1), Some(date(2018, 12, 28)));
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Mon, -53), Some(date(2018, 1, 1)));
+        assert_eq!(nth_weekday_of_year(2018, Weekday::Mon, 0), None);
+    }
+}
@@ -0,0 +1,194 @@
+extern crate chrono;
+
+use chrono::{DateTime, Duration, TimeZone};
+use period_key::{from_period_key, period_key, Unit};
+use std::iter::Peekable;
+use std::ops::Range;
+
+/// Defines how a timeline is divided into consecutive, non-overlapping windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Window {
+    /// Calendar-based windows (days, weeks, months or years) in the local time of a timezone.
+    /// Windows start at local midnight, so they may be shorter or longer than 24 hours per day
+    /// across DST transitions.
+    Calendar(Unit),
+    /// Fixed-length windows aligned to the Unix epoch (e.g. 15-minute windows start at :00,
+    /// :15, :30 and :45 UTC).  The duration must be positive and shorter than about 292 years.
+    Fixed(Duration),
+}
+
+fn fixed_bounds<Tz: TimeZone>(dt: &DateTime<Tz>, length: Duration) -> Option<Range<DateTime<Tz>>> {
+    let length = length.num_nanoseconds().filter(|&n| n > 0)
+        .expect("Window::Fixed duration must be positive and shorter than 292 years") as i128;
+    let instant = dt.timestamp() as i128 * 1_000_000_000 + dt.timestamp_subsec_nanos() as i128;
+    let start = instant - instant.rem_euclid(length);
+    let to_dt = |nanos: i128| {
+        DateTime::from_timestamp(nanos.div_euclid(1_000_000_000) as i64,
+                                 nanos.rem_euclid(1_000_000_000) as u32)
+            .map(|utc| utc.with_timezone(&dt.timezone()))
+    };
+    Some(to_dt(start)?..to_dt(start + length)?)
+}
+
+/// Returns the bounds of the window containing `dt`, as a half-open range expressed in `tz`.
+/// Calendar windows are taken in the local time of `tz`.
+///
+/// Returns `None` if either bound falls outside chrono's representable range.
+///
+/// # Panics
+///
+/// Panics if `window` is `Window::Fixed` with a duration that is not positive or is longer than
+/// about 292 years.
+pub fn window_bounds<Tz: TimeZone, Tz2: TimeZone>(dt: &DateTime<Tz>, window: Window,
+                                                  tz: &Tz2) -> Option<Range<DateTime<Tz2>>> {
+    match window {
+        Window::Calendar(unit) => {
+            let key = period_key(dt, unit, tz);
+            // A window whose local midnight falls in a DST gap starts once the clocks have
+            // jumped forward.
+            Some(from_period_key(key, unit, tz)?..from_period_key(key.checked_add(1)?, unit, tz)?)
+        },
+        Window::Fixed(length) => fixed_bounds(&dt.with_timezone(tz), length),
+    }
+}
+
+/// An iterator which groups a sorted stream of items into consecutive windows.  Created by
+/// `windows_by` and `windows_by_key`.
+pub struct Windows<I, F, Tz> where I: Iterator, Tz: TimeZone {
+    iter: Peekable<I>,
+    key: F,
+    window: Window,
+    tz: Tz,
+}
+
+impl<I, F, Tz> Iterator for Windows<I, F, Tz>
+    where I: Iterator, F: FnMut(&I::Item) -> DateTime<Tz>, Tz: TimeZone {
+    type Item = (Range<DateTime<Tz>>, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.iter.next()?;
+        let first_dt = (self.key)(&first);
+        let bounds = window_bounds(&first_dt, self.window, &self.tz)
+            .unwrap_or_else(|| first_dt.clone()..first_dt.clone());
+
+        let mut items = vec![first];
+        loop {
+            let in_window = match self.iter.peek() {
+                Some(item) => (self.key)(item) < bounds.end,
+                None => false,
+            };
+            if !in_window {
+                break;
+            }
+            items.extend(self.iter.next());
+        }
+        Some((bounds, items))
+    }
+}
+
+/// Lazily group a stream of items, sorted by the DateTime returned by `key`, into consecutive
+/// windows.  Each call to `next` yields the bounds of one window (in `tz`) and the items which
+/// fall inside it; windows with no items are skipped.  Only the items of the current window are
+/// held in memory at a time.
+///
+/// Items are expected to be sorted; an item which is earlier than the window currently being
+/// filled is placed in that window rather than starting a new one.
+pub fn windows_by_key<I, F, Tz>(iter: I, window: Window, tz: Tz,
+                                key: F) -> Windows<I::IntoIter, F, Tz>
+    where I: IntoIterator, F: FnMut(&I::Item) -> DateTime<Tz>, Tz: TimeZone {
+    Windows { iter: iter.into_iter().peekable(), key, window, tz }
+}
+
+/// Lazily group a sorted stream of DateTimes into consecutive windows, with calendar windows
+/// taken in the local time of `tz`.  See `windows_by_key`.
+pub fn windows_by<I, Tz>(iter: I, window: Window, tz: Tz)
+    -> impl Iterator<Item = (Range<DateTime<Tz>>, Vec<DateTime<Tz>>)>
+    where I: IntoIterator<Item = DateTime<Tz>>, Tz: TimeZone {
+    windows_by_key(iter, window, tz, DateTime::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_window_bounds_calendar() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let bounds = window_bounds(&at("2018-03-31T20:00:00Z"), Window::Calendar(Unit::Month),
+                                   &tokyo).unwrap();
+        assert_eq!(bounds.start, at("2018-03-31T15:00:00Z"));
+        assert_eq!(bounds.end, at("2018-04-30T15:00:00Z"));
+    }
+
+    #[test]
+    fn test_window_bounds_midnight_gap() {
+        use test_tz::MidnightGap;
+        // November 4th, 2018 starts at 01:00 local time (03:00 UTC) and lasts 23 hours.
+        let bounds = window_bounds(&at("2018-11-04T12:00:00Z"), Window::Calendar(Unit::Day),
+                                   &MidnightGap).unwrap();
+        assert_eq!(bounds.start, at("2018-11-04T03:00:00Z"));
+        assert_eq!(bounds.end, at("2018-11-05T02:00:00Z"));
+    }
+
+    #[test]
+    fn test_window_bounds_fixed() {
+        let window = Window::Fixed(Duration::minutes(15));
+        let bounds = window_bounds(&at("2018-03-15T12:34:56Z"), window, &Utc).unwrap();
+        assert_eq!(bounds, at("2018-03-15T12:30:00Z")..at("2018-03-15T12:45:00Z"));
+
+        let window = Window::Fixed(Duration::hours(1));
+        let bounds = window_bounds(&at("1969-12-31T23:59:59Z"), window, &Utc).unwrap();
+        assert_eq!(bounds, at("1969-12-31T23:00:00Z")..at("1970-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_window_bounds_fixed_zero() {
+        window_bounds(&at("2018-03-15T12:34:56Z"), Window::Fixed(Duration::zero()), &Utc);
+    }
+
+    #[test]
+    fn test_windows_by_fixed() {
+        let stamps = vec![
+            at("2018-03-15T12:01:00Z"),
+            at("2018-03-15T12:14:59Z"),
+            at("2018-03-15T12:15:00Z"),
+            at("2018-03-15T13:20:00Z"),
+        ];
+        let windows: Vec<_> = windows_by(stamps, Window::Fixed(Duration::minutes(15)), Utc)
+            .collect();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].0, at("2018-03-15T12:00:00Z")..at("2018-03-15T12:15:00Z"));
+        assert_eq!(windows[0].1.len(), 2);
+        assert_eq!(windows[1].0, at("2018-03-15T12:15:00Z")..at("2018-03-15T12:30:00Z"));
+        assert_eq!(windows[1].1, vec![at("2018-03-15T12:15:00Z")]);
+        assert_eq!(windows[2].0, at("2018-03-15T13:15:00Z")..at("2018-03-15T13:30:00Z"));
+    }
+
+    #[test]
+    fn test_windows_by_key_calendar() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let records = vec![
+            (at("2018-03-14T14:00:00Z"), "a"),
+            (at("2018-03-14T16:00:00Z"), "b"),
+            (at("2018-03-15T10:00:00Z"), "c"),
+        ];
+        let windows: Vec<_> = windows_by_key(records, Window::Calendar(Unit::Day), tokyo,
+                                             |r| r.0.with_timezone(&tokyo)).collect();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].1.iter().map(|r| r.1).collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(windows[1].0.start, at("2018-03-14T15:00:00Z"));
+        assert_eq!(windows[1].1.iter().map(|r| r.1).collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_windows_by_empty() {
+        let stamps: Vec<DateTime<Utc>> = Vec::new();
+        assert_eq!(windows_by(stamps, Window::Calendar(Unit::Day), Utc).count(), 0);
+    }
+}
@@ -1,46 +1,283 @@
 extern crate chrono;
 
-use chrono::{DateTime, Datelike, TimeZone, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use civil::{resolve_local, DstPolicy};
+use month_calc::{add_months_with_datelike, OverflowPolicy};
 use std::cmp;
 
 /// This trait defines functions which allow for year calculations between two dates.  As
 /// the standard DateTime, Date, and Duration types in chrono are unable to do this (due to
 /// complications with leap-years, etc.), a utility function must be added to calculate the
 /// years between two DateTimes separately.
-pub trait YearCalculations {
-    /// Returns the number of years between Self and another DateTime as an integer.
-    fn years_since<Tz2: TimeZone>(&self, b: &DateTime<Tz2>) -> i32;
+///
+/// DateTimes can be compared with DateTimes in any timezone (as UTC dates), NaiveDates and
+/// NaiveDateTimes with values of the same type, chrono's deprecated Dates with other Dates (by
+/// their local dates), and `(year, month)` pairs with other pairs.
+///
+/// Note that recent versions of chrono define inherent `DateTime::years_since` and
+/// `NaiveDate::years_since` methods with a different signature, which take precedence over this
+/// trait's method in method-call syntax.  Use the fully-qualified form
+/// (`YearCalculations::years_since(&a, &b)`) to call this one.
+pub trait YearCalculations<Rhs = Self> {
+    /// Returns the number of years between Self and another date as an integer.
+    fn years_since(&self, b: &Rhs) -> i32;
+
+    /// Returns the number of years between Self and another date as an integer, with `boundary`
+    /// deciding when a year is completed.  Only DateTimes and NaiveDateTimes have a time of day
+    /// to consider; for other types this is the same as `years_since`.
+    fn years_since_with(&self, b: &Rhs, boundary: YearBoundary) -> i32;
 }
 
-fn cmp_month_day(a_utc: &DateTime<Utc>, b_utc: &DateTime<Utc>) -> i32 {
-    match a_utc.month().cmp(&b_utc.month()) {
+/// This trait defines the number of years between two dates as a fraction, for the same types as
+/// `YearCalculations`.  (It is a separate trait so that adding it didn't break implementors of
+/// `YearCalculations`.)
+pub trait FractionalYears<Rhs = Self> {
+    /// Returns the number of years between Self and another date, with a fractional part for the
+    /// days elapsed since the last whole year, as a proportion of the days until the next one
+    /// (so the fraction accounts for leap years).  `(year, month)` pairs count months instead.
+    fn years_since_exact(&self, b: &Rhs) -> f64;
+}
+
+/// Determines when `YearCalculations::years_since_with` counts a year as completed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum YearBoundary {
+    /// At the start of the anniversary date, as `years_since` does (so 2010-03-15T12:00 to
+    /// 2018-03-15T11:00 is 8 years).
+    Date,
+    /// At the same time of day on the anniversary date (so 2010-03-15T12:00 to 2018-03-15T11:00
+    /// is 7 years).
+    TimeOfDay,
+}
+
+/// Determines where February 29th goes when a number of years is added to it and the resulting
+/// year is not a leap year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LeapDayPolicy {
+    /// Use February 28th.
+    February28,
+    /// Use March 1st.
+    March1,
+}
+
+/// This trait defines year arithmetic.  (It is separate from `YearCalculations` since that trait
+/// is generic over the type compared with, so its methods can't be called on a value without
+/// naming that type.)
+pub trait YearArithmetic {
+    /// Add a positive or negative number of years to self, keeping the month and day of the month
+    /// (and for DateTimes, the local time of day), with `policy` deciding where February 29th goes
+    /// in a year which isn't a leap year.  Panics if the result is out of range.
+    fn add_years(&self, num_years: i32, policy: LeapDayPolicy) -> Self;
+}
+
+fn try_add_years_datelike<T: Datelike>(dt: &T, num_years: i32, policy: LeapDayPolicy) -> Option<T> {
+    let policy = match policy {
+        LeapDayPolicy::February28 => OverflowPolicy::ClampToLastDay,
+        LeapDayPolicy::March1 => OverflowPolicy::RollForwardToNextMonth,
+    };
+    add_months_with_datelike(dt, num_years.checked_mul(12)?, policy).ok()
+}
+
+fn add_years_datelike<T: Datelike>(dt: &T, num_years: i32, policy: LeapDayPolicy) -> T {
+    try_add_years_datelike(dt, num_years, policy)
+        .expect("Value invalid: the resulting date is out of range")
+}
+
+/// Returns an iterator over the yearly anniversaries of `start` (not including `start` itself),
+/// each the same number of years from `start`, with `policy` deciding where anniversaries of
+/// February 29th go in years which aren't leap years.  The local time of day is kept, and moved
+/// forward if it falls in a DST gap.  The iterator ends when the anniversaries go out of range.
+pub fn anniversaries_of<Tz: TimeZone>(start: &DateTime<Tz>,
+                                      policy: LeapDayPolicy) -> impl Iterator<Item = DateTime<Tz>> {
+    let (local, tz) = (start.naive_local(), start.timezone());
+    (1..).map_while(move |num_years| {
+        let anniversary = try_add_years_datelike(&local, num_years, policy)?;
+        resolve_local(&tz, &anniversary, DstPolicy::ShiftForward)
+    })
+}
+
+impl<Tz> YearArithmetic for DateTime<Tz> where Tz: TimeZone {
+    fn add_years(&self, num_years: i32, policy: LeapDayPolicy) -> Self {
+        add_years_datelike(self, num_years, policy)
+    }
+}
+
+impl YearArithmetic for NaiveDate {
+    fn add_years(&self, num_years: i32, policy: LeapDayPolicy) -> Self {
+        add_years_datelike(self, num_years, policy)
+    }
+}
+
+impl YearArithmetic for NaiveDateTime {
+    fn add_years(&self, num_years: i32, policy: LeapDayPolicy) -> Self {
+        add_years_datelike(self, num_years, policy)
+    }
+}
+
+#[allow(deprecated)]
+impl<Tz> YearArithmetic for chrono::Date<Tz> where Tz: TimeZone {
+    fn add_years(&self, num_years: i32, policy: LeapDayPolicy) -> Self {
+        add_years_datelike(self, num_years, policy)
+    }
+}
+
+fn cmp_month_day<A: Datelike, B: Datelike>(a: &A, b: &B) -> i32 {
+    match a.month().cmp(&b.month()) {
         cmp::Ordering::Greater => 0,
         cmp::Ordering::Less => -1,
-        cmp::Ordering::Equal => match a_utc.day().cmp(&b_utc.day()) {
+        cmp::Ordering::Equal => match a.day().cmp(&b.day()) {
             cmp::Ordering::Greater | cmp::Ordering::Equal => 0,
             cmp::Ordering::Less => -1,
         }
     }
 }
 
-impl<Tz> YearCalculations for DateTime<Tz> where Tz: TimeZone {
-    fn years_since<Tz2: TimeZone>(&self, b: &DateTime<Tz2>) -> i32 {
-        let me_utc = self.with_timezone(&Utc);
-        let b_utc = b.with_timezone(&Utc);
+fn years_between<A: Datelike, B: Datelike>(a: &A, b: &B) -> i32 {
+    adjust_years(a.year() - b.year(), cmp_month_day(a, b))
+}
+
+/// Corrects the difference between two years for whether the later date's month and day have
+/// been reached (`month_day` being -1 if not, as returned by `cmp_month_day`).
+fn adjust_years(base_years: i32, month_day: i32) -> i32 {
+    match base_years.cmp(&0) {
+        cmp::Ordering::Equal => 0,
+        cmp::Ordering::Greater => base_years + month_day,
+        cmp::Ordering::Less => base_years - month_day,
+    }
+}
+
+/// Returns the number of completed years between two instants, counting a year as completed at
+/// the same time of day as `b` (and February 29th's anniversaries on March 1st, as
+/// `years_between` does).
+fn years_between_instants(a: &NaiveDateTime, b: &NaiveDateTime) -> i32 {
+    if a < b {
+        return -years_between_instants(b, a);
+    }
+    let years = years_between(a, b);
+    let policy = OverflowPolicy::RollForwardToNextMonth;
+    match add_months_with_datelike(b, years * 12, policy) {
+        Ok(anniversary) if years > 0 && *a < anniversary => years - 1,
+        _ => years,
+    }
+}
+
+/// Returns the number of years between two dates, with the fraction of the partial year.
+fn exact_years_between(a: &NaiveDate, b: &NaiveDate) -> f64 {
+    if a < b {
+        return -exact_years_between(b, a);
+    }
+    let years = years_between(a, b);
+    let anniversary = |years: i32| {
+        add_months_with_datelike(b, years.checked_mul(12)?, OverflowPolicy::RollForwardToNextMonth)
+            .ok()
+    };
+    match (anniversary(years), anniversary(years + 1)) {
+        (Some(last), Some(next)) => {
+            let elapsed = a.signed_duration_since(last).num_days() as f64;
+            years as f64 + elapsed / next.signed_duration_since(last).num_days() as f64
+        },
+        // Only possible within a year of the latest representable date.
+        _ => years as f64,
+    }
+}
+
+impl<Tz, Tz2> YearCalculations<DateTime<Tz2>> for DateTime<Tz> where Tz: TimeZone, Tz2: TimeZone {
+    fn years_since(&self, b: &DateTime<Tz2>) -> i32 {
+        years_between(&self.with_timezone(&Utc), &b.with_timezone(&Utc))
+    }
 
-        let base_years = me_utc.year() - b_utc.year();
+    fn years_since_with(&self, b: &DateTime<Tz2>, boundary: YearBoundary) -> i32 {
+        match boundary {
+            YearBoundary::Date => YearCalculations::years_since(self, b),
+            YearBoundary::TimeOfDay => years_between_instants(&self.naive_utc(), &b.naive_utc()),
+        }
+    }
+}
 
-        match base_years.cmp(&0) {
-            cmp::Ordering::Equal => 0,
-            cmp::Ordering::Greater => base_years + cmp_month_day(&me_utc, &b_utc),
-            cmp::Ordering::Less => base_years - cmp_month_day(&me_utc, &b_utc),
+impl YearCalculations for NaiveDate {
+    fn years_since(&self, b: &NaiveDate) -> i32 {
+        years_between(self, b)
+    }
+
+    fn years_since_with(&self, b: &NaiveDate, boundary: YearBoundary) -> i32 {
+        // A date has no time of day, so its years are always completed at the start of the day.
+        match boundary {
+            YearBoundary::Date | YearBoundary::TimeOfDay => years_between(self, b),
         }
     }
 }
 
+impl YearCalculations for NaiveDateTime {
+    fn years_since(&self, b: &NaiveDateTime) -> i32 {
+        years_between(self, b)
+    }
+
+    fn years_since_with(&self, b: &NaiveDateTime, boundary: YearBoundary) -> i32 {
+        match boundary {
+            YearBoundary::Date => years_between(self, b),
+            YearBoundary::TimeOfDay => years_between_instants(self, b),
+        }
+    }
+}
+
+// `Date` is deprecated in chrono; Dates are compared by their local dates.
+#[allow(deprecated)]
+impl<Tz, Tz2> YearCalculations<chrono::Date<Tz2>> for chrono::Date<Tz>
+    where Tz: TimeZone, Tz2: TimeZone {
+    fn years_since(&self, b: &chrono::Date<Tz2>) -> i32 {
+        years_between(&self.naive_local(), &b.naive_local())
+    }
+
+    fn years_since_with(&self, b: &chrono::Date<Tz2>, boundary: YearBoundary) -> i32 {
+        match boundary {
+            YearBoundary::Date | YearBoundary::TimeOfDay => YearCalculations::years_since(self, b),
+        }
+    }
+}
+
+/// `(year, month)` pairs count a year as completed once the same month is reached, giving the
+/// same results as dates on the same day of the month (including when self is earlier).
+impl YearCalculations for (i32, u32) {
+    fn years_since(&self, b: &(i32, u32)) -> i32 {
+        adjust_years(self.0 - b.0, if self.1 < b.1 { -1 } else { 0 })
+    }
+
+    fn years_since_with(&self, b: &(i32, u32), boundary: YearBoundary) -> i32 {
+        match boundary {
+            YearBoundary::Date | YearBoundary::TimeOfDay => self.years_since(b),
+        }
+    }
+}
+
+impl<Tz, Tz2> FractionalYears<DateTime<Tz2>> for DateTime<Tz> where Tz: TimeZone, Tz2: TimeZone {
+    fn years_since_exact(&self, b: &DateTime<Tz2>) -> f64 {
+        exact_years_between(&self.naive_utc().date(), &b.naive_utc().date())
+    }
+}
+
+impl FractionalYears for NaiveDate {
+    fn years_since_exact(&self, b: &NaiveDate) -> f64 {
+        exact_years_between(self, b)
+    }
+}
+
+#[allow(deprecated)]
+impl<Tz, Tz2> FractionalYears<chrono::Date<Tz2>> for chrono::Date<Tz>
+    where Tz: TimeZone, Tz2: TimeZone {
+    fn years_since_exact(&self, b: &chrono::Date<Tz2>) -> f64 {
+        exact_years_between(&self.naive_local(), &b.naive_local())
+    }
+}
+
+impl FractionalYears for (i32, u32) {
+    fn years_since_exact(&self, b: &(i32, u32)) -> f64 {
+        ((self.0 as i64 - b.0 as i64) * 12 + self.1 as i64 - b.1 as i64) as f64 / 12.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_tz::Cet;
 
     /// Format of fn name = test_years_ymd_xyz where
     /// x = year (b = before, a = after, s = same)
@@ -50,188 +287,305 @@ mod tests {
     fn test_years_ymd_bbb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-01-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 8);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 8);
     }
 
     #[test]
     fn test_years_ymd_bba() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-01-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 8);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 8);
     }
 
     #[test]
     fn test_years_ymd_bbs() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-01-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 8);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 8);
     }
 
     #[test]
     fn test_years_ymd_bab() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-05-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 7);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 7);
     }
 
     #[test]
     fn test_years_ymd_baa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-05-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 7);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 7);
     }
 
     #[test]
     fn test_years_ymd_bas() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-05-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 7);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 7);
     }
 
     #[test]
     fn test_years_ymd_bsb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-03-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 8);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 8);
     }
 
     #[test]
     fn test_years_ymd_bsa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-03-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 7);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 7);
     }
 
     #[test]
     fn test_years_ymd_bss() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2010-03-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 8);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 8);
     }
 
     #[test]
     fn test_years_ymd_abb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-01-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -12);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -12);
     }
 
     #[test]
     fn test_years_ymd_aba() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-01-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -12);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -12);
     }
 
     #[test]
     fn test_years_ymd_abs() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-01-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -12);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -12);
     }
 
     #[test]
     fn test_years_ymd_aab() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-06-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -11);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -11);
     }
 
     #[test]
     fn test_years_ymd_aaa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-06-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -11);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -11);
     }
 
     #[test]
     fn test_years_ymd_aas() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-06-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -11);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -11);
     }
 
     #[test]
     fn test_years_ymd_asb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-03-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -12);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -12);
     }
 
     #[test]
     fn test_years_ymd_asa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-03-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -11);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -11);
     }
 
     #[test]
     fn test_years_ymd_ass() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2030-03-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), -12);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), -12);
     }
 
     #[test]
     fn test_years_ymd_sbb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-01-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_sba() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-01-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_sbs() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-01-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_sab() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-06-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_saa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-06-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_sas() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-06-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_ssb() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-03-11T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_ssa() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-03-21T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
     }
 
     #[test]
     fn test_years_ymd_sss() {
         let test_date1 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
         let test_date2 = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
-        assert_eq!(test_date1.years_since(&test_date2), 0);
+        assert_eq!(YearCalculations::years_since(&test_date1, &test_date2), 0);
+    }
+
+    #[test]
+    fn test_years_naive_date() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(YearCalculations::years_since(&date(2018, 3, 15), &date(2010, 3, 15)), 8);
+        assert_eq!(YearCalculations::years_since(&date(2018, 3, 14), &date(2010, 3, 15)), 7);
+        assert_eq!(YearCalculations::years_since(&date(2018, 3, 15), &date(2030, 3, 21)), -11);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_years_date() {
+        let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().date();
+        assert_eq!(YearCalculations::years_since(&date(2018, 3, 15), &date(2010, 3, 15)), 8);
+        assert_eq!(YearCalculations::years_since(&date(2018, 3, 14), &date(2010, 3, 15)), 7);
+    }
+
+    #[test]
+    fn test_years_year_month() {
+        assert_eq!((2018, 3).years_since(&(2010, 3)), 8);
+        assert_eq!((2018, 3).years_since(&(2010, 5)), 7);
+        assert_eq!((2010, 5).years_since(&(2018, 3)), -8);
+        assert_eq!((2018, 3).years_since(&(2018, 1)), 0);
+        // The same as for NaiveDates on the same day of the month, in either order.
+        let date = |(y, m)| NaiveDate::from_ymd_opt(y, m, 15).unwrap();
+        for a in (2016..2020).flat_map(|y| (1..13).map(move |m| (y, m))) {
+            for b in [(2018, 1), (2018, 3), (2018, 12)] {
+                assert_eq!(a.years_since(&b), YearCalculations::years_since(&date(a), &date(b)));
+                assert_eq!(b.years_since(&a), YearCalculations::years_since(&date(b), &date(a)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_years_since_with_time_of_day() {
+        let a = DateTime::parse_from_rfc3339("2018-03-15T11:00:00Z").unwrap();
+        let b = DateTime::parse_from_rfc3339("2010-03-15T12:00:00Z").unwrap();
+        assert_eq!(a.years_since_with(&b, YearBoundary::Date), 8);
+        assert_eq!(a.years_since_with(&b, YearBoundary::TimeOfDay), 7);
+        assert_eq!(b.years_since_with(&a, YearBoundary::TimeOfDay), -7);
+        let a = DateTime::parse_from_rfc3339("2018-03-15T12:00:00Z").unwrap();
+        assert_eq!(a.years_since_with(&b, YearBoundary::TimeOfDay), 8);
+        // The same instant in another timezone.
+        let tokyo = DateTime::parse_from_rfc3339("2010-03-15T21:00:00+09:00").unwrap();
+        assert_eq!(a.years_since_with(&tokyo, YearBoundary::TimeOfDay), 8);
+    }
+
+    #[test]
+    fn test_years_since_with_naive() {
+        let at = |y, h| NaiveDate::from_ymd_opt(y, 3, 15).unwrap().and_hms_opt(h, 0, 0).unwrap();
+        assert_eq!(YearCalculations::years_since(&at(2018, 11), &at(2010, 12)), 8);
+        assert_eq!(at(2018, 11).years_since_with(&at(2010, 12), YearBoundary::Date), 8);
+        assert_eq!(at(2018, 11).years_since_with(&at(2010, 12), YearBoundary::TimeOfDay), 7);
+        assert_eq!(at(2010, 12).years_since_with(&at(2018, 11), YearBoundary::TimeOfDay), -7);
+        // Dates have no time of day, so both boundaries agree.
+        let (a, b) = (at(2018, 0).date(), at(2010, 0).date());
+        assert_eq!(a.years_since_with(&b, YearBoundary::TimeOfDay), 8);
+    }
+
+    #[test]
+    fn test_years_since_exact() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date(2018, 3, 15).years_since_exact(&date(2010, 3, 15)), 8.0);
+        // 2019-03-15 is 365 days after 2018-03-15, and 2020-03-15 366 days after 2019-03-15.
+        assert_eq!(date(2018, 9, 14).years_since_exact(&date(2010, 3, 15)), 8.0 + 183.0 / 365.0);
+        assert_eq!(date(2019, 9, 14).years_since_exact(&date(2010, 3, 15)), 9.0 + 183.0 / 366.0);
+        assert_eq!(date(2010, 3, 15).years_since_exact(&date(2018, 9, 14)), -8.0 - 183.0 / 365.0);
+        // February 29th's anniversary in 2017 is March 1st.
+        assert_eq!(date(2017, 2, 28).years_since_exact(&date(2016, 2, 29)), 365.0 / 366.0);
+        let a = DateTime::parse_from_rfc3339("2018-09-14T23:00:00Z").unwrap();
+        let b = DateTime::parse_from_rfc3339("2010-03-15T01:00:00Z").unwrap();
+        assert_eq!(a.years_since_exact(&b), 8.0 + 183.0 / 365.0);
+        assert_eq!((2018, 9).years_since_exact(&(2010, 3)), 8.5);
+    }
+
+    #[test]
+    fn test_anniversaries_of() {
+        let start = DateTime::parse_from_rfc3339("2016-02-29T09:00:00+09:00").unwrap();
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap();
+        let anniversaries: Vec<_> = anniversaries_of(&start, LeapDayPolicy::February28)
+            .take(4)
+            .collect();
+        // Each anniversary is counted from the start, so 2020 is back on the 29th.
+        assert_eq!(anniversaries, vec![at("2017-02-28T09:00:00+09:00"),
+                                       at("2018-02-28T09:00:00+09:00"),
+                                       at("2019-02-28T09:00:00+09:00"),
+                                       at("2020-02-29T09:00:00+09:00")]);
+        let renewal = anniversaries_of(&start, LeapDayPolicy::March1).next();
+        assert_eq!(renewal, Some(at("2017-03-01T09:00:00+09:00")));
+        // In a DST gap, the time of day is moved forward.
+        let start = Cet.with_ymd_and_hms(2017, 3, 25, 2, 30, 0).unwrap();
+        let first = anniversaries_of(&start, LeapDayPolicy::February28).next().unwrap();
+        assert_eq!(first.naive_local().to_string(), "2018-03-25 03:30:00");
+        let near_end = NaiveDate::from_ymd_opt(262_140, 1, 1).unwrap().and_time(Default::default());
+        assert_eq!(anniversaries_of(&Utc.from_utc_datetime(&near_end), LeapDayPolicy::March1)
+                       .count(), 2);
+    }
+
+    #[test]
+    fn test_add_years() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let leap_day = date(2016, 2, 29);
+        assert_eq!(leap_day.add_years(1, LeapDayPolicy::February28), date(2017, 2, 28));
+        assert_eq!(leap_day.add_years(1, LeapDayPolicy::March1), date(2017, 3, 1));
+        assert_eq!(leap_day.add_years(4, LeapDayPolicy::March1), date(2020, 2, 29));
+        assert_eq!(leap_day.add_years(-1, LeapDayPolicy::March1), date(2015, 3, 1));
+        assert_eq!(date(2018, 3, 15).add_years(-8, LeapDayPolicy::February28), date(2010, 3, 15));
+        let dt = DateTime::parse_from_rfc3339("2020-02-29T18:30:00+09:00").unwrap();
+        assert_eq!(dt.add_years(3, LeapDayPolicy::March1),
+                   DateTime::parse_from_rfc3339("2023-03-01T18:30:00+09:00").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_add_years_out_of_range() {
+        NaiveDate::MAX.add_years(1, LeapDayPolicy::February28);
     }
 }